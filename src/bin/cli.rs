@@ -0,0 +1,404 @@
+//! Runs the task tree analysis from the command line with a ClickUp personal
+//! API token, or against exported ClickUp JSON files, without the web UI or a
+//! Redis instance.
+
+use chrono::{NaiveDate, Weekday};
+use chrono_tz::Tz;
+use clickup_time_in_status_analyzer::domain::{
+    AnalysisOptions, analyze_task,
+    duration_policy::{BusinessDays, CustomCalendar, WorkingTime},
+    filters::TaskFilter,
+    generate_points_vs_time_spent_analysis,
+    offline::OfflineDataset,
+};
+use clickup_time_in_status_analyzer::services::clickup::{
+    Cassette, ClickUpApi, ClickUpService, GetTaskRequest, TaskTreeLimits,
+};
+use clickup_time_in_status_analyzer::services::workspace_settings::{WorkCalendar, WorkingHours};
+use std::{path::PathBuf, process::ExitCode, sync::Arc};
+
+const USAGE: &str = "Usage: clickup_time_in_status_cli task <TASK_ID> [OPTIONS]
+       clickup_time_in_status_cli offline <PATH> [TASK_ID] [OPTIONS]
+
+The offline command reads tasks and their time in status saved from the
+ClickUp API, from a directory of .json files, a .tar archive or a single .json
+file. Time in status can be saved inline as `time_in_status`, in a file named
+<TASK_ID>.time_in_status.json, or as the bulk time in status response. Without
+a TASK_ID every task that isn't a subtask is analysed.
+
+Task options:
+  --token <TOKEN>                 ClickUp API token, defaults to $CLICK_UP_TOKEN
+  --workspace-id <ID>             Treat TASK_ID as a custom task id in the workspace
+  --record <PATH>                 Save the ClickUp responses to a cassette, tokens redacted
+  --replay <PATH>                 Answer from a recorded cassette without a token or network
+  --max-depth <LEVELS>            Levels of subtasks to fetch, defaults to 10
+  --max-tasks <COUNT>             Tasks to fetch in total, defaults to 1000
+
+Options for both commands:
+  --remove-weekends               Only count Monday to Friday
+  --working-hours <HH:MM-HH:MM>   Only count time inside these hours, Monday to Friday
+  --time-zone <ZONE>              Time zone of the options above, e.g. Europe/Paris, defaults to UTC
+  --working-days <DAYS>           Only count these days, e.g. Sun,Mon,Tue,Wed,Thu
+  --holidays <DATES>              Leave out these YYYY-MM-DD dates
+  --include-tags <NAMES>          Only keep subtasks with one of these tags
+  --exclude-tags <NAMES>          Leave out subtasks with any of these tags
+  --include-priorities <NAMES>    Only keep subtasks with one of these priorities
+  --exclude-priorities <NAMES>    Leave out subtasks with any of these priorities
+  --include-statuses <NAMES>      Only keep subtasks in one of these statuses
+  --exclude-statuses <NAMES>      Leave out subtasks in any of these statuses
+  --format <text|json>            Output format, defaults to text
+
+NAMES are comma separated, e.g. --exclude-tags \"chore,won't do\".";
+
+/// Options that only change how a task is fetched from ClickUp, which the
+/// offline command has no use for.
+const TASK_ONLY_OPTIONS: [&str; 6] = [
+    "--token",
+    "--workspace-id",
+    "--record",
+    "--replay",
+    "--max-depth",
+    "--max-tasks",
+];
+
+enum OutputFormat {
+    Text,
+    Json,
+}
+
+struct TaskArgs {
+    task_id: String,
+    token: String,
+    workspace_id: Option<String>,
+    cassette: Option<Cassette>,
+    limits: TaskTreeLimits,
+    analysis_options: AnalysisOptions,
+    format: OutputFormat,
+}
+
+struct OfflineArgs {
+    path: PathBuf,
+    /// Every root task of the dataset when empty.
+    task_id: Option<String>,
+    analysis_options: AnalysisOptions,
+    format: OutputFormat,
+}
+
+/// The options shared by every command.
+struct Options {
+    token: Option<String>,
+    workspace_id: Option<String>,
+    cassette: Option<Cassette>,
+    limits: TaskTreeLimits,
+    analysis_options: AnalysisOptions,
+    format: OutputFormat,
+}
+
+fn parse_task_args(mut args: impl Iterator<Item = String>) -> Result<TaskArgs, String> {
+    let task_id = args.next().ok_or("Missing task id.")?;
+    let options = parse_options(args, true)?;
+
+    let token = match (options.token, &options.cassette) {
+        (Some(token), _) => token,
+        // Recorded requests don't carry the token, so any will do.
+        (None, Some(cassette)) if cassette.is_replaying() => String::new(),
+        (None, _) => return Err("Missing token, pass --token or set CLICK_UP_TOKEN.".to_string()),
+    };
+
+    Ok(TaskArgs {
+        task_id,
+        token,
+        workspace_id: options.workspace_id,
+        cassette: options.cassette,
+        limits: options.limits,
+        analysis_options: options.analysis_options,
+        format: options.format,
+    })
+}
+
+fn parse_offline_args(args: impl Iterator<Item = String>) -> Result<OfflineArgs, String> {
+    let mut args = args.peekable();
+    let path = PathBuf::from(args.next().ok_or("Missing path.")?);
+    let task_id = args.next_if(|arg| !arg.starts_with("--"));
+    let options = parse_options(args, false)?;
+
+    Ok(OfflineArgs {
+        path,
+        task_id,
+        analysis_options: options.analysis_options,
+        format: options.format,
+    })
+}
+
+/// Parses the options of a command, which only takes [`TASK_ONLY_OPTIONS`]
+/// with `task_options`.
+fn parse_options(
+    mut args: impl Iterator<Item = String>,
+    task_options: bool,
+) -> Result<Options, String> {
+    let mut token = std::env::var("CLICK_UP_TOKEN").ok();
+    let mut workspace_id = None;
+    let mut cassette = None;
+    let mut limits = TaskTreeLimits::default();
+    let mut analysis_options = AnalysisOptions::default();
+    let mut task_filter = TaskFilter::default();
+    let mut format = OutputFormat::Text;
+    let mut remove_weekends = false;
+    let mut working_hours = None;
+    let mut time_zone = "UTC".to_string();
+    let mut working_days = None;
+    let mut holidays = vec![];
+
+    while let Some(arg) = args.next() {
+        if !task_options && TASK_ONLY_OPTIONS.contains(&arg.as_str()) {
+            return Err(format!("{arg} only applies to the task command."));
+        }
+        if arg == "--remove-weekends" {
+            remove_weekends = true;
+            continue;
+        }
+
+        let value = args
+            .next()
+            .ok_or_else(|| format!("Missing value for {arg}."))?;
+        match arg.as_str() {
+            "--token" => token = Some(value),
+            "--workspace-id" => workspace_id = Some(value),
+            "--max-depth" => {
+                limits.max_depth = value
+                    .parse()
+                    .map_err(|_| format!("Invalid maximum depth {value}."))?
+            }
+            "--max-tasks" => {
+                limits.max_tasks = value
+                    .parse()
+                    .map_err(|_| format!("Invalid maximum task count {value}."))?
+            }
+            "--record" => cassette = Some(Cassette::record(value)),
+            "--replay" => {
+                cassette = Some(
+                    Cassette::replay(&value)
+                        .map_err(|e| format!("Failed to load the cassette {value}: {e:?}"))?,
+                )
+            }
+            "--working-hours" => working_hours = Some(value),
+            "--time-zone" => time_zone = value,
+            "--working-days" => {
+                let days: Result<Vec<Weekday>, _> =
+                    value.split(',').map(|day| day.trim().parse()).collect();
+                working_days = Some(days.map_err(|_| format!("Invalid working days {value}."))?);
+            }
+            "--holidays" => {
+                for date in value.split(',') {
+                    holidays.push(
+                        NaiveDate::parse_from_str(date.trim(), "%Y-%m-%d")
+                            .map_err(|_| format!("Invalid holiday {date}."))?,
+                    );
+                }
+            }
+            "--include-tags" => task_filter.include_tags = TaskFilter::parse_list(&value),
+            "--exclude-tags" => task_filter.exclude_tags = TaskFilter::parse_list(&value),
+            "--include-priorities" => {
+                task_filter.include_priorities = TaskFilter::parse_list(&value)
+            }
+            "--exclude-priorities" => {
+                task_filter.exclude_priorities = TaskFilter::parse_list(&value)
+            }
+            "--include-statuses" => task_filter.include_statuses = TaskFilter::parse_list(&value),
+            "--exclude-statuses" => task_filter.exclude_statuses = TaskFilter::parse_list(&value),
+            "--format" => {
+                format = match value.as_str() {
+                    "text" => OutputFormat::Text,
+                    "json" => OutputFormat::Json,
+                    _ => return Err(format!("Unknown format {value}.")),
+                }
+            }
+            _ => return Err(format!("Unknown option {arg}.")),
+        }
+    }
+
+    analysis_options.task_filter = task_filter;
+    let time_zone: Tz = time_zone
+        .parse()
+        .map_err(|_| format!("Unknown time zone {time_zone}."))?;
+    let working_hours = match working_hours {
+        Some(working_hours) => Some(
+            WorkingHours::parse(&working_hours, time_zone.name())
+                .ok_or_else(|| format!("Invalid working hours {working_hours}."))?,
+        ),
+        None => None,
+    };
+    if working_days.is_some() || !holidays.is_empty() {
+        analysis_options.duration_policy = Arc::new(CustomCalendar {
+            calendar: WorkCalendar {
+                working_days: working_days.unwrap_or_else(|| {
+                    vec![
+                        Weekday::Mon,
+                        Weekday::Tue,
+                        Weekday::Wed,
+                        Weekday::Thu,
+                        Weekday::Fri,
+                    ]
+                }),
+                holidays,
+            },
+            working_hours,
+        });
+    } else if let Some(working_hours) = working_hours {
+        analysis_options.duration_policy = Arc::new(WorkingTime { working_hours });
+    } else if remove_weekends {
+        analysis_options.duration_policy = Arc::new(BusinessDays { time_zone });
+    }
+
+    Ok(Options {
+        token,
+        workspace_id,
+        cassette,
+        limits,
+        analysis_options,
+        format,
+    })
+}
+
+async fn run_task(args: TaskArgs) -> Result<String, String> {
+    let mut click_up_service = ClickUpService::new("", "", "");
+    if let Some(cassette) = &args.cassette {
+        click_up_service = click_up_service.with_cassette(cassette.clone());
+    }
+    let task = click_up_service
+        .get_task(
+            &args.token,
+            GetTaskRequest {
+                task_id: args.task_id,
+                workspace_id: args.workspace_id,
+                progress: None,
+                limits: args.limits,
+            },
+        )
+        .await;
+
+    // Saved even when the fetch failed, so the failing response can be looked
+    // at.
+    if let Some(cassette) = args.cassette.filter(|c| !c.is_replaying()) {
+        cassette
+            .save()
+            .await
+            .map_err(|e| format!("Failed to save the cassette: {e:?}"))?;
+    }
+
+    let task = task.map_err(|e| format!("Failed to fetch the task: {e:?}"))?;
+    let task = analyze_task(task, &args.analysis_options);
+
+    match args.format {
+        OutputFormat::Text => Ok(generate_points_vs_time_spent_analysis(&task)),
+        OutputFormat::Json => serde_json::to_string_pretty(&task).map_err(|e| e.to_string()),
+    }
+}
+
+fn run_offline(args: OfflineArgs) -> Result<String, String> {
+    let dataset = OfflineDataset::from_path(&args.path)
+        .map_err(|e| format!("Failed to read the dataset: {e:?}"))?;
+
+    let task_ids = match args.task_id {
+        Some(task_id) => vec![task_id],
+        None => dataset
+            .root_tasks()
+            .into_iter()
+            .filter(|t| t.time_in_status.is_some())
+            .map(|t| t.id.clone())
+            .collect(),
+    };
+    let mut tasks = vec![];
+    for task_id in task_ids {
+        let task = dataset.task_tree(&task_id).ok_or_else(|| {
+            format!("Task {task_id} isn't in the dataset, or was saved without its time in status.")
+        })?;
+        tasks.push(analyze_task(task, &args.analysis_options));
+    }
+    if tasks.is_empty() {
+        return Err("No task in the dataset was saved with its time in status.".to_string());
+    }
+
+    match args.format {
+        OutputFormat::Text => Ok(tasks
+            .iter()
+            .map(generate_points_vs_time_spent_analysis)
+            .collect::<Vec<_>>()
+            .join("\n")),
+        OutputFormat::Json => serde_json::to_string_pretty(&tasks).map_err(|e| e.to_string()),
+    }
+}
+
+#[tokio::main]
+async fn main() -> ExitCode {
+    let mut args = std::env::args().skip(1);
+
+    let result = match args.next().as_deref() {
+        Some("task") => match parse_task_args(args) {
+            Ok(task_args) => run_task(task_args).await,
+            Err(message) => Err(format!("{message}\n\n{USAGE}")),
+        },
+        Some("offline") => match parse_offline_args(args) {
+            Ok(offline_args) => run_offline(offline_args),
+            Err(message) => Err(format!("{message}\n\n{USAGE}")),
+        },
+        Some("-h" | "--help") => Ok(USAGE.to_string()),
+        _ => Err(USAGE.to_string()),
+    };
+
+    match result {
+        Ok(output) => {
+            println!("{output}");
+            ExitCode::SUCCESS
+        }
+        Err(message) => {
+            eprintln!("{message}");
+            ExitCode::FAILURE
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn args(args: &[&str]) -> impl Iterator<Item = String> {
+        args.iter()
+            .map(|arg| arg.to_string())
+            .collect::<Vec<_>>()
+            .into_iter()
+    }
+
+    #[test]
+    fn offline_rejects_task_only_options() {
+        for option in TASK_ONLY_OPTIONS {
+            let result = parse_offline_args(args(&["tasks.tar", option, "1"]));
+
+            assert_eq!(
+                result.err(),
+                Some(format!("{option} only applies to the task command."))
+            );
+        }
+    }
+
+    #[test]
+    fn offline_accepts_filters_and_time_options() {
+        let offline_args = parse_offline_args(args(&[
+            "tasks.tar",
+            "abc",
+            "--exclude-tags",
+            "chore",
+            "--remove-weekends",
+            "--format",
+            "json",
+        ]))
+        .unwrap();
+
+        assert_eq!(offline_args.task_id.as_deref(), Some("abc"));
+        assert_eq!(
+            offline_args.analysis_options.task_filter.exclude_tags,
+            ["chore"]
+        );
+        assert!(matches!(offline_args.format, OutputFormat::Json));
+    }
+}
@@ -0,0 +1,67 @@
+use crate::{
+    components::task_link,
+    domain::aging_wip::{AgingRisk, AgingWip},
+};
+use askama::Template;
+
+pub struct AgingWipRow {
+    pub number: String,
+    pub name: String,
+    /// Unset when the URL isn't one to link to, see [`task_link`].
+    pub url: Option<String>,
+    pub status: String,
+    pub days_in_status: String,
+    pub days_in_progress: String,
+    pub risk: &'static str,
+    pub risk_class: &'static str,
+}
+
+#[derive(Template)]
+#[template(path = "components/aging_wip_report.html")]
+pub struct AgingWipReport {
+    /// How durations were counted, e.g. `working_hours (end: 17:00, ...)`.
+    pub duration_policy: String,
+    pub cycle_time_p50: String,
+    pub cycle_time_p85: String,
+    pub completed_task_count: usize,
+    pub rows: Vec<AgingWipRow>,
+}
+
+impl From<AgingWip> for AgingWipReport {
+    fn from(value: AgingWip) -> Self {
+        let format_days = |days: Option<f32>| match days {
+            Some(days) => format!("{days:.1}"),
+            None => "-".to_string(),
+        };
+
+        Self {
+            duration_policy: value.duration_policy.description(),
+            cycle_time_p50: format_days(value.cycle_time_p50),
+            cycle_time_p85: format_days(value.cycle_time_p85),
+            completed_task_count: value.completed_task_count,
+            rows: value
+                .tasks
+                .into_iter()
+                .map(|t| {
+                    let (risk, risk_class) = match t.risk {
+                        AgingRisk::OnTrack => ("On track", "risk-on-track"),
+                        AgingRisk::Watch => ("Past p50", "risk-watch"),
+                        AgingRisk::AtRisk => ("Past p85", "risk-at-risk"),
+                        AgingRisk::Unknown => ("Unknown", ""),
+                    };
+
+                    AgingWipRow {
+                        number: t.number,
+                        name: t.name,
+                        url: task_link(&t.url),
+                        status: t.status,
+                        days_in_status: format!("{:.1}", t.days_in_status),
+                        days_in_progress: format!("{:.1}", t.days_in_progress),
+                        risk,
+                        risk_class,
+                    }
+                })
+                .collect(),
+        }
+    }
+}
@@ -0,0 +1,45 @@
+use crate::domain::assignees::AssigneeBreakdown;
+use askama::Template;
+
+pub struct AssigneeRow {
+    pub name: String,
+    pub completed_tasks: usize,
+    pub points_completed: String,
+    pub dev_days: String,
+    pub days_per_point: String,
+    pub tasks_in_progress: usize,
+}
+
+#[derive(Template)]
+#[template(path = "components/assignee_breakdown_report.html")]
+pub struct AssigneeBreakdownReport {
+    pub anonymized: bool,
+    /// Path the report is loaded from, ending in `?` or `&` so more query
+    /// parameters can be appended to toggle anonymization.
+    pub path: String,
+    pub rows: Vec<AssigneeRow>,
+}
+
+impl AssigneeBreakdownReport {
+    pub fn new(value: AssigneeBreakdown, path: String) -> Self {
+        Self {
+            anonymized: value.anonymized,
+            path,
+            rows: value
+                .assignees
+                .into_iter()
+                .map(|a| AssigneeRow {
+                    name: a.name,
+                    completed_tasks: a.completed_tasks,
+                    points_completed: format!("{:.1}", a.points_completed),
+                    dev_days: format!("{:.1}", a.dev_days),
+                    days_per_point: match a.days_per_point {
+                        Some(days_per_point) => format!("{days_per_point:.1}"),
+                        None => "-".to_string(),
+                    },
+                    tasks_in_progress: a.tasks_in_progress,
+                })
+                .collect(),
+        }
+    }
+}
@@ -0,0 +1,198 @@
+use crate::{
+    components::charts::{
+        CHART_HEIGHT, CHART_WIDTH, LinearScale, MARGIN_BOTTOM, MARGIN_LEFT, MARGIN_RIGHT,
+        MARGIN_TOP, Tick, format_number, palette_color,
+    },
+    domain::control_chart::ControlChart,
+};
+use askama::Template;
+use chrono::{DateTime, TimeDelta, Utc};
+
+const BAND_LABEL_WIDTH: f32 = 80.0;
+const DATE_TICKS: usize = 6;
+
+pub struct ControlChartDot {
+    pub cx: f32,
+    pub cy: f32,
+    pub title: String,
+    /// Shown next to outliers, empty otherwise.
+    pub label: String,
+}
+
+pub struct ControlChartBand {
+    pub y: f32,
+    pub label: String,
+    pub color: &'static str,
+    pub dash_array: &'static str,
+}
+
+/// Plots each completed task by completion date against its cycle time, with
+/// horizontal lines for the mean and percentiles.
+#[derive(Template)]
+#[template(path = "components/charts/control.svg")]
+pub struct ControlChartView {
+    pub width: f32,
+    pub height: f32,
+    pub plot_left: f32,
+    pub plot_right: f32,
+    pub plot_top: f32,
+    pub plot_bottom: f32,
+    pub x_ticks: Vec<Tick>,
+    pub y_ticks: Vec<Tick>,
+    pub bands: Vec<ControlChartBand>,
+    pub dots: Vec<ControlChartDot>,
+}
+
+impl From<&ControlChart> for ControlChartView {
+    fn from(value: &ControlChart) -> Self {
+        let plot_left = MARGIN_LEFT;
+        let plot_right = CHART_WIDTH - MARGIN_RIGHT - BAND_LABEL_WIDTH;
+        let plot_top = MARGIN_TOP;
+        let plot_bottom = CHART_HEIGHT - MARGIN_BOTTOM;
+
+        // Completion dates are mapped as days since the first completion,
+        // which f32 can hold precisely unlike a timestamp.
+        let first = value.tasks.first().map(|t| t.completed_at);
+        let last = value.tasks.last().map(|t| t.completed_at);
+        let days_since_first = |date: DateTime<Utc>| match first {
+            Some(first) => (date - first).num_minutes() as f32 / 60.0 / 24.0,
+            None => 0.0,
+        };
+        let span = match last {
+            Some(last) => days_since_first(last),
+            None => 0.0,
+        };
+
+        let max_cycle_time = value
+            .tasks
+            .iter()
+            .map(|t| t.cycle_time_days)
+            .fold(0.0, f32::max);
+        let x_scale = LinearScale::new((0.0, span), (plot_left, plot_right));
+        let y_scale = LinearScale::from_zero(max_cycle_time, (plot_bottom, plot_top));
+
+        // Completions all on the same instant would give identical ticks.
+        let date_ticks = if span == 0.0 { 1 } else { DATE_TICKS };
+        let x_ticks = match first {
+            Some(first) => (0..date_ticks)
+                .map(|i| {
+                    let days = span * i as f32 / (date_ticks - 1).max(1) as f32;
+                    let date = first + TimeDelta::minutes((days * 24.0 * 60.0) as i64);
+                    Tick {
+                        position: x_scale.map(days),
+                        label: date.format("%b %d").to_string(),
+                    }
+                })
+                .collect(),
+            None => vec![],
+        };
+
+        let bands = [
+            ("mean", value.mean, palette_color(0), "6 4"),
+            ("p50", value.p50, palette_color(4), "none"),
+            ("p85", value.p85, palette_color(1), "none"),
+            ("p95", value.p95, palette_color(2), "none"),
+        ]
+        .into_iter()
+        .filter_map(|(name, days, color, dash_array)| {
+            let days = days?;
+            Some(ControlChartBand {
+                y: y_scale.map(days),
+                label: format!("{name} {}d", format_number(days)),
+                color,
+                dash_array,
+            })
+        })
+        .collect();
+
+        let dots = value
+            .tasks
+            .iter()
+            .map(|t| ControlChartDot {
+                cx: x_scale.map(days_since_first(t.completed_at)),
+                cy: y_scale.map(t.cycle_time_days),
+                title: format!(
+                    "{} {} - completed {}, cycle time: {} days",
+                    t.number,
+                    t.name,
+                    t.completed_at.format("%Y-%m-%d"),
+                    format_number(t.cycle_time_days)
+                ),
+                label: if t.is_outlier {
+                    t.number.clone()
+                } else {
+                    String::new()
+                },
+            })
+            .collect();
+
+        Self {
+            width: CHART_WIDTH,
+            height: CHART_HEIGHT,
+            plot_left,
+            plot_right,
+            plot_top,
+            plot_bottom,
+            x_ticks,
+            y_ticks: y_scale.ticks(),
+            bands,
+            dots,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::{
+        control_chart::ControlChartTask,
+        duration_policy::{DurationPolicy, FullTime},
+    };
+    use chrono::TimeZone;
+
+    fn chart(completed_at: &[DateTime<Utc>]) -> ControlChart {
+        ControlChart {
+            duration_policy: FullTime.record(),
+            mean: Some(2.0),
+            p50: Some(2.0),
+            p85: Some(2.0),
+            p95: Some(2.0),
+            tasks: completed_at
+                .iter()
+                .enumerate()
+                .map(|(i, completed_at)| ControlChartTask {
+                    id: i.to_string(),
+                    number: i.to_string(),
+                    name: format!("Task {i}"),
+                    url: String::new(),
+                    completed_at: *completed_at,
+                    cycle_time_days: 2.0,
+                    is_outlier: false,
+                })
+                .collect(),
+        }
+    }
+
+    #[test]
+    fn same_instant_completions_get_one_date_tick() {
+        let completed_at = Utc.with_ymd_and_hms(2025, 3, 3, 12, 0, 0).unwrap();
+
+        let view = ControlChartView::from(&chart(&[completed_at, completed_at]));
+
+        assert_eq!(view.x_ticks.len(), 1);
+        assert_eq!(view.x_ticks[0].label, "Mar 03");
+    }
+
+    #[test]
+    fn completions_over_time_get_evenly_spread_date_ticks() {
+        let first = Utc.with_ymd_and_hms(2025, 3, 3, 12, 0, 0).unwrap();
+
+        let view = ControlChartView::from(&chart(&[first, first + TimeDelta::days(10)]));
+
+        let labels: Vec<&str> = view.x_ticks.iter().map(|t| t.label.as_str()).collect();
+        assert_eq!(
+            labels,
+            ["Mar 03", "Mar 05", "Mar 07", "Mar 09", "Mar 11", "Mar 13"]
+        );
+    }
+}
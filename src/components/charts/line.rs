@@ -0,0 +1,240 @@
+use crate::{
+    components::charts::{
+        CHART_HEIGHT, CHART_WIDTH, LegendEntry, LinearScale, MARGIN_BOTTOM, MARGIN_LEFT,
+        MARGIN_RIGHT, MARGIN_TOP, Tick, format_number, palette_color,
+    },
+    domain::velocity::{VelocityMetric, VelocityTrend},
+};
+use askama::Template;
+
+const LEGEND_WIDTH: f32 = 150.0;
+const LEGEND_ROW_HEIGHT: f32 = 18.0;
+const MAX_X_TICKS: usize = 8;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum LineStyle {
+    /// Drawn with a marker on every value.
+    Solid,
+    Dashed,
+    Dotted,
+}
+
+pub struct LineSeriesData {
+    /// Series with an empty label are left out of the legend.
+    pub label: String,
+    /// One per x label, `None` leaves a gap.
+    pub values: Vec<Option<f32>>,
+    pub color: &'static str,
+    pub style: LineStyle,
+}
+
+pub struct LineMarker {
+    pub cx: f32,
+    pub cy: f32,
+    pub title: String,
+}
+
+pub struct LineSeries {
+    /// Polyline points for each unbroken run of values.
+    pub segments: Vec<String>,
+    pub color: &'static str,
+    pub dash_array: &'static str,
+    pub markers: Vec<LineMarker>,
+}
+
+/// Draws one or more series of values against evenly spaced labels, e.g. a
+/// value per week.
+#[derive(Template)]
+#[template(path = "components/charts/line.svg")]
+pub struct LineChart {
+    pub width: f32,
+    pub height: f32,
+    pub plot_left: f32,
+    pub plot_right: f32,
+    pub plot_top: f32,
+    pub plot_bottom: f32,
+    pub y_label: String,
+    pub x_ticks: Vec<Tick>,
+    pub y_ticks: Vec<Tick>,
+    pub series: Vec<LineSeries>,
+    pub legend: Vec<LegendEntry>,
+}
+
+impl LineChart {
+    pub fn new(y_label: String, x_labels: &[String], series: Vec<LineSeriesData>) -> Self {
+        let plot_left = MARGIN_LEFT;
+        let plot_right = CHART_WIDTH - MARGIN_RIGHT - LEGEND_WIDTH;
+        let plot_top = MARGIN_TOP;
+        let plot_bottom = CHART_HEIGHT - MARGIN_BOTTOM;
+
+        let max_value = series
+            .iter()
+            .flat_map(|s| s.values.iter().flatten())
+            .fold(0.0f32, |max, v| max.max(*v));
+        let last_index = x_labels.len().saturating_sub(1) as f32;
+        let x_scale = LinearScale::new((0.0, last_index), (plot_left, plot_right));
+        let y_scale = LinearScale::from_zero(max_value, (plot_bottom, plot_top));
+
+        let x_step = x_labels.len().div_ceil(MAX_X_TICKS).max(1);
+        let x_ticks = x_labels
+            .iter()
+            .enumerate()
+            .step_by(x_step)
+            .map(|(i, label)| Tick {
+                position: x_scale.map(i as f32),
+                label: label.clone(),
+            })
+            .collect();
+
+        let legend = series
+            .iter()
+            .filter(|s| !s.label.is_empty())
+            .enumerate()
+            .map(|(i, s)| LegendEntry {
+                x: plot_right + 16.0,
+                y: plot_top + LEGEND_ROW_HEIGHT * i as f32,
+                color: s.color,
+                label: s.label.clone(),
+            })
+            .collect();
+
+        let series = series
+            .into_iter()
+            .map(|s| {
+                let mut segments = vec![];
+                let mut segment = vec![];
+                let mut markers = vec![];
+                for (i, value) in s.values.iter().enumerate() {
+                    let Some(value) = value else {
+                        if !segment.is_empty() {
+                            segments.push(segment.join(" "));
+                            segment.clear();
+                        }
+                        continue;
+                    };
+
+                    let (cx, cy) = (x_scale.map(i as f32), y_scale.map(*value));
+                    segment.push(format!("{cx:.1},{cy:.1}"));
+                    if s.style == LineStyle::Solid {
+                        markers.push(LineMarker {
+                            cx,
+                            cy,
+                            title: format!(
+                                "{}: {}",
+                                x_labels.get(i).map(String::as_str).unwrap_or_default(),
+                                format_number(*value)
+                            ),
+                        });
+                    }
+                }
+                if !segment.is_empty() {
+                    segments.push(segment.join(" "));
+                }
+
+                LineSeries {
+                    segments,
+                    color: s.color,
+                    dash_array: match s.style {
+                        LineStyle::Solid => "none",
+                        LineStyle::Dashed => "6 4",
+                        LineStyle::Dotted => "2 3",
+                    },
+                    markers,
+                }
+            })
+            .collect();
+
+        Self {
+            width: CHART_WIDTH,
+            height: CHART_HEIGHT,
+            plot_left,
+            plot_right,
+            plot_top,
+            plot_bottom,
+            y_label,
+            x_ticks,
+            y_ticks: y_scale.ticks(),
+            series,
+            legend,
+        }
+    }
+
+    /// Charts completed work per period with its rolling average and a band
+    /// of one standard deviation either side of it.
+    pub fn from_velocity_trend(value: &VelocityTrend, metric: VelocityMetric) -> Self {
+        let (y_label, completed, averages, standard_deviations): (_, Vec<f32>, Vec<_>, Vec<_>) =
+            match metric {
+                VelocityMetric::Points => (
+                    "Completed Points",
+                    value.points.iter().map(|p| p.completed_points).collect(),
+                    value.points.iter().map(|p| p.points_average).collect(),
+                    value
+                        .points
+                        .iter()
+                        .map(|p| p.points_standard_deviation)
+                        .collect(),
+                ),
+                VelocityMetric::Tasks => (
+                    "Completed Tasks",
+                    value
+                        .points
+                        .iter()
+                        .map(|p| p.completed_tasks as f32)
+                        .collect(),
+                    value.points.iter().map(|p| p.tasks_average).collect(),
+                    value
+                        .points
+                        .iter()
+                        .map(|p| p.tasks_standard_deviation)
+                        .collect(),
+                ),
+            };
+
+        let band = |sign: f32| -> Vec<Option<f32>> {
+            averages
+                .iter()
+                .zip(&standard_deviations)
+                .map(|(average, deviation)| {
+                    Some((average.as_ref()? + sign * deviation.as_ref()?).max(0.0))
+                })
+                .collect()
+        };
+
+        let x_labels: Vec<String> = value
+            .points
+            .iter()
+            .map(|p| p.period.label.clone())
+            .collect();
+
+        Self::new(
+            y_label.to_string(),
+            &x_labels,
+            vec![
+                LineSeriesData {
+                    label: "Completed".to_string(),
+                    values: completed.into_iter().map(Some).collect(),
+                    color: palette_color(0),
+                    style: LineStyle::Solid,
+                },
+                LineSeriesData {
+                    label: format!("{}-period average", value.window),
+                    values: averages.clone(),
+                    color: palette_color(1),
+                    style: LineStyle::Dashed,
+                },
+                LineSeriesData {
+                    label: "±1 std dev".to_string(),
+                    values: band(1.0),
+                    color: palette_color(1),
+                    style: LineStyle::Dotted,
+                },
+                LineSeriesData {
+                    label: String::new(),
+                    values: band(-1.0),
+                    color: palette_color(1),
+                    style: LineStyle::Dotted,
+                },
+            ],
+        )
+    }
+}
@@ -0,0 +1,104 @@
+mod control;
+mod line;
+mod scatter;
+mod stacked_area;
+mod stacked_bar;
+
+pub use control::*;
+pub use line::*;
+pub use scatter::*;
+pub use stacked_area::*;
+pub use stacked_bar::*;
+
+pub const CHART_WIDTH: f32 = 640.0;
+pub const CHART_HEIGHT: f32 = 400.0;
+pub const MARGIN_TOP: f32 = 20.0;
+pub const MARGIN_RIGHT: f32 = 20.0;
+pub const MARGIN_BOTTOM: f32 = 40.0;
+pub const MARGIN_LEFT: f32 = 50.0;
+
+const PALETTE: [&str; 10] = [
+    "#4e79a7", "#f28e2b", "#e15759", "#76b7b2", "#59a14f", "#edc948", "#b07aa1", "#ff9da7",
+    "#9c755f", "#bab0ac",
+];
+
+pub fn palette_color(index: usize) -> &'static str {
+    PALETTE[index % PALETTE.len()]
+}
+
+pub struct Tick {
+    pub position: f32,
+    pub label: String,
+}
+
+/// Maps values from a data domain onto pixel positions.
+#[derive(Debug, Clone, Copy)]
+pub struct LinearScale {
+    domain: (f32, f32),
+    range: (f32, f32),
+}
+
+impl LinearScale {
+    /// Creates a scale from zero to `max`, rounded up to a tick boundary so
+    /// the largest value doesn't touch the edge of the plot.
+    pub fn from_zero(max: f32, range: (f32, f32)) -> Self {
+        let max = if max > 0.0 { max } else { 1.0 };
+        let step = nice_step(max);
+
+        Self {
+            domain: (0.0, (max / step).ceil() * step),
+            range,
+        }
+    }
+
+    pub fn new(domain: (f32, f32), range: (f32, f32)) -> Self {
+        let domain = if domain.0 == domain.1 {
+            (domain.0, domain.0 + 1.0)
+        } else {
+            domain
+        };
+
+        Self { domain, range }
+    }
+
+    pub fn map(&self, value: f32) -> f32 {
+        let ratio = (value - self.domain.0) / (self.domain.1 - self.domain.0);
+        self.range.0 + ratio * (self.range.1 - self.range.0)
+    }
+
+    pub fn ticks(&self) -> Vec<Tick> {
+        let step = nice_step(self.domain.1 - self.domain.0);
+        let mut value = (self.domain.0 / step).ceil() * step;
+        let mut ticks = vec![];
+
+        while value <= self.domain.1 + step / 1000.0 {
+            ticks.push(Tick {
+                position: self.map(value),
+                label: format_number(value),
+            });
+            value += step;
+        }
+
+        ticks
+    }
+}
+
+fn nice_step(span: f32) -> f32 {
+    let raw_step = span / 5.0;
+    let magnitude = 10f32.powf(raw_step.log10().floor());
+
+    match raw_step / magnitude {
+        r if r <= 1.0 => magnitude,
+        r if r <= 2.0 => magnitude * 2.0,
+        r if r <= 5.0 => magnitude * 5.0,
+        _ => magnitude * 10.0,
+    }
+}
+
+pub fn format_number(value: f32) -> String {
+    if value.fract() == 0.0 {
+        format!("{value:.0}")
+    } else {
+        format!("{value:.1}")
+    }
+}
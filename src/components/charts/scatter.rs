@@ -0,0 +1,137 @@
+use crate::{
+    components::charts::{
+        CHART_HEIGHT, CHART_WIDTH, LinearScale, MARGIN_BOTTOM, MARGIN_LEFT, MARGIN_RIGHT,
+        MARGIN_TOP, Tick, format_number,
+    },
+    domain::Task,
+};
+use askama::Template;
+
+pub struct ScatterDot {
+    pub cx: f32,
+    pub cy: f32,
+    pub title: String,
+    pub is_outlier: bool,
+}
+
+/// Plots one dot per task with its points on the x axis and days spent in a
+/// development status on the y axis.
+#[derive(Template)]
+#[template(path = "components/charts/scatter.svg")]
+pub struct ScatterChart {
+    pub width: f32,
+    pub height: f32,
+    pub plot_left: f32,
+    pub plot_right: f32,
+    pub plot_top: f32,
+    pub plot_bottom: f32,
+    pub x_ticks: Vec<Tick>,
+    pub y_ticks: Vec<Tick>,
+    pub dots: Vec<ScatterDot>,
+}
+
+impl ScatterChart {
+    pub fn from_tasks(tasks: &[Task]) -> Self {
+        let tasks: Vec<&Task> = tasks.iter().flat_map(|t| t.flatten()).collect();
+
+        let plot_left = MARGIN_LEFT;
+        let plot_right = CHART_WIDTH - MARGIN_RIGHT;
+        let plot_top = MARGIN_TOP;
+        let plot_bottom = CHART_HEIGHT - MARGIN_BOTTOM;
+
+        let max_points = tasks.iter().map(|t| t.points).fold(0.0, f32::max);
+        let max_days = tasks
+            .iter()
+            .map(|t| t.time_in_dev_status as f32)
+            .fold(0.0, f32::max);
+
+        let x_scale = LinearScale::from_zero(max_points, (plot_left, plot_right));
+        let y_scale = LinearScale::from_zero(max_days, (plot_bottom, plot_top));
+
+        let dots = tasks
+            .iter()
+            .map(|t| ScatterDot {
+                cx: x_scale.map(t.points),
+                cy: y_scale.map(t.time_in_dev_status as f32),
+                title: format!(
+                    "{} {} - points: {}, days: {}",
+                    t.number,
+                    t.name,
+                    format_number(t.points),
+                    t.time_in_dev_status
+                ),
+                is_outlier: t.outlier.is_some(),
+            })
+            .collect();
+
+        Self {
+            width: CHART_WIDTH,
+            height: CHART_HEIGHT,
+            plot_left,
+            plot_right,
+            plot_top,
+            plot_bottom,
+            x_ticks: x_scale.ticks(),
+            y_ticks: y_scale.ticks(),
+            dots,
+        }
+    }
+}
+
+impl From<&Task> for ScatterChart {
+    fn from(value: &Task) -> Self {
+        Self::from_tasks(std::slice::from_ref(value))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::testing::{done_task, parse, task_json};
+    use serde_json::json;
+
+    #[test]
+    fn plots_every_task_in_the_tree() {
+        let mut parent = task_json("parent", 1.0, &[("in progress", "custom", 48)]);
+        parent["subtasks"] = json!([{
+            "id": "child",
+            "custom_id": null,
+            "name": "Task child",
+            "points": 4,
+            "date_created": "1740960000000",
+            "task": task_json("child", 4.0, &[("in progress", "custom", 96)])
+        }]);
+        let task = Task::from(parse(parent));
+
+        let chart = ScatterChart::from(&task);
+
+        assert_eq!(chart.dots.len(), 2);
+        let (parent, child) = (&chart.dots[0], &chart.dots[1]);
+        assert_eq!(parent.title, "parent Task parent - points: 1, days: 2");
+        assert_eq!(child.title, "child Task child - points: 4, days: 4");
+        // More points sit further right, more days further up.
+        assert!(child.cx > parent.cx);
+        assert!(child.cy < parent.cy);
+        assert!(
+            chart
+                .dots
+                .iter()
+                .all(|d| (chart.plot_left..=chart.plot_right).contains(&d.cx)
+                    && (chart.plot_top..=chart.plot_bottom).contains(&d.cy))
+        );
+    }
+
+    #[test]
+    fn renders_escaped_task_names() {
+        let mut task = done_task("a", 2.0, 3);
+        task.name = "<script>".to_string();
+        let tasks = [Task::from(task)];
+
+        let svg = ScatterChart::from_tasks(&tasks).render().unwrap();
+
+        assert!(svg.starts_with("<svg"));
+        assert_eq!(svg.matches("<circle").count(), 1);
+        assert!(svg.contains("&#60;script&#62;"));
+        assert!(!svg.contains("<script>"));
+    }
+}
@@ -0,0 +1,124 @@
+use crate::{
+    components::charts::{
+        CHART_HEIGHT, CHART_WIDTH, LegendEntry, LinearScale, MARGIN_BOTTOM, MARGIN_LEFT,
+        MARGIN_RIGHT, MARGIN_TOP, Tick, palette_color,
+    },
+    domain::cumulative_flow::CumulativeFlow,
+};
+use askama::Template;
+
+const LEGEND_WIDTH: f32 = 150.0;
+const LEGEND_ROW_HEIGHT: f32 = 18.0;
+const MAX_DATE_TICKS: usize = 6;
+
+pub struct StackedAreaBand {
+    pub points: String,
+    pub color: &'static str,
+    pub title: String,
+}
+
+/// Draws a cumulative flow diagram, with the last workflow status at the
+/// bottom of the stack.
+#[derive(Template)]
+#[template(path = "components/charts/stacked_area.svg")]
+pub struct StackedAreaChart {
+    pub width: f32,
+    pub height: f32,
+    pub plot_left: f32,
+    pub plot_right: f32,
+    pub plot_top: f32,
+    pub plot_bottom: f32,
+    pub x_ticks: Vec<Tick>,
+    pub y_ticks: Vec<Tick>,
+    pub bands: Vec<StackedAreaBand>,
+    pub legend: Vec<LegendEntry>,
+}
+
+impl From<&CumulativeFlow> for StackedAreaChart {
+    fn from(value: &CumulativeFlow) -> Self {
+        let plot_left = MARGIN_LEFT;
+        let plot_right = CHART_WIDTH - MARGIN_RIGHT - LEGEND_WIDTH;
+        let plot_top = MARGIN_TOP;
+        let plot_bottom = CHART_HEIGHT - MARGIN_BOTTOM;
+
+        let max_total = value
+            .days
+            .iter()
+            .map(|d| d.counts.iter().sum::<usize>())
+            .max()
+            .unwrap_or_default();
+        let last_day = value.days.len().saturating_sub(1) as f32;
+        let x_scale = LinearScale::new((0.0, last_day), (plot_left, plot_right));
+        let y_scale = LinearScale::from_zero(max_total as f32, (plot_bottom, plot_top));
+
+        let mut baseline = vec![0usize; value.days.len()];
+        let mut bands = vec![];
+        for (status_index, status) in value.statuses.iter().enumerate().rev() {
+            let top: Vec<usize> = value
+                .days
+                .iter()
+                .zip(&baseline)
+                .map(|(d, base)| base + d.counts[status_index])
+                .collect();
+
+            let upper = top
+                .iter()
+                .enumerate()
+                .map(|(i, count)| (x_scale.map(i as f32), y_scale.map(*count as f32)));
+            let lower = baseline
+                .iter()
+                .enumerate()
+                .rev()
+                .map(|(i, count)| (x_scale.map(i as f32), y_scale.map(*count as f32)));
+            let points = upper
+                .chain(lower)
+                .map(|(x, y)| format!("{x:.1},{y:.1}"))
+                .collect::<Vec<_>>()
+                .join(" ");
+
+            bands.push(StackedAreaBand {
+                points,
+                color: palette_color(status_index),
+                title: status.clone(),
+            });
+            baseline = top;
+        }
+
+        let date_step = value.days.len().div_ceil(MAX_DATE_TICKS).max(1);
+        let x_ticks = value
+            .days
+            .iter()
+            .enumerate()
+            .step_by(date_step)
+            .map(|(i, d)| Tick {
+                position: x_scale.map(i as f32),
+                label: d.date.format("%b %d").to_string(),
+            })
+            .collect();
+
+        let legend = value
+            .statuses
+            .iter()
+            .enumerate()
+            .map(|(i, status)| LegendEntry {
+                x: plot_right + 16.0,
+                y: plot_top + LEGEND_ROW_HEIGHT * i as f32,
+                color: palette_color(i),
+                label: status.clone(),
+            })
+            .collect();
+
+        Self {
+            width: CHART_WIDTH,
+            height: CHART_HEIGHT,
+            plot_left,
+            plot_right,
+            plot_top,
+            plot_bottom,
+            x_ticks,
+            y_ticks: y_scale.ticks(),
+            bands,
+            legend,
+        }
+    }
+}
@@ -0,0 +1,196 @@
+use crate::{
+    components::charts::{
+        CHART_WIDTH, LinearScale, MARGIN_BOTTOM, MARGIN_RIGHT, MARGIN_TOP, Tick, format_number,
+        palette_color,
+    },
+    domain::Task,
+};
+use askama::Template;
+
+const LABEL_WIDTH: f32 = 180.0;
+const ROW_HEIGHT: f32 = 22.0;
+const BAR_HEIGHT: f32 = 16.0;
+const LEGEND_ENTRY_WIDTH: f32 = 140.0;
+const LEGEND_ROW_HEIGHT: f32 = 18.0;
+
+pub struct StackedBarSegment {
+    pub x: f32,
+    pub width: f32,
+    pub color: &'static str,
+    pub title: String,
+}
+
+pub struct StackedBar {
+    pub y: f32,
+    pub label: String,
+    pub segments: Vec<StackedBarSegment>,
+}
+
+pub struct LegendEntry {
+    pub x: f32,
+    pub y: f32,
+    pub color: &'static str,
+    pub label: String,
+}
+
+/// Draws one horizontal bar per task, split into the days spent in each status.
+#[derive(Template)]
+#[template(path = "components/charts/stacked_bar.svg")]
+pub struct StackedBarChart {
+    pub width: f32,
+    pub height: f32,
+    pub plot_left: f32,
+    pub plot_right: f32,
+    pub plot_top: f32,
+    pub plot_bottom: f32,
+    pub bar_height: f32,
+    pub x_ticks: Vec<Tick>,
+    pub bars: Vec<StackedBar>,
+    pub legend: Vec<LegendEntry>,
+}
+
+impl StackedBarChart {
+    pub fn from_tasks(tasks: &[Task]) -> Self {
+        let tasks: Vec<&Task> = tasks.iter().flat_map(|t| t.flatten()).collect();
+
+        // Statuses keep the order they are first seen in, which follows the
+        // workflow order ClickUp returns them in.
+        let mut statuses: Vec<&str> = vec![];
+        for task in &tasks {
+            for status_time in &task.status_times {
+                if !statuses.contains(&status_time.status.as_str()) {
+                    statuses.push(&status_time.status);
+                }
+            }
+        }
+
+        let plot_left = LABEL_WIDTH;
+        let plot_right = CHART_WIDTH - MARGIN_RIGHT;
+        let plot_top = MARGIN_TOP;
+        let plot_bottom = plot_top + ROW_HEIGHT * tasks.len().max(1) as f32;
+
+        let max_days = tasks
+            .iter()
+            .map(|t| t.status_times.iter().map(|s| s.minutes).sum::<i64>() as f32 / 60.0 / 24.0)
+            .fold(0.0, f32::max);
+        let x_scale = LinearScale::from_zero(max_days, (plot_left, plot_right));
+
+        let bars = tasks
+            .iter()
+            .enumerate()
+            .map(|(i, task)| {
+                let mut elapsed_days = 0.0;
+                let segments = task
+                    .status_times
+                    .iter()
+                    .filter(|s| s.minutes > 0)
+                    .map(|s| {
+                        let days = s.minutes as f32 / 60.0 / 24.0;
+                        let x = x_scale.map(elapsed_days);
+                        elapsed_days += days;
+                        let color_index = statuses
+                            .iter()
+                            .position(|status| *status == s.status)
+                            .unwrap_or_default();
+
+                        StackedBarSegment {
+                            x,
+                            width: x_scale.map(elapsed_days) - x,
+                            color: palette_color(color_index),
+                            title: format!("{}: {} days", s.status, format_number(days)),
+                        }
+                    })
+                    .collect();
+
+                StackedBar {
+                    y: plot_top + ROW_HEIGHT * i as f32 + (ROW_HEIGHT - BAR_HEIGHT) / 2.0,
+                    label: task.number.clone(),
+                    segments,
+                }
+            })
+            .collect();
+
+        let legend_columns = ((CHART_WIDTH - LABEL_WIDTH) / LEGEND_ENTRY_WIDTH).max(1.0) as usize;
+        let legend_top = plot_bottom + MARGIN_BOTTOM;
+        let legend = statuses
+            .iter()
+            .enumerate()
+            .map(|(i, status)| LegendEntry {
+                x: plot_left + LEGEND_ENTRY_WIDTH * (i % legend_columns) as f32,
+                y: legend_top + LEGEND_ROW_HEIGHT * (i / legend_columns) as f32,
+                color: palette_color(i),
+                label: status.to_string(),
+            })
+            .collect::<Vec<_>>();
+        let legend_rows = statuses.len().div_ceil(legend_columns);
+
+        Self {
+            width: CHART_WIDTH,
+            height: legend_top + LEGEND_ROW_HEIGHT * legend_rows as f32 + MARGIN_TOP,
+            plot_left,
+            plot_right,
+            plot_top,
+            plot_bottom,
+            bar_height: BAR_HEIGHT,
+            x_ticks: x_scale.ticks(),
+            bars,
+            legend,
+        }
+    }
+}
+
+impl From<&Task> for StackedBarChart {
+    fn from(value: &Task) -> Self {
+        Self::from_tasks(std::slice::from_ref(value))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::testing::{done_task, parse, task_json};
+
+    #[test]
+    fn splits_each_bar_into_its_statuses() {
+        let tasks = [
+            Task::from(done_task("a", 1.0, 2)),
+            Task::from(parse(task_json(
+                "b",
+                1.0,
+                &[("to do", "open", 0), ("in progress", "custom", 24)],
+            ))),
+        ];
+
+        let chart = StackedBarChart::from_tasks(&tasks);
+
+        assert_eq!(chart.bars.len(), 2);
+        let a = &chart.bars[0];
+        assert_eq!(a.label, "a");
+        // `done` took no time, so it gets no segment.
+        let titles: Vec<&str> = a.segments.iter().map(|s| s.title.as_str()).collect();
+        assert_eq!(titles, ["to do: 1 days", "in progress: 2 days"]);
+        assert_eq!(a.segments[0].x, chart.plot_left);
+        assert_eq!(a.segments[1].x, a.segments[0].x + a.segments[0].width);
+        assert!((a.segments[1].width - 2.0 * a.segments[0].width).abs() < 0.01);
+
+        // Statuses keep their color from task to task.
+        let b = &chart.bars[1];
+        assert_eq!(b.segments.len(), 1);
+        assert_eq!(b.segments[0].color, a.segments[1].color);
+        assert!(b.y > a.y);
+
+        let legend: Vec<&str> = chart.legend.iter().map(|e| e.label.as_str()).collect();
+        assert_eq!(legend, ["to do", "in progress", "done"]);
+    }
+
+    #[test]
+    fn renders_a_bar_and_legend_entry_per_status() {
+        let tasks = [Task::from(done_task("a", 1.0, 2))];
+
+        let svg = StackedBarChart::from_tasks(&tasks).render().unwrap();
+
+        assert!(svg.starts_with("<svg"));
+        assert!(svg.contains("<title>in progress: 2 days</title>"));
+        assert!(svg.contains(">done</text>"));
+    }
+}
@@ -0,0 +1,14 @@
+use crate::components::charts::ControlChartView;
+use askama::Template;
+
+#[derive(Template)]
+#[template(path = "components/control_chart_report.html")]
+pub struct ControlChartReport {
+    /// How durations were counted, e.g. `working_hours (end: 17:00, ...)`.
+    pub duration_policy: String,
+    pub chart: ControlChartView,
+    pub completed_task_count: usize,
+    pub outlier_count: usize,
+    /// Query string that reproduces this report, used for the download links.
+    pub query: String,
+}
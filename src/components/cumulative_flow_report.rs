@@ -0,0 +1,12 @@
+use crate::components::charts::StackedAreaChart;
+use askama::Template;
+
+#[derive(Template)]
+#[template(path = "components/cumulative_flow_report.html")]
+pub struct CumulativeFlowReport {
+    /// How durations were counted, e.g. `working_hours (end: 17:00, ...)`.
+    pub duration_policy: String,
+    pub chart: StackedAreaChart,
+    /// Query string that reproduces this report, used for the download links.
+    pub query: String,
+}
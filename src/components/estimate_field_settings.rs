@@ -0,0 +1,66 @@
+use crate::{
+    domain::estimates::ESTIMATE_FIELD_TYPES,
+    services::{clickup::ClickUpCustomField, workspace_settings::EstimateField},
+};
+use askama::Template;
+
+pub struct EstimateFieldRow {
+    pub id: String,
+    pub name: String,
+    pub is_selected: bool,
+    pub options: Vec<EstimateOptionRow>,
+}
+
+pub struct EstimateOptionRow {
+    pub name: String,
+    /// Empty when the option has no mapping rule.
+    pub points: String,
+}
+
+#[derive(Template)]
+#[template(path = "components/estimate_field_settings.html")]
+pub struct EstimateFieldSettings {
+    pub current_field_name: String,
+    pub fields: Vec<EstimateFieldRow>,
+}
+
+impl EstimateFieldSettings {
+    /// Lists the custom fields that can be read as an estimate, with the
+    /// workspace's current mapping rules filled in.
+    pub fn new(fields: Vec<ClickUpCustomField>, current: Option<&EstimateField>) -> Self {
+        let fields = fields
+            .into_iter()
+            .filter(|f| ESTIMATE_FIELD_TYPES.contains(&f.field_type.as_str()))
+            .map(|f| {
+                let current = current.filter(|c| c.field_id == f.id);
+                let options = f
+                    .options()
+                    .iter()
+                    .filter_map(|o| o.name.clone())
+                    .map(|name| EstimateOptionRow {
+                        points: current
+                            .and_then(|c| c.option_points.get(&name.to_lowercase()))
+                            .map(|points| points.to_string())
+                            .unwrap_or_default(),
+                        name,
+                    })
+                    .collect();
+
+                EstimateFieldRow {
+                    is_selected: current.is_some(),
+                    id: f.id,
+                    name: f.name,
+                    options,
+                }
+            })
+            .collect();
+
+        Self {
+            current_field_name: match current {
+                Some(current) => current.field_name.clone(),
+                None => "Sprint Points".to_string(),
+            },
+            fields,
+        }
+    }
+}
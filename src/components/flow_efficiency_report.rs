@@ -0,0 +1,96 @@
+use crate::{
+    components::task_link, domain::flow_efficiency::FlowEfficiency,
+    services::workspace_settings::StatusCategory,
+};
+use askama::Template;
+
+pub struct FlowEfficiencyRow {
+    pub number: String,
+    pub name: String,
+    /// Unset when the URL isn't one to link to, see [`task_link`].
+    pub url: Option<String>,
+    pub active_days: String,
+    pub waiting_days: String,
+    pub efficiency: String,
+}
+
+pub struct WaitingStatusRow {
+    pub status: String,
+    pub days: String,
+    pub share: String,
+}
+
+pub struct StatusCategoryRow {
+    pub status: String,
+    /// `active`, `waiting`, or empty when defaulted.
+    pub category: &'static str,
+    pub default_label: &'static str,
+}
+
+#[derive(Template)]
+#[template(path = "components/flow_efficiency_report.html")]
+pub struct FlowEfficiencyReport {
+    /// How durations were counted, e.g. `working_hours (end: 17:00, ...)`.
+    pub duration_policy: String,
+    pub efficiency: String,
+    pub active_days: String,
+    pub waiting_days: String,
+    pub can_edit_statuses: bool,
+    pub waiting_statuses: Vec<WaitingStatusRow>,
+    pub statuses: Vec<StatusCategoryRow>,
+    pub rows: Vec<FlowEfficiencyRow>,
+}
+
+impl FlowEfficiencyReport {
+    pub fn new(value: FlowEfficiency, can_edit_statuses: bool) -> Self {
+        let format_percentage = |percentage: Option<f32>| match percentage {
+            Some(percentage) => format!("{percentage:.0}%"),
+            None => "-".to_string(),
+        };
+
+        Self {
+            duration_policy: value.duration_policy.description(),
+            efficiency: format_percentage(value.efficiency),
+            active_days: format!("{:.1}", value.active_days),
+            waiting_days: format!("{:.1}", value.waiting_days),
+            can_edit_statuses,
+            waiting_statuses: value
+                .waiting_statuses
+                .into_iter()
+                .map(|w| WaitingStatusRow {
+                    status: w.status,
+                    days: format!("{:.1}", w.days),
+                    share: format!("{:.0}%", w.share),
+                })
+                .collect(),
+            statuses: value
+                .statuses
+                .into_iter()
+                .map(|s| StatusCategoryRow {
+                    category: match (s.is_tagged, s.category) {
+                        (true, Some(StatusCategory::Active)) => "active",
+                        (true, Some(StatusCategory::Waiting)) => "waiting",
+                        _ => "",
+                    },
+                    default_label: match s.category {
+                        Some(_) if !s.is_tagged => "Default (active)",
+                        _ => "Default (not counted)",
+                    },
+                    status: s.status,
+                })
+                .collect(),
+            rows: value
+                .tasks
+                .into_iter()
+                .map(|t| FlowEfficiencyRow {
+                    number: t.number,
+                    name: t.name,
+                    url: task_link(&t.url),
+                    active_days: format!("{:.1}", t.active_days),
+                    waiting_days: format!("{:.1}", t.waiting_days),
+                    efficiency: format_percentage(t.efficiency),
+                })
+                .collect(),
+        }
+    }
+}
@@ -0,0 +1,15 @@
+use crate::services::clickup::FetchProgress;
+use askama::Template;
+
+#[derive(Template)]
+#[template(path = "components/job_progress.html")]
+pub struct JobProgress {
+    pub job_id: String,
+    pub progress: FetchProgress,
+}
+
+#[derive(Template)]
+#[template(path = "components/job_progress_bar.html")]
+pub struct JobProgressBar {
+    pub progress: FetchProgress,
+}
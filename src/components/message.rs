@@ -0,0 +1,17 @@
+use askama::Template;
+
+/// A message shown in place of a fragment, e.g. why a form couldn't be
+/// submitted. The message is escaped, so it can quote user input.
+#[derive(Template)]
+#[template(path = "components/message.html")]
+pub struct Message<'a> {
+    pub message: &'a str,
+}
+
+/// A [`Message`] shown next to a form's controls, e.g. why a setting wasn't
+/// saved.
+#[derive(Template)]
+#[template(path = "components/inline_message.html")]
+pub struct InlineMessage<'a> {
+    pub message: &'a str,
+}
@@ -1,3 +1,63 @@
+mod aging_wip_report;
+mod assignee_breakdown_report;
+pub mod charts;
+mod control_chart_report;
+mod cumulative_flow_report;
+mod estimate_field_settings;
+mod flow_efficiency_report;
+mod job_progress;
+mod message;
+mod offline_dataset_view;
+mod rework_report;
+mod sprint_report_view;
+mod task_analysis;
+mod task_charts_report;
+mod task_tree;
+mod velocity_report;
+mod working_hours_settings;
 mod workspace_select;
 
+pub use aging_wip_report::*;
+pub use assignee_breakdown_report::*;
+pub use control_chart_report::*;
+pub use cumulative_flow_report::*;
+pub use estimate_field_settings::*;
+pub use flow_efficiency_report::*;
+pub use job_progress::*;
+pub use message::*;
+pub use offline_dataset_view::*;
+pub use rework_report::*;
+pub use sprint_report_view::*;
+pub use task_analysis::*;
+pub use task_charts_report::*;
+pub use task_tree::*;
+pub use velocity_report::*;
+pub use working_hours_settings::*;
 pub use workspace_select::*;
+
+/// Returns the task's URL if it can be linked to. Only `http` and `https` URLs
+/// are, since uploaded datasets could hold `javascript:` ones.
+pub(crate) fn task_link(url: &str) -> Option<String> {
+    match url::Url::parse(url) {
+        Ok(parsed) if matches!(parsed.scheme(), "http" | "https") => Some(url.to_string()),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn only_links_to_web_urls() {
+        assert_eq!(
+            task_link("https://app.clickup.com/t/abc").as_deref(),
+            Some("https://app.clickup.com/t/abc")
+        );
+        assert!(task_link("http://localhost/t/abc").is_some());
+        assert_eq!(task_link("javascript:alert(1)"), None);
+        assert_eq!(task_link(" JavaScript:alert(1)"), None);
+        assert_eq!(task_link("data:text/html,<b>"), None);
+        assert_eq!(task_link(""), None);
+    }
+}
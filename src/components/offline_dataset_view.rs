@@ -0,0 +1,38 @@
+use crate::domain::offline::OfflineDataset;
+use askama::Template;
+
+pub struct OfflineDatasetTask {
+    pub id: String,
+    pub name: String,
+}
+
+/// An uploaded dataset, with forms to run the task analysis and the list
+/// reports against it.
+#[derive(Template)]
+#[template(path = "components/offline_dataset_view.html")]
+pub struct OfflineDatasetView {
+    pub dataset_id: String,
+    pub task_count: usize,
+    pub time_in_status_count: usize,
+    /// Tasks that can be analysed with their sub tasks.
+    pub root_tasks: Vec<OfflineDatasetTask>,
+}
+
+impl OfflineDatasetView {
+    pub fn new(dataset_id: String, dataset: &OfflineDataset) -> Self {
+        Self {
+            dataset_id,
+            task_count: dataset.task_count(),
+            time_in_status_count: dataset.time_in_status_count(),
+            root_tasks: dataset
+                .root_tasks()
+                .into_iter()
+                .filter(|t| t.time_in_status.is_some())
+                .map(|t| OfflineDatasetTask {
+                    id: t.id.clone(),
+                    name: t.name.clone(),
+                })
+                .collect(),
+        }
+    }
+}
@@ -0,0 +1,62 @@
+use crate::{
+    components::task_link,
+    domain::transitions::{ReworkReport, TransitionSource},
+};
+use askama::Template;
+
+pub struct ReworkRow {
+    pub number: String,
+    pub name: String,
+    /// Unset when the URL isn't one to link to, see [`task_link`].
+    pub url: Option<String>,
+    pub rework_loops: usize,
+    pub backward_transitions: Vec<String>,
+}
+
+#[derive(Template)]
+#[template(path = "components/rework_report.html")]
+pub struct ReworkReportView {
+    /// Every task had a status history, so the counts are exact rather than
+    /// a lower bound.
+    pub is_exact: bool,
+    pub task_count: usize,
+    pub tasks_with_rework: usize,
+    pub rework_loops: usize,
+    pub rows: Vec<ReworkRow>,
+}
+
+impl From<ReworkReport> for ReworkReportView {
+    fn from(value: ReworkReport) -> Self {
+        Self {
+            is_exact: value.source == TransitionSource::History,
+            task_count: value.task_count,
+            tasks_with_rework: value.tasks_with_rework,
+            rework_loops: value.rework_loops,
+            rows: value
+                .tasks
+                .into_iter()
+                .filter(|t| t.transitions.rework_loops > 0)
+                .map(|t| ReworkRow {
+                    number: t.number,
+                    name: t.name,
+                    url: task_link(&t.url),
+                    rework_loops: t.transitions.rework_loops,
+                    backward_transitions: t
+                        .transitions
+                        .transitions
+                        .iter()
+                        .filter(|transition| transition.is_backward)
+                        .map(|transition| {
+                            format!(
+                                "{} → {} on {}",
+                                transition.from,
+                                transition.to,
+                                transition.at.format("%Y-%m-%d")
+                            )
+                        })
+                        .collect(),
+                })
+                .collect(),
+        }
+    }
+}
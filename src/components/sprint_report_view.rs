@@ -0,0 +1,118 @@
+use crate::{
+    components::task_link,
+    domain::sprints::{SprintReport, SprintTask, SprintVelocity},
+};
+use askama::Template;
+
+pub struct SprintTaskRow {
+    pub number: String,
+    pub name: String,
+    /// Unset when the URL isn't one to link to, see [`task_link`].
+    pub url: Option<String>,
+    pub points: f32,
+    /// e.g. `in progress 2.5d, qa 1.0d`.
+    pub status_days: String,
+}
+
+pub struct SprintTaskSection {
+    pub title: &'static str,
+    pub rows: Vec<SprintTaskRow>,
+}
+
+pub struct SprintVelocityRow {
+    pub name: String,
+    pub dates: String,
+    pub committed_points: f32,
+    pub added_points: f32,
+    pub completed_points: f32,
+}
+
+#[derive(Template)]
+#[template(path = "components/sprint_report.html")]
+pub struct SprintReportView {
+    pub name: String,
+    pub dates: String,
+    pub committed_points: f32,
+    pub added_points: f32,
+    pub completed_points: f32,
+    pub committed_task_count: usize,
+    pub completed_task_count: usize,
+    pub average_velocity: String,
+    /// Completed, carry-over and added tasks.
+    pub task_sections: Vec<SprintTaskSection>,
+    pub velocity_history: Vec<SprintVelocityRow>,
+}
+
+impl From<&SprintTask> for SprintTaskRow {
+    fn from(value: &SprintTask) -> Self {
+        Self {
+            number: value.number.clone(),
+            name: value.name.clone(),
+            url: task_link(&value.url),
+            points: value.points,
+            status_days: value
+                .status_days
+                .iter()
+                .map(|s| format!("{} {:.1}d", s.status, s.days))
+                .collect::<Vec<_>>()
+                .join(", "),
+        }
+    }
+}
+
+impl From<&SprintVelocity> for SprintVelocityRow {
+    fn from(value: &SprintVelocity) -> Self {
+        Self {
+            name: value.sprint.name.clone(),
+            dates: format_dates(value),
+            committed_points: value.committed_points,
+            added_points: value.added_points,
+            completed_points: value.completed_points,
+        }
+    }
+}
+
+impl From<SprintReport> for SprintReportView {
+    fn from(value: SprintReport) -> Self {
+        Self {
+            name: value.velocity.sprint.name.clone(),
+            dates: format_dates(&value.velocity),
+            committed_points: value.velocity.committed_points,
+            added_points: value.velocity.added_points,
+            completed_points: value.velocity.completed_points,
+            committed_task_count: value.committed_task_count,
+            completed_task_count: value.completed_task_count,
+            average_velocity: match value.average_velocity {
+                Some(average_velocity) => format!("{average_velocity:.1}"),
+                None => "-".to_string(),
+            },
+            task_sections: vec![
+                SprintTaskSection {
+                    title: "Completed",
+                    rows: value.completed.iter().map(SprintTaskRow::from).collect(),
+                },
+                SprintTaskSection {
+                    title: "Carry-over",
+                    rows: value.carry_over.iter().map(SprintTaskRow::from).collect(),
+                },
+                SprintTaskSection {
+                    title: "Added Mid-Sprint",
+                    rows: value.added.iter().map(SprintTaskRow::from).collect(),
+                },
+            ],
+            velocity_history: value
+                .velocity_history
+                .iter()
+                .map(SprintVelocityRow::from)
+                .collect(),
+        }
+    }
+}
+
+fn format_dates(velocity: &SprintVelocity) -> String {
+    format!(
+        "{} to {}",
+        velocity.sprint.start.format("%Y-%m-%d"),
+        velocity.sprint.end.format("%Y-%m-%d")
+    )
+}
@@ -0,0 +1,40 @@
+use crate::{
+    components::{
+        AssigneeBreakdownReport, TaskTree,
+        charts::{ScatterChart, StackedBarChart},
+    },
+    domain::{Task, assignees::AssigneeBreakdown},
+};
+use askama::Template;
+
+/// The full result of a finished task analysis job.
+#[derive(Template)]
+#[template(path = "components/task_analysis.html")]
+pub struct TaskAnalysis {
+    pub job_id: String,
+    /// How durations were counted, e.g. `working_hours (end: 17:00, ...)`.
+    pub duration_policy: String,
+    pub task_tree: TaskTree,
+    pub scatter_chart: ScatterChart,
+    pub status_chart: StackedBarChart,
+    pub assignees: AssigneeBreakdownReport,
+}
+
+impl TaskAnalysis {
+    pub fn new(job_id: String, task: &Task) -> Self {
+        Self {
+            duration_policy: match &task.duration_policy {
+                Some(duration_policy) => duration_policy.description(),
+                None => "full_time".to_string(),
+            },
+            task_tree: TaskTree::from(task),
+            scatter_chart: ScatterChart::from(task),
+            status_chart: StackedBarChart::from(task),
+            assignees: AssigneeBreakdownReport::new(
+                AssigneeBreakdown::new(std::slice::from_ref(task), false),
+                format!("/jobs/{job_id}/assignees?"),
+            ),
+            job_id,
+        }
+    }
+}
@@ -0,0 +1,15 @@
+use crate::components::charts::{ScatterChart, StackedBarChart};
+use askama::Template;
+
+/// The scatter and per-status charts of every task in a list.
+#[derive(Template)]
+#[template(path = "components/task_charts_report.html")]
+pub struct TaskChartsReport {
+    /// How durations were counted, e.g. `working_hours (end: 17:00, ...)`.
+    pub duration_policy: String,
+    pub task_count: usize,
+    pub scatter_chart: ScatterChart,
+    pub status_chart: StackedBarChart,
+    /// Query string that reproduces this report, used for the download links.
+    pub query: String,
+}
@@ -0,0 +1,107 @@
+use crate::{
+    components::task_link,
+    domain::{Outlier, Task, TimeDiscrepancy},
+};
+use askama::Template;
+
+pub struct TaskTreeRow {
+    pub row_id: String,
+    pub parent_row_id: Option<String>,
+    pub depth: usize,
+    pub has_children: bool,
+    pub number: String,
+    pub name: String,
+    /// Unset when the URL isn't one to link to, see [`task_link`].
+    pub url: Option<String>,
+    pub points: f32,
+    pub total_points: f32,
+    pub time_in_dev_status: i64,
+    pub total_time_in_dev_status: i64,
+    pub time_estimate_hours: String,
+    pub total_time_estimate_hours: String,
+    pub time_tracked_hours: String,
+    pub total_time_tracked_hours: String,
+    pub rework_loops: usize,
+    pub outlier_class: &'static str,
+    pub outlier_reasons: Vec<String>,
+    /// Explains why the logged time is flagged, empty when it isn't.
+    pub time_discrepancy: &'static str,
+    /// Why the task wasn't fetched, empty when it was.
+    pub load_error: String,
+    pub is_incomplete: bool,
+}
+
+/// Renders a task tree as a table whose rows can be collapsed by parent.
+#[derive(Template)]
+#[template(path = "components/task_tree.html")]
+pub struct TaskTree {
+    pub rows: Vec<TaskTreeRow>,
+    /// Whether some sub tasks weren't fetched.
+    pub is_incomplete: bool,
+}
+
+impl From<&Task> for TaskTree {
+    fn from(value: &Task) -> Self {
+        fn push_rows(
+            task: &Task,
+            parent_row_id: Option<String>,
+            depth: usize,
+            rows: &mut Vec<TaskTreeRow>,
+        ) {
+            let row_id = format!("task-tree-row-{}", rows.len());
+            rows.push(TaskTreeRow {
+                row_id: row_id.clone(),
+                parent_row_id,
+                depth,
+                has_children: !task.sub_tasks.is_empty(),
+                number: task.number.clone(),
+                name: task.name.clone(),
+                url: task_link(&task.url),
+                points: task.points,
+                total_points: task.total_points,
+                time_in_dev_status: task.time_in_dev_status,
+                total_time_in_dev_status: task.total_time_in_dev_status,
+                time_estimate_hours: match task.time_estimate_hours {
+                    Some(hours) => format!("{hours:.1}"),
+                    None => "-".to_string(),
+                },
+                total_time_estimate_hours: format!("{:.1}", task.total_time_estimate_hours),
+                time_tracked_hours: format!("{:.1}", task.time_tracked_hours),
+                total_time_tracked_hours: format!("{:.1}", task.total_time_tracked_hours),
+                rework_loops: task.rework_loops,
+                outlier_class: match task.outlier {
+                    Some(Outlier::SlowerThanNorm) => "outlier-slow",
+                    Some(Outlier::FasterThanNorm) => "outlier-fast",
+                    Some(Outlier::Unestimated) => "outlier-unestimated",
+                    None => "",
+                },
+                outlier_reasons: task
+                    .outlier_reasons
+                    .iter()
+                    .map(|r| r.description())
+                    .collect(),
+                time_discrepancy: match task.time_discrepancy {
+                    Some(TimeDiscrepancy::UnderTracked) => {
+                        "Little time was logged for the days spent in development"
+                    }
+                    Some(TimeDiscrepancy::OverEstimate) => "More time was logged than estimated",
+                    None => "",
+                },
+                load_error: task.load_error.clone().unwrap_or_default(),
+                is_incomplete: task.is_incomplete,
+            });
+
+            for sub_task in &task.sub_tasks {
+                push_rows(sub_task, Some(row_id.clone()), depth + 1, rows);
+            }
+        }
+
+        let mut rows = vec![];
+        push_rows(value, None, 0, &mut rows);
+
+        Self {
+            rows,
+            is_incomplete: value.is_incomplete,
+        }
+    }
+}
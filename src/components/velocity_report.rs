@@ -0,0 +1,72 @@
+use crate::{
+    components::charts::LineChart,
+    domain::velocity::{VelocityMetric, VelocityTrend},
+};
+use askama::Template;
+
+pub struct VelocityRow {
+    pub period: String,
+    pub completed_points: String,
+    pub completed_tasks: usize,
+    pub points_average: String,
+    pub tasks_average: String,
+}
+
+#[derive(Template)]
+#[template(path = "components/velocity_report.html")]
+pub struct VelocityReport {
+    /// How durations were counted, e.g. `working_hours (end: 17:00, ...)`.
+    pub duration_policy: String,
+    pub window: usize,
+    pub points_average: String,
+    pub points_standard_deviation: String,
+    pub tasks_average: String,
+    pub tasks_standard_deviation: String,
+    pub points_chart: LineChart,
+    pub tasks_chart: LineChart,
+    pub rows: Vec<VelocityRow>,
+    /// Query string that reproduces this report, used for the download links.
+    pub query: String,
+}
+
+impl VelocityReport {
+    pub fn new(value: &VelocityTrend, query: String) -> Self {
+        let format_optional = |value: Option<f32>| match value {
+            Some(value) => format!("{value:.1}"),
+            None => "-".to_string(),
+        };
+
+        Self {
+            duration_policy: value.duration_policy.description(),
+            window: value.window,
+            points_average: format_optional(value.points_average),
+            points_standard_deviation: format_optional(value.points_standard_deviation),
+            tasks_average: format_optional(value.tasks_average),
+            tasks_standard_deviation: format_optional(value.tasks_standard_deviation),
+            points_chart: LineChart::from_velocity_trend(value, VelocityMetric::Points),
+            tasks_chart: LineChart::from_velocity_trend(value, VelocityMetric::Tasks),
+            rows: value
+                .points
+                .iter()
+                .map(|p| VelocityRow {
+                    period: p.period.label.clone(),
+                    completed_points: format!("{:.1}", p.completed_points),
+                    completed_tasks: p.completed_tasks,
+                    points_average: match (p.points_average, p.points_standard_deviation) {
+                        (Some(average), Some(deviation)) => {
+                            format!("{average:.1} ± {deviation:.1}")
+                        }
+                        _ => "-".to_string(),
+                    },
+                    tasks_average: match (p.tasks_average, p.tasks_standard_deviation) {
+                        (Some(average), Some(deviation)) => {
+                            format!("{average:.1} ± {deviation:.1}")
+                        }
+                        _ => "-".to_string(),
+                    },
+                })
+                .collect(),
+            query,
+        }
+    }
+}
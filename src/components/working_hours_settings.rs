@@ -0,0 +1,75 @@
+use crate::services::workspace_settings::{WorkCalendar, WorkingHours};
+use askama::Template;
+use chrono::Weekday;
+
+const WEEK: [Weekday; 7] = [
+    Weekday::Mon,
+    Weekday::Tue,
+    Weekday::Wed,
+    Weekday::Thu,
+    Weekday::Fri,
+    Weekday::Sat,
+    Weekday::Sun,
+];
+
+pub struct WorkingDayOption {
+    pub value: String,
+    pub is_checked: bool,
+}
+
+#[derive(Template)]
+#[template(path = "components/working_hours_settings.html")]
+pub struct WorkingHoursSettings {
+    /// Empty when the workspace counts wall-clock time.
+    pub start: String,
+    pub end: String,
+    /// An IANA name such as `Europe/Paris`.
+    pub time_zone: String,
+    pub has_calendar: bool,
+    pub working_days: Vec<WorkingDayOption>,
+    /// One `YYYY-MM-DD` date per line.
+    pub holidays: String,
+}
+
+impl WorkingHoursSettings {
+    pub fn new(working_hours: Option<&WorkingHours>, calendar: Option<&WorkCalendar>) -> Self {
+        let (start, end, time_zone) = match working_hours {
+            Some(working_hours) => (
+                working_hours.start.format("%H:%M").to_string(),
+                working_hours.end.format("%H:%M").to_string(),
+                working_hours.time_zone.name().to_string(),
+            ),
+            None => (String::new(), String::new(), "UTC".to_string()),
+        };
+
+        let working_days = WEEK
+            .iter()
+            .map(|day| WorkingDayOption {
+                value: day.to_string(),
+                is_checked: match calendar {
+                    Some(calendar) => calendar.working_days.contains(day),
+                    None => !matches!(day, Weekday::Sat | Weekday::Sun),
+                },
+            })
+            .collect();
+
+        let holidays = match calendar {
+            Some(calendar) => calendar
+                .holidays
+                .iter()
+                .map(|d| d.to_string())
+                .collect::<Vec<_>>()
+                .join("\n"),
+            None => String::new(),
+        };
+
+        Self {
+            start,
+            end,
+            time_zone,
+            has_calendar: calendar.is_some(),
+            working_days,
+            holidays,
+        }
+    }
+}
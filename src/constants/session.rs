@@ -1,2 +1,3 @@
 pub const CLICK_UP_AUTH_TOKEN: &str = "click_up_access_token";
 pub const CURRENT_WORKSPACE_ID: &str = "current_WORKSPACE_ID";
+pub const RECENT_JOB_IDS: &str = "recent_job_ids";
@@ -0,0 +1,117 @@
+use crate::{
+    domain::{
+        duration_policy::{DurationPolicy, DurationPolicyRecord},
+        statistics::percentile,
+        timeline::{
+            current_period, cycle_time_days, minutes_to_days, status_timeline, work_started_at,
+        },
+    },
+    services::clickup::ClickUpTaskResponseBody,
+};
+use chrono::{DateTime, TimeDelta, Utc};
+use serde::Serialize;
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AgingRisk {
+    /// Younger than the historical median cycle time.
+    OnTrack,
+    /// Older than the median but not yet past the 85th percentile.
+    Watch,
+    /// Older than 85% of the tasks completed before it.
+    AtRisk,
+    /// There are no completed tasks to compare against.
+    Unknown,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct AgingWipTask {
+    pub id: String,
+    pub number: String,
+    pub name: String,
+    pub url: String,
+    pub status: String,
+    pub days_in_status: f32,
+    pub days_in_progress: f32,
+    pub risk: AgingRisk,
+}
+
+/// How long every in progress task in a list has been worked on, compared
+/// against the cycle times of the list's completed tasks.
+#[derive(Debug, Clone, Serialize)]
+pub struct AgingWip {
+    /// How ages and cycle times were counted.
+    pub duration_policy: DurationPolicyRecord,
+    pub cycle_time_p50: Option<f32>,
+    pub cycle_time_p85: Option<f32>,
+    pub completed_task_count: usize,
+    /// Oldest first.
+    pub tasks: Vec<AgingWipTask>,
+}
+
+impl AgingWip {
+    pub fn new(
+        tasks: &[ClickUpTaskResponseBody],
+        now: DateTime<Utc>,
+        duration_policy: &dyn DurationPolicy,
+    ) -> Self {
+        let mut cycle_times = vec![];
+        let mut in_progress = vec![];
+
+        for task in tasks {
+            let Some(time_in_status) = &task.time_in_status else {
+                continue;
+            };
+            let timeline = status_timeline(time_in_status);
+
+            if let Some(cycle_time) = cycle_time_days(&timeline, time_in_status, duration_policy) {
+                cycle_times.push(cycle_time);
+                continue;
+            }
+
+            let Some(current) = current_period(&timeline, time_in_status) else {
+                continue;
+            };
+            if !current.is_in_progress() {
+                continue;
+            }
+
+            let started = work_started_at(&timeline).unwrap_or(current.start);
+            let current_total_time = &time_in_status.current_status.total_time;
+            in_progress.push(AgingWipTask {
+                id: task.id.clone(),
+                number: task.custom_id.clone().unwrap_or_else(|| task.id.clone()),
+                name: task.name.clone(),
+                url: task.url.clone(),
+                status: current.status.clone(),
+                days_in_status: minutes_to_days(duration_policy.minutes(
+                    current_total_time.since,
+                    current_total_time.since + TimeDelta::minutes(current_total_time.by_minute),
+                )),
+                days_in_progress: minutes_to_days(duration_policy.minutes(started, now)),
+                risk: AgingRisk::Unknown,
+            });
+        }
+
+        let cycle_time_p50 = percentile(&cycle_times, 50.0);
+        let cycle_time_p85 = percentile(&cycle_times, 85.0);
+
+        for task in &mut in_progress {
+            task.risk = match (cycle_time_p50, cycle_time_p85) {
+                (Some(_), Some(p85)) if task.days_in_progress > p85 => AgingRisk::AtRisk,
+                (Some(p50), Some(_)) if task.days_in_progress > p50 => AgingRisk::Watch,
+                (Some(_), Some(_)) => AgingRisk::OnTrack,
+                _ => AgingRisk::Unknown,
+            };
+        }
+        in_progress.sort_by(|a, b| b.days_in_progress.total_cmp(&a.days_in_progress));
+
+        Self {
+            duration_policy: duration_policy.record(),
+            cycle_time_p50,
+            cycle_time_p85,
+            completed_task_count: cycle_times.len(),
+            tasks: in_progress,
+        }
+    }
+}
@@ -0,0 +1,212 @@
+use crate::domain::Task;
+use serde::Serialize;
+
+const UNASSIGNED: &str = "Unassigned";
+
+#[derive(Debug, Clone, Serialize)]
+pub struct AssigneeSummary {
+    pub name: String,
+    pub completed_tasks: usize,
+    pub points_completed: f32,
+    pub dev_days: f32,
+    /// Dev days spent on completed tasks per completed point.
+    pub days_per_point: Option<f32>,
+    pub tasks_in_progress: usize,
+}
+
+/// Points and time grouped by assignee. A task with several assignees is
+/// shared evenly between them, so the totals still add up.
+#[derive(Debug, Clone, Serialize)]
+pub struct AssigneeBreakdown {
+    pub anonymized: bool,
+    /// Most points completed first.
+    pub assignees: Vec<AssigneeSummary>,
+}
+
+impl AssigneeBreakdown {
+    /// Groups `tasks` and all of their subtasks. With `anonymize` the names are
+    /// replaced by "Person 1", "Person 2" and so on.
+    pub fn new(tasks: &[Task], anonymize: bool) -> Self {
+        struct Totals {
+            id: Option<i64>,
+            name: String,
+            completed_tasks: usize,
+            points_completed: f32,
+            dev_days: f32,
+            completed_dev_days: f32,
+            tasks_in_progress: usize,
+        }
+
+        let mut totals: Vec<Totals> = vec![];
+        for task in tasks.iter().flat_map(|t| t.flatten()) {
+            let assignees: Vec<(Option<i64>, &str)> = if task.assignees.is_empty() {
+                vec![(None, UNASSIGNED)]
+            } else {
+                task.assignees
+                    .iter()
+                    .map(|a| (Some(a.id), a.name.as_str()))
+                    .collect()
+            };
+            let share = 1.0 / assignees.len() as f32;
+
+            for (id, name) in assignees {
+                let i = match totals.iter().position(|t| t.id == id) {
+                    Some(i) => i,
+                    None => {
+                        totals.push(Totals {
+                            id,
+                            name: name.to_string(),
+                            completed_tasks: 0,
+                            points_completed: 0.0,
+                            dev_days: 0.0,
+                            completed_dev_days: 0.0,
+                            tasks_in_progress: 0,
+                        });
+                        totals.len() - 1
+                    }
+                };
+                let assignee_totals = &mut totals[i];
+
+                let dev_days = task.time_in_dev_status as f32 * share;
+                assignee_totals.dev_days += dev_days;
+                if task.is_completed {
+                    assignee_totals.completed_tasks += 1;
+                    assignee_totals.points_completed += task.points * share;
+                    assignee_totals.completed_dev_days += dev_days;
+                }
+                if task.is_in_progress {
+                    assignee_totals.tasks_in_progress += 1;
+                }
+            }
+        }
+
+        if anonymize {
+            for (i, assignee_totals) in totals.iter_mut().filter(|t| t.id.is_some()).enumerate() {
+                assignee_totals.name = format!("Person {}", i + 1);
+            }
+        }
+
+        let mut assignees: Vec<AssigneeSummary> = totals
+            .into_iter()
+            .map(|t| AssigneeSummary {
+                days_per_point: if t.points_completed > 0.0 {
+                    Some(t.completed_dev_days / t.points_completed)
+                } else {
+                    None
+                },
+                name: t.name,
+                completed_tasks: t.completed_tasks,
+                points_completed: t.points_completed,
+                dev_days: t.dev_days,
+                tasks_in_progress: t.tasks_in_progress,
+            })
+            .collect();
+        assignees.sort_by(|a, b| b.points_completed.total_cmp(&a.points_completed));
+
+        Self {
+            anonymized: anonymize,
+            assignees,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::{
+        Assignee,
+        testing::{done_task, parse, task_json},
+    };
+
+    fn assigned(mut task: Task, assignees: &[(i64, &str)]) -> Task {
+        task.assignees = assignees
+            .iter()
+            .map(|(id, name)| Assignee {
+                id: *id,
+                name: name.to_string(),
+            })
+            .collect();
+        task
+    }
+
+    fn tasks() -> Vec<Task> {
+        let in_progress = parse(task_json(
+            "c",
+            3.0,
+            &[("to do", "open", 24), ("in progress", "custom", 48)],
+        ));
+        vec![
+            assigned(Task::from(done_task("a", 2.0, 6)), &[(1, "Ada")]),
+            assigned(
+                Task::from(done_task("b", 1.0, 2)),
+                &[(1, "Ada"), (2, "Grace")],
+            ),
+            assigned(Task::from(in_progress), &[(2, "Grace")]),
+            Task::from(done_task("d", 1.0, 1)),
+        ]
+    }
+
+    #[test]
+    fn shares_tasks_between_their_assignees() {
+        let breakdown = AssigneeBreakdown::new(&tasks(), false);
+
+        let assignees: Vec<_> = breakdown
+            .assignees
+            .iter()
+            .map(|a| {
+                (
+                    a.name.as_str(),
+                    a.completed_tasks,
+                    a.points_completed,
+                    a.dev_days,
+                    a.tasks_in_progress,
+                )
+            })
+            .collect();
+        assert_eq!(
+            assignees,
+            [
+                ("Ada", 2, 2.5, 7.0, 0),
+                ("Unassigned", 1, 1.0, 1.0, 0),
+                ("Grace", 1, 0.5, 3.0, 1),
+            ]
+        );
+    }
+
+    #[test]
+    fn days_per_point_only_counts_completed_tasks() {
+        let breakdown = AssigneeBreakdown::new(&tasks(), false);
+
+        let days_per_point: Vec<_> = breakdown
+            .assignees
+            .iter()
+            .map(|a| (a.name.as_str(), a.days_per_point))
+            .collect();
+        assert_eq!(
+            days_per_point,
+            [
+                ("Ada", Some(2.8)),
+                ("Unassigned", Some(1.0)),
+                ("Grace", Some(2.0)),
+            ]
+        );
+
+        let breakdown = AssigneeBreakdown::new(&tasks()[2..3], false);
+        assert_eq!(breakdown.assignees[0].days_per_point, None);
+    }
+
+    #[test]
+    fn anonymized_breakdowns_have_no_real_names() {
+        let breakdown = AssigneeBreakdown::new(&tasks(), true);
+
+        let names: Vec<_> = breakdown
+            .assignees
+            .iter()
+            .map(|a| a.name.as_str())
+            .collect();
+        assert_eq!(names, ["Person 1", "Unassigned", "Person 2"]);
+        let json = serde_json::to_string(&breakdown).unwrap();
+        assert!(!json.contains("Ada"));
+        assert!(!json.contains("Grace"));
+    }
+}
@@ -0,0 +1,74 @@
+use crate::{
+    domain::{
+        duration_policy::{DurationPolicy, DurationPolicyRecord},
+        statistics::{mean, percentile},
+        timeline::{completed_at, cycle_time_days, status_timeline},
+    },
+    services::clickup::ClickUpTaskResponseBody,
+};
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ControlChartTask {
+    pub id: String,
+    pub number: String,
+    pub name: String,
+    pub url: String,
+    pub completed_at: DateTime<Utc>,
+    pub cycle_time_days: f32,
+    /// Slower than 95% of the completed tasks.
+    pub is_outlier: bool,
+}
+
+/// The cycle time of every completed task in a list by completion date,
+/// with the bands it is usually expected to fall within.
+#[derive(Debug, Clone, Serialize)]
+pub struct ControlChart {
+    /// How cycle times were counted.
+    pub duration_policy: DurationPolicyRecord,
+    pub mean: Option<f32>,
+    pub p50: Option<f32>,
+    pub p85: Option<f32>,
+    pub p95: Option<f32>,
+    /// Oldest completion first.
+    pub tasks: Vec<ControlChartTask>,
+}
+
+impl ControlChart {
+    pub fn new(tasks: &[ClickUpTaskResponseBody], duration_policy: &dyn DurationPolicy) -> Self {
+        let mut completed: Vec<ControlChartTask> = tasks
+            .iter()
+            .filter_map(|task| {
+                let time_in_status = task.time_in_status.as_ref()?;
+                let timeline = status_timeline(time_in_status);
+
+                Some(ControlChartTask {
+                    id: task.id.clone(),
+                    number: task.custom_id.clone().unwrap_or_else(|| task.id.clone()),
+                    name: task.name.clone(),
+                    url: task.url.clone(),
+                    completed_at: completed_at(&timeline, time_in_status)?,
+                    cycle_time_days: cycle_time_days(&timeline, time_in_status, duration_policy)?,
+                    is_outlier: false,
+                })
+            })
+            .collect();
+        completed.sort_by_key(|t| t.completed_at);
+
+        let cycle_times: Vec<f32> = completed.iter().map(|t| t.cycle_time_days).collect();
+        let p95 = percentile(&cycle_times, 95.0);
+        for task in &mut completed {
+            task.is_outlier = p95.is_some_and(|p95| task.cycle_time_days > p95);
+        }
+
+        Self {
+            duration_policy: duration_policy.record(),
+            mean: mean(&cycle_times),
+            p50: percentile(&cycle_times, 50.0),
+            p85: percentile(&cycle_times, 85.0),
+            p95,
+            tasks: completed,
+        }
+    }
+}
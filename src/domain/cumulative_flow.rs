@@ -0,0 +1,147 @@
+use crate::{
+    domain::{
+        csv::csv_row,
+        duration_policy::{DurationPolicy, DurationPolicyRecord, FullTime},
+        timeline::{status_at, status_timeline},
+    },
+    services::clickup::ClickUpTaskResponseBody,
+};
+use chrono::{Days, NaiveDate, NaiveTime, Utc};
+use serde::Serialize;
+
+/// Number of tasks in each status at the end of each day in a date range.
+#[derive(Debug, Clone, Serialize)]
+pub struct CumulativeFlow {
+    /// Always [`FullTime`], since tasks are counted at the end of each
+    /// calendar day, whatever the time formula.
+    pub duration_policy: DurationPolicyRecord,
+    /// Statuses in workflow order.
+    pub statuses: Vec<String>,
+    pub days: Vec<CumulativeFlowDay>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct CumulativeFlowDay {
+    pub date: NaiveDate,
+    /// Task counts, in the same order as [`CumulativeFlow::statuses`].
+    pub counts: Vec<usize>,
+}
+
+impl CumulativeFlow {
+    pub fn new(tasks: &[ClickUpTaskResponseBody], from: NaiveDate, to: NaiveDate) -> Self {
+        let timelines: Vec<_> = tasks
+            .iter()
+            .filter_map(|t| t.time_in_status.as_ref())
+            .map(status_timeline)
+            .collect();
+
+        let mut statuses: Vec<(String, i32)> = vec![];
+        for period in timelines.iter().flatten() {
+            let order_index = period.order_index.unwrap_or(i32::MAX);
+            match statuses.iter_mut().find(|(s, _)| *s == period.status) {
+                Some((_, existing)) => *existing = (*existing).min(order_index),
+                None => statuses.push((period.status.clone(), order_index)),
+            }
+        }
+        statuses.sort_by_key(|(_, order_index)| *order_index);
+        let statuses: Vec<String> = statuses.into_iter().map(|(s, _)| s).collect();
+
+        let mut days = vec![];
+        let mut date = from;
+        while date <= to {
+            let end_of_day = date.and_time(NaiveTime::MIN).and_utc() + Days::new(1);
+            let end_of_day = end_of_day.min(Utc::now());
+
+            let mut counts = vec![0; statuses.len()];
+            for timeline in &timelines {
+                if let Some(period) = status_at(timeline, end_of_day)
+                    && let Some(i) = statuses.iter().position(|s| *s == period.status)
+                {
+                    counts[i] += 1;
+                }
+            }
+
+            days.push(CumulativeFlowDay { date, counts });
+            date = match date.succ_opt() {
+                Some(date) => date,
+                None => break,
+            };
+        }
+
+        Self {
+            duration_policy: FullTime.record(),
+            statuses,
+            days,
+        }
+    }
+
+    pub fn to_csv(&self) -> String {
+        let mut csv =
+            csv_row(std::iter::once("date").chain(self.statuses.iter().map(|s| s.as_str())));
+        for day in &self.days {
+            csv.push_str(&csv_row(
+                std::iter::once(day.date.to_string())
+                    .chain(day.counts.iter().map(|c| c.to_string())),
+            ));
+        }
+
+        csv
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::testing::{parse, task_json};
+
+    #[test]
+    fn counts_the_tasks_in_each_status_at_the_end_of_each_day() {
+        let tasks = [
+            parse(task_json(
+                "a",
+                1.0,
+                &[
+                    ("to do", "open", 24),
+                    ("in progress", "custom", 48),
+                    ("done", "done", 0),
+                ],
+            )),
+            parse(task_json(
+                "b",
+                1.0,
+                &[
+                    ("to do", "open", 48),
+                    ("in progress", "custom", 24),
+                    ("done", "done", 0),
+                ],
+            )),
+        ];
+        let date = |day| NaiveDate::from_ymd_opt(2025, 3, day).unwrap();
+
+        let cumulative_flow = CumulativeFlow::new(&tasks, date(3), date(6));
+
+        assert_eq!(cumulative_flow.statuses, ["to do", "in progress", "done"]);
+        let days: Vec<_> = cumulative_flow
+            .days
+            .iter()
+            .map(|d| (d.date, d.counts.clone()))
+            .collect();
+        assert_eq!(
+            days,
+            [
+                (date(3), vec![1, 1, 0]),
+                (date(4), vec![0, 2, 0]),
+                (date(5), vec![0, 0, 2]),
+                (date(6), vec![0, 0, 2]),
+            ]
+        );
+        assert_eq!(
+            cumulative_flow.to_csv(),
+            "date,to do,in progress,done\n\
+             2025-03-03,1,1,0\n\
+             2025-03-04,0,2,0\n\
+             2025-03-05,0,0,2\n\
+             2025-03-06,0,0,2\n"
+        );
+    }
+}
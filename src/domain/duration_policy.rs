@@ -0,0 +1,284 @@
+use crate::{
+    domain::working_hours::{WEEKDAYS, counted_minutes},
+    services::workspace_settings::{WorkCalendar, WorkingHours},
+};
+use chrono::{DateTime, Datelike, Utc};
+use chrono_tz::Tz;
+use serde::Serialize;
+use std::{collections::BTreeMap, fmt::Debug};
+
+/// Decides how much of the time between two instants counts towards a task's
+/// time in status.
+pub trait DurationPolicy: Debug + Send + Sync {
+    fn name(&self) -> &'static str;
+
+    /// Everything needed to rebuild the policy and reproduce its numbers.
+    fn parameters(&self) -> BTreeMap<&'static str, String>;
+
+    /// Minutes between `start` and `end` that count, scaled so that a counted
+    /// day is always 24 hours long.
+    fn minutes(&self, start: DateTime<Utc>, end: DateTime<Utc>) -> i64;
+
+    fn record(&self) -> DurationPolicyRecord {
+        DurationPolicyRecord {
+            name: self.name(),
+            parameters: self.parameters(),
+        }
+    }
+}
+
+/// The policy an analysis was run with.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct DurationPolicyRecord {
+    pub name: &'static str,
+    pub parameters: BTreeMap<&'static str, String>,
+}
+
+impl DurationPolicyRecord {
+    pub fn description(&self) -> String {
+        if self.parameters.is_empty() {
+            return self.name.to_string();
+        }
+
+        let parameters: Vec<String> = self
+            .parameters
+            .iter()
+            .map(|(key, value)| format!("{key}: {value}"))
+            .collect();
+        format!("{} ({})", self.name, parameters.join(", "))
+    }
+}
+
+/// Wall-clock time, nights and weekends included.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct FullTime;
+
+impl DurationPolicy for FullTime {
+    fn name(&self) -> &'static str {
+        "full_time"
+    }
+
+    fn parameters(&self) -> BTreeMap<&'static str, String> {
+        BTreeMap::new()
+    }
+
+    fn minutes(&self, start: DateTime<Utc>, end: DateTime<Utc>) -> i64 {
+        (end - start).num_minutes().max(0)
+    }
+}
+
+/// Whole days, Monday to Friday, in a time zone.
+#[derive(Debug, Clone, Copy)]
+pub struct BusinessDays {
+    pub time_zone: Tz,
+}
+
+impl DurationPolicy for BusinessDays {
+    fn name(&self) -> &'static str {
+        "business_days"
+    }
+
+    fn parameters(&self) -> BTreeMap<&'static str, String> {
+        BTreeMap::from([("time_zone", self.time_zone.name().to_string())])
+    }
+
+    fn minutes(&self, start: DateTime<Utc>, end: DateTime<Utc>) -> i64 {
+        counted_minutes(
+            start,
+            end,
+            self.time_zone,
+            |date| WEEKDAYS.contains(&date.weekday()),
+            None,
+        )
+    }
+}
+
+/// Only the team's working hours, Monday to Friday.
+#[derive(Debug, Clone, Copy)]
+pub struct WorkingTime {
+    pub working_hours: WorkingHours,
+}
+
+impl DurationPolicy for WorkingTime {
+    fn name(&self) -> &'static str {
+        "working_hours"
+    }
+
+    fn parameters(&self) -> BTreeMap<&'static str, String> {
+        working_hours_parameters(&self.working_hours)
+    }
+
+    fn minutes(&self, start: DateTime<Utc>, end: DateTime<Utc>) -> i64 {
+        counted_minutes(
+            start,
+            end,
+            self.working_hours.time_zone,
+            |date| WEEKDAYS.contains(&date.weekday()),
+            Some((self.working_hours.start, self.working_hours.end)),
+        )
+    }
+}
+
+/// The team's own working days and holidays, limited to its working hours
+/// when it has set them.
+#[derive(Debug, Clone)]
+pub struct CustomCalendar {
+    pub calendar: WorkCalendar,
+    pub working_hours: Option<WorkingHours>,
+}
+
+impl DurationPolicy for CustomCalendar {
+    fn name(&self) -> &'static str {
+        "custom_calendar"
+    }
+
+    fn parameters(&self) -> BTreeMap<&'static str, String> {
+        let mut parameters = match &self.working_hours {
+            Some(working_hours) => working_hours_parameters(working_hours),
+            None => BTreeMap::from([("time_zone", Tz::UTC.name().to_string())]),
+        };
+        let working_days: Vec<String> = self
+            .calendar
+            .working_days
+            .iter()
+            .map(|d| d.to_string())
+            .collect();
+        let holidays: Vec<String> = self
+            .calendar
+            .holidays
+            .iter()
+            .map(|d| d.to_string())
+            .collect();
+        parameters.insert("working_days", working_days.join(","));
+        parameters.insert("holidays", holidays.join(","));
+
+        parameters
+    }
+
+    fn minutes(&self, start: DateTime<Utc>, end: DateTime<Utc>) -> i64 {
+        counted_minutes(
+            start,
+            end,
+            match &self.working_hours {
+                Some(working_hours) => working_hours.time_zone,
+                None => Tz::UTC,
+            },
+            |date| {
+                self.calendar.working_days.contains(&date.weekday())
+                    && !self.calendar.holidays.contains(&date)
+            },
+            self.working_hours.map(|w| (w.start, w.end)),
+        )
+    }
+}
+
+fn working_hours_parameters(working_hours: &WorkingHours) -> BTreeMap<&'static str, String> {
+    BTreeMap::from([
+        ("start", working_hours.start.format("%H:%M").to_string()),
+        ("end", working_hours.end.format("%H:%M").to_string()),
+        ("time_zone", working_hours.time_zone.name().to_string()),
+    ])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::testing::at;
+    use chrono::Weekday;
+
+    const DAY: i64 = 24 * 60;
+
+    #[test]
+    fn working_time_counts_a_week_as_five_days() {
+        let policy = WorkingTime {
+            working_hours: WorkingHours::parse("09:00-17:00", "Europe/Paris").unwrap(),
+        };
+
+        assert_eq!(policy.minutes(at(0), at(7 * 24)), 5 * DAY);
+        // 09:00 to 13:00 in Paris, still on winter time.
+        assert_eq!(policy.minutes(at(8), at(12)), DAY / 2);
+        assert_eq!(
+            policy.record().description(),
+            "working_hours (end: 17:00, start: 09:00, time_zone: Europe/Paris)"
+        );
+    }
+
+    #[test]
+    fn business_days_end_at_midnight_in_their_time_zone() {
+        let policy = BusinessDays {
+            time_zone: chrono_tz::America::New_York,
+        };
+
+        // Friday 00:00 to Saturday 05:00 UTC is Thursday 19:00 to Saturday
+        // 00:00 in New York.
+        assert_eq!(policy.minutes(at(4 * 24), at(5 * 24 + 5)), DAY + 5 * 60);
+        // New York moves to summer time on Sunday, so its weekend ends at
+        // 04:00 UTC on Monday.
+        assert_eq!(policy.minutes(at(5 * 24 + 5), at(7 * 24 + 4)), 0);
+        assert_eq!(policy.minutes(at(5 * 24 + 5), at(7 * 24 + 5)), 60);
+        assert_eq!(
+            policy.parameters(),
+            BTreeMap::from([("time_zone", "America/New_York".to_string())])
+        );
+    }
+
+    #[test]
+    fn working_hours_need_a_known_time_zone_and_an_ordered_window() {
+        assert!(WorkingHours::parse("09:00-17:00", "UTC+1").is_none());
+        assert!(WorkingHours::parse("17:00-09:00", "UTC").is_none());
+        assert_eq!(
+            WorkingHours::parse(" 09:00 - 17:00", " Asia/Kolkata ").map(|w| w.time_zone),
+            Some(chrono_tz::Asia::Kolkata)
+        );
+    }
+
+    fn sunday_to_thursday(holidays: &[&str]) -> WorkCalendar {
+        WorkCalendar {
+            working_days: vec![
+                Weekday::Sun,
+                Weekday::Mon,
+                Weekday::Tue,
+                Weekday::Wed,
+                Weekday::Thu,
+            ],
+            holidays: holidays.iter().map(|d| d.parse().unwrap()).collect(),
+        }
+    }
+
+    #[test]
+    fn custom_calendar_skips_days_off_and_holidays() {
+        let policy = CustomCalendar {
+            calendar: sunday_to_thursday(&["2025-03-04"]),
+            working_hours: None,
+        };
+
+        // Monday to the next Monday, without Tuesday, Friday and Saturday.
+        assert_eq!(policy.minutes(at(0), at(7 * 24)), 4 * DAY);
+        // Thursday noon to Sunday noon.
+        assert_eq!(policy.minutes(at(3 * 24 + 12), at(6 * 24 + 12)), DAY);
+        assert_eq!(
+            policy.parameters(),
+            BTreeMap::from([
+                ("holidays", "2025-03-04".to_string()),
+                ("time_zone", "UTC".to_string()),
+                ("working_days", "Sun,Mon,Tue,Wed,Thu".to_string()),
+            ])
+        );
+    }
+
+    #[test]
+    fn custom_calendar_counts_working_hours_in_their_time_zone() {
+        let policy = CustomCalendar {
+            calendar: sunday_to_thursday(&["2025-03-05"]),
+            working_hours: WorkingHours::parse("08:00-16:00", "Asia/Jerusalem"),
+        };
+
+        // 08:00 in Jerusalem is 06:00 UTC, so a window runs 06:00-14:00 UTC.
+        assert_eq!(policy.minutes(at(6), at(14)), DAY);
+        assert_eq!(policy.minutes(at(10), at(24 + 10)), DAY);
+        // Wednesday is a holiday, Friday and Saturday are days off.
+        assert_eq!(policy.minutes(at(2 * 24), at(6 * 24)), DAY);
+        // Sunday 06:00 UTC starts the next working day.
+        assert_eq!(policy.minutes(at(6 * 24), at(6 * 24 + 10)), DAY / 2);
+    }
+}
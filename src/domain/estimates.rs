@@ -0,0 +1,157 @@
+use crate::services::{
+    clickup::{ClickUpCustomField, ClickUpTaskResponseBody},
+    workspace_settings::EstimateField,
+};
+use serde_json::Value;
+
+/// Field types whose value can be read as an estimate.
+pub const ESTIMATE_FIELD_TYPES: [&str; 4] = ["number", "drop_down", "currency", "emoji"];
+
+/// Replaces the sprint points of the task and all of its subtasks with the
+/// value of `estimate_field`. Tasks without the field set end up unestimated.
+pub fn apply_estimate_field(task: &mut ClickUpTaskResponseBody, estimate_field: &EstimateField) {
+    task.points = task
+        .custom_fields
+        .iter()
+        .find(|f| f.id == estimate_field.field_id)
+        .and_then(|f| custom_field_points(f, estimate_field));
+
+    if let Some(sub_tasks) = &mut task.sub_tasks {
+        for sub_task in sub_tasks {
+            if let Some(task) = &mut sub_task.task {
+                apply_estimate_field(task, estimate_field);
+                sub_task.points = task.points;
+            }
+        }
+    }
+}
+
+/// Reads a custom field's value as points. Dropdown values are the option's
+/// order index, which is mapped through the option's name.
+pub fn custom_field_points(
+    custom_field: &ClickUpCustomField,
+    estimate_field: &EstimateField,
+) -> Option<f32> {
+    let value = custom_field.value.as_ref()?;
+
+    if custom_field.field_type == "drop_down" {
+        let order_index = value_as_f64(value)? as i64;
+        let option = custom_field
+            .options()
+            .iter()
+            .find(|o| o.order_index == Some(order_index))?;
+
+        return estimate_field.option_points(option.name.as_deref()?);
+    }
+
+    value_as_f64(value).map(|v| v as f32)
+}
+
+/// ClickUp sends number field values as either JSON numbers or strings.
+fn value_as_f64(value: &Value) -> Option<f64> {
+    match value {
+        Value::Number(number) => number.as_f64(),
+        Value::String(string) => string.trim().parse().ok(),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::{
+        Task,
+        testing::{done_task, parse, sub_task_json, task_json},
+    };
+    use serde_json::json;
+    use std::collections::HashMap;
+
+    fn t_shirt_size(order_index: Option<i64>) -> Value {
+        json!({
+            "id": "size",
+            "name": "T-shirt size",
+            "type": "drop_down",
+            "type_config": {
+                "options": [
+                    { "id": "s", "name": "S", "orderindex": 0 },
+                    { "id": "m", "name": "M", "orderindex": 1 },
+                    { "id": "l", "name": "L", "orderindex": 2 },
+                    { "id": "8", "name": "8", "orderindex": 3 }
+                ]
+            },
+            "value": order_index
+        })
+    }
+
+    fn t_shirt_size_field() -> EstimateField {
+        EstimateField {
+            field_id: "size".to_string(),
+            field_name: "T-shirt size".to_string(),
+            option_points: HashMap::from([("s".to_string(), 1.0), ("m".to_string(), 3.0)]),
+        }
+    }
+
+    #[test]
+    fn maps_dropdown_options_through_their_names() {
+        let estimate_field = t_shirt_size_field();
+        let points = |value| {
+            let custom_field: ClickUpCustomField = serde_json::from_value(value).unwrap();
+            custom_field_points(&custom_field, &estimate_field)
+        };
+
+        assert_eq!(points(t_shirt_size(Some(0))), Some(1.0));
+        assert_eq!(points(t_shirt_size(Some(1))), Some(3.0));
+        // Unmapped options count if their name is a number.
+        assert_eq!(points(t_shirt_size(Some(2))), None);
+        assert_eq!(points(t_shirt_size(Some(3))), Some(8.0));
+        assert_eq!(points(t_shirt_size(Some(9))), None);
+        assert_eq!(points(t_shirt_size(None)), None);
+    }
+
+    #[test]
+    fn reads_numbers_sent_as_numbers_or_strings() {
+        let estimate_field = EstimateField {
+            field_id: "hours".to_string(),
+            field_name: "Estimate (h)".to_string(),
+            option_points: HashMap::new(),
+        };
+        let points = |value: Value| {
+            let custom_field: ClickUpCustomField = serde_json::from_value(json!({
+                "id": "hours",
+                "name": "Estimate (h)",
+                "type": "number",
+                "value": value
+            }))
+            .unwrap();
+            custom_field_points(&custom_field, &estimate_field)
+        };
+
+        assert_eq!(points(json!(2.5)), Some(2.5));
+        assert_eq!(points(json!(" 4 ")), Some(4.0));
+        assert_eq!(points(json!("four")), None);
+        assert_eq!(points(json!(null)), None);
+    }
+
+    #[test]
+    fn replaces_the_points_of_the_whole_tree() {
+        let mut sized = task_json("b", 5.0, &[("done", "done", 0)]);
+        sized["custom_fields"] = json!([t_shirt_size(Some(1))]);
+        let unsized_task = task_json("c", 5.0, &[("done", "done", 0)]);
+        let mut parent = task_json("a", 5.0, &[("done", "done", 0)]);
+        parent["custom_fields"] = json!([t_shirt_size(Some(0))]);
+        parent["subtasks"] = json!([sub_task_json(sized), sub_task_json(unsized_task)]);
+        let mut parent = parse(parent);
+
+        apply_estimate_field(&mut parent, &t_shirt_size_field());
+
+        assert_eq!(parent.points, Some(1.0));
+        let sub_tasks = parent.sub_tasks.as_ref().unwrap();
+        assert_eq!(sub_tasks[0].points, Some(3.0));
+        assert_eq!(sub_tasks[1].points, None);
+        assert_eq!(Task::from(parent).total_points, 4.0);
+
+        let mut task = done_task("d", 2.0, 1);
+        apply_estimate_field(&mut task, &t_shirt_size_field());
+        assert_eq!(task.points, None);
+    }
+}
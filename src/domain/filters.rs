@@ -0,0 +1,203 @@
+use crate::services::clickup::ClickUpTaskResponseBody;
+
+/// Priority name matched by tasks without a priority.
+pub const NO_PRIORITY: &str = "none";
+
+/// Include and exclude rules on tags, priorities and statuses. Names are
+/// matched case-insensitively, empty include lists match every task.
+#[derive(Debug, Clone, Default)]
+pub struct TaskFilter {
+    pub include_tags: Vec<String>,
+    pub exclude_tags: Vec<String>,
+    pub include_priorities: Vec<String>,
+    pub exclude_priorities: Vec<String>,
+    pub include_statuses: Vec<String>,
+    pub exclude_statuses: Vec<String>,
+}
+
+impl TaskFilter {
+    /// Splits a comma separated list of names, as used by the form, the
+    /// query string and the CLI.
+    pub fn parse_list(value: &str) -> Vec<String> {
+        value
+            .split(',')
+            .map(|name| name.trim().to_lowercase())
+            .filter(|name| !name.is_empty())
+            .collect()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.include_tags.is_empty()
+            && self.exclude_tags.is_empty()
+            && self.include_priorities.is_empty()
+            && self.exclude_priorities.is_empty()
+            && self.include_statuses.is_empty()
+            && self.exclude_statuses.is_empty()
+    }
+
+    pub fn matches(&self, task: &ClickUpTaskResponseBody) -> bool {
+        let tags: Vec<String> = task.tags.iter().map(|t| t.name.to_lowercase()).collect();
+        let priority = match &task.priority {
+            Some(priority) => priority.priority.to_lowercase(),
+            None => NO_PRIORITY.to_string(),
+        };
+        let status = task.status.as_ref().map(|s| s.status.to_lowercase());
+
+        let matches_rules = |include: &[String], exclude: &[String], values: &[&String]| {
+            (include.is_empty() || values.iter().any(|v| include.contains(v)))
+                && !values.iter().any(|v| exclude.contains(v))
+        };
+
+        matches_rules(
+            &self.include_tags,
+            &self.exclude_tags,
+            &tags.iter().collect::<Vec<_>>(),
+        ) && matches_rules(
+            &self.include_priorities,
+            &self.exclude_priorities,
+            &[&priority],
+        ) && matches_rules(
+            &self.include_statuses,
+            &self.exclude_statuses,
+            &status.iter().collect::<Vec<_>>(),
+        )
+    }
+}
+
+/// Drops subtasks that don't match `filter`, along with their own subtasks,
+/// so they are left out of the tree's rollups. The root task is always kept.
+pub fn filter_sub_tasks(task: &mut ClickUpTaskResponseBody, filter: &TaskFilter) {
+    if let Some(sub_tasks) = &mut task.sub_tasks {
+        sub_tasks.retain(|sub_task| match &sub_task.task {
+            Some(task) => filter.matches(task),
+            None => true,
+        });
+
+        for sub_task in sub_tasks {
+            if let Some(task) = &mut sub_task.task {
+                filter_sub_tasks(task, filter);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::{
+        AnalysisOptions, analyze_task,
+        testing::{parse, sub_task_json, task_json},
+    };
+    use serde_json::{Value, json};
+
+    fn done_task_json(id: &str, points: f32, days: i64) -> Value {
+        task_json(
+            id,
+            points,
+            &[
+                ("to do", "open", 24),
+                ("in progress", "custom", days * 24),
+                ("done", "done", 0),
+            ],
+        )
+    }
+
+    /// An epic of a day and a point, with 18 more points and 10 more days in
+    /// its subtasks.
+    fn epic() -> ClickUpTaskResponseBody {
+        let mut chore = done_task_json("chore", 2.0, 1);
+        chore["tags"] = json!([{ "name": "Chore" }]);
+        let mut feature = done_task_json("feature", 3.0, 2);
+        feature["tags"] = json!([{ "name": "feature" }]);
+        feature["priority"] = json!({ "priority": "high" });
+
+        let mut clean_up = done_task_json("clean-up", 5.0, 4);
+        clean_up["tags"] = json!([{ "name": "chore" }]);
+        clean_up["priority"] = json!({ "priority": "low" });
+
+        let cancelled = task_json(
+            "cancelled",
+            8.0,
+            &[
+                ("to do", "open", 24),
+                ("in progress", "custom", 72),
+                ("Won't do", "closed", 0),
+            ],
+        );
+
+        let mut epic = done_task_json("epic", 1.0, 1);
+        epic["subtasks"] = json!([
+            sub_task_json(feature),
+            sub_task_json(chore),
+            sub_task_json(clean_up),
+            sub_task_json(cancelled)
+        ]);
+        parse(epic)
+    }
+
+    fn rollups(task_filter: TaskFilter) -> (f32, i64) {
+        let task = analyze_task(
+            epic(),
+            &AnalysisOptions {
+                task_filter,
+                ..AnalysisOptions::default()
+            },
+        );
+        (task.total_points, task.total_time_in_dev_status)
+    }
+
+    #[test]
+    fn filtered_out_subtasks_leave_the_rollups() {
+        assert_eq!(rollups(TaskFilter::default()), (19.0, 11));
+        assert_eq!(
+            rollups(TaskFilter {
+                exclude_tags: TaskFilter::parse_list("chore"),
+                ..TaskFilter::default()
+            }),
+            (12.0, 6)
+        );
+        assert_eq!(
+            rollups(TaskFilter {
+                exclude_statuses: TaskFilter::parse_list("won't do"),
+                ..TaskFilter::default()
+            }),
+            (11.0, 8)
+        );
+    }
+
+    #[test]
+    fn include_rules_keep_only_matching_subtasks() {
+        // The chore has no priority, so it goes too.
+        assert_eq!(
+            rollups(TaskFilter {
+                include_priorities: TaskFilter::parse_list("high"),
+                ..TaskFilter::default()
+            }),
+            (4.0, 3)
+        );
+        assert_eq!(
+            rollups(TaskFilter {
+                include_tags: TaskFilter::parse_list("feature, chore"),
+                exclude_priorities: TaskFilter::parse_list("low"),
+                ..TaskFilter::default()
+            }),
+            (6.0, 4)
+        );
+    }
+
+    #[test]
+    fn tasks_without_a_priority_match_none() {
+        let task = parse(done_task_json("a", 1.0, 1));
+
+        let filter = |priorities: &str| TaskFilter {
+            include_priorities: TaskFilter::parse_list(priorities),
+            ..TaskFilter::default()
+        };
+        assert!(filter("None").matches(&task));
+        assert!(!filter("urgent,high").matches(&task));
+        assert_eq!(
+            TaskFilter::parse_list(" Chore, ,Won't Do "),
+            ["chore", "won't do"]
+        );
+    }
+}
@@ -0,0 +1,280 @@
+use crate::{
+    domain::{
+        duration_policy::{DurationPolicy, DurationPolicyRecord},
+        timeline::{StatusPeriod, minutes_to_days, status_timeline},
+    },
+    services::{
+        clickup::ClickUpTaskResponseBody,
+        workspace_settings::{StatusCategory, WorkspaceSettings},
+    },
+};
+use serde::Serialize;
+
+/// Decides whether time in a status counts as active work, waiting, or isn't
+/// part of the cycle at all.
+///
+/// Statuses of type `open`, `done` and `closed` are outside the cycle. Other
+/// statuses use the workspace's tag, and untagged in progress statuses are
+/// treated as active.
+pub fn status_category(
+    period: &StatusPeriod,
+    settings: &WorkspaceSettings,
+) -> Option<StatusCategory> {
+    if period.is_completed() || period.status_type == "open" {
+        return None;
+    }
+
+    match settings.status_category(&period.status) {
+        Some(category) => Some(category),
+        None if period.is_in_progress() => Some(StatusCategory::Active),
+        None => None,
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct TaskFlowEfficiency {
+    pub id: String,
+    pub number: String,
+    pub name: String,
+    pub url: String,
+    pub active_days: f32,
+    pub waiting_days: f32,
+    /// Active time as a percentage of the cycle time.
+    pub efficiency: Option<f32>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct WaitingStatusDelay {
+    pub status: String,
+    pub days: f32,
+    /// Percentage of all waiting time spent in this status.
+    pub share: f32,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct CategorizedStatus {
+    pub status: String,
+    pub category: Option<StatusCategory>,
+    /// Whether the category was set for the workspace rather than defaulted.
+    pub is_tagged: bool,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct FlowEfficiency {
+    /// How time in each status was counted.
+    pub duration_policy: DurationPolicyRecord,
+    pub active_days: f32,
+    pub waiting_days: f32,
+    pub efficiency: Option<f32>,
+    /// Largest delay first.
+    pub waiting_statuses: Vec<WaitingStatusDelay>,
+    pub statuses: Vec<CategorizedStatus>,
+    pub tasks: Vec<TaskFlowEfficiency>,
+}
+
+impl FlowEfficiency {
+    pub fn new(
+        tasks: &[ClickUpTaskResponseBody],
+        settings: &WorkspaceSettings,
+        duration_policy: &dyn DurationPolicy,
+    ) -> Self {
+        let mut statuses: Vec<CategorizedStatus> = vec![];
+        let mut waiting_statuses: Vec<WaitingStatusDelay> = vec![];
+        let mut task_flow_efficiencies = vec![];
+
+        for task in tasks {
+            let Some(time_in_status) = &task.time_in_status else {
+                continue;
+            };
+
+            let mut active_minutes = 0;
+            let mut waiting_minutes = 0;
+            for period in status_timeline(time_in_status) {
+                let category = status_category(&period, settings);
+                let minutes = duration_policy.minutes(period.start, period.end);
+
+                if !period.is_completed()
+                    && period.status_type != "open"
+                    && !statuses.iter().any(|s| s.status == period.status)
+                {
+                    statuses.push(CategorizedStatus {
+                        status: period.status.clone(),
+                        category,
+                        is_tagged: settings.status_category(&period.status).is_some(),
+                    });
+                }
+
+                match category {
+                    Some(StatusCategory::Active) => active_minutes += minutes,
+                    Some(StatusCategory::Waiting) => {
+                        waiting_minutes += minutes;
+                        match waiting_statuses
+                            .iter_mut()
+                            .find(|w| w.status == period.status)
+                        {
+                            Some(waiting_status) => waiting_status.days += minutes_to_days(minutes),
+                            None => waiting_statuses.push(WaitingStatusDelay {
+                                status: period.status.clone(),
+                                days: minutes_to_days(minutes),
+                                share: 0.0,
+                            }),
+                        }
+                    }
+                    None => {}
+                }
+            }
+
+            task_flow_efficiencies.push(TaskFlowEfficiency {
+                id: task.id.clone(),
+                number: task.custom_id.clone().unwrap_or_else(|| task.id.clone()),
+                name: task.name.clone(),
+                url: task.url.clone(),
+                active_days: minutes_to_days(active_minutes),
+                waiting_days: minutes_to_days(waiting_minutes),
+                efficiency: efficiency(active_minutes as f32, waiting_minutes as f32),
+            });
+        }
+
+        let active_days: f32 = task_flow_efficiencies.iter().map(|t| t.active_days).sum();
+        let waiting_days: f32 = task_flow_efficiencies.iter().map(|t| t.waiting_days).sum();
+
+        for waiting_status in &mut waiting_statuses {
+            waiting_status.share = if waiting_days > 0.0 {
+                waiting_status.days / waiting_days * 100.0
+            } else {
+                0.0
+            };
+        }
+        waiting_statuses.sort_by(|a, b| b.days.total_cmp(&a.days));
+
+        Self {
+            duration_policy: duration_policy.record(),
+            active_days,
+            waiting_days,
+            efficiency: efficiency(active_days, waiting_days),
+            waiting_statuses,
+            statuses,
+            tasks: task_flow_efficiencies,
+        }
+    }
+}
+
+fn efficiency(active: f32, waiting: f32) -> Option<f32> {
+    let total = active + waiting;
+    if total > 0.0 {
+        Some(active / total * 100.0)
+    } else {
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::{
+        duration_policy::FullTime,
+        testing::{parse, task_json},
+    };
+
+    #[test]
+    fn splits_the_cycle_into_active_and_waiting_time() {
+        let tasks = [
+            parse(task_json(
+                "a",
+                1.0,
+                &[
+                    ("to do", "open", 24),
+                    ("in progress", "custom", 48),
+                    ("ready for qa", "custom", 24),
+                    ("qa", "custom", 24),
+                    ("done", "done", 0),
+                ],
+            )),
+            parse(task_json(
+                "b",
+                1.0,
+                &[
+                    ("to do", "open", 24),
+                    ("blocked", "custom", 72),
+                    ("in progress", "custom", 24),
+                    ("done", "done", 0),
+                ],
+            )),
+        ];
+        let mut settings = WorkspaceSettings::default();
+        settings
+            .status_categories
+            .insert("ready for qa".to_string(), StatusCategory::Waiting);
+        settings
+            .status_categories
+            .insert("blocked".to_string(), StatusCategory::Waiting);
+
+        let flow_efficiency = FlowEfficiency::new(&tasks, &settings, &FullTime);
+
+        let a = &flow_efficiency.tasks[0];
+        assert_eq!((a.active_days, a.waiting_days), (3.0, 1.0));
+        assert_eq!(a.efficiency, Some(75.0));
+        let b = &flow_efficiency.tasks[1];
+        assert_eq!((b.active_days, b.waiting_days), (1.0, 3.0));
+        assert_eq!(b.efficiency, Some(25.0));
+
+        assert_eq!(flow_efficiency.active_days, 4.0);
+        assert_eq!(flow_efficiency.waiting_days, 4.0);
+        assert_eq!(flow_efficiency.efficiency, Some(50.0));
+
+        let waiting: Vec<_> = flow_efficiency
+            .waiting_statuses
+            .iter()
+            .map(|w| (w.status.as_str(), w.days, w.share))
+            .collect();
+        assert_eq!(
+            waiting,
+            [("blocked", 3.0, 75.0), ("ready for qa", 1.0, 25.0)]
+        );
+
+        // Open and done statuses are outside the cycle.
+        let statuses: Vec<_> = flow_efficiency
+            .statuses
+            .iter()
+            .map(|s| (s.status.as_str(), s.category, s.is_tagged))
+            .collect();
+        assert_eq!(
+            statuses,
+            [
+                ("in progress", Some(StatusCategory::Active), false),
+                ("ready for qa", Some(StatusCategory::Waiting), true),
+                ("qa", Some(StatusCategory::Active), false),
+                ("blocked", Some(StatusCategory::Waiting), true),
+            ]
+        );
+    }
+
+    #[test]
+    fn tags_override_the_default_and_ignore_case() {
+        let task = parse(task_json(
+            "a",
+            1.0,
+            &[("in progress", "custom", 24), ("done", "done", 0)],
+        ));
+        let mut settings = WorkspaceSettings::default();
+        settings
+            .status_categories
+            .insert("in progress".to_string(), StatusCategory::Waiting);
+
+        let timeline = status_timeline(task.time_in_status.as_ref().unwrap());
+        let mut in_progress = timeline[0].clone();
+        in_progress.status = "In Progress".to_string();
+
+        assert_eq!(
+            status_category(&in_progress, &settings),
+            Some(StatusCategory::Waiting)
+        );
+        assert_eq!(
+            status_category(&in_progress, &WorkspaceSettings::default()),
+            Some(StatusCategory::Active)
+        );
+        assert_eq!(status_category(&timeline[1], &settings), None);
+        let flow_efficiency = FlowEfficiency::new(&[task], &settings, &FullTime);
+        assert_eq!(flow_efficiency.efficiency, Some(0.0));
+    }
+}
@@ -0,0 +1,657 @@
+#![allow(dead_code)]
+
+pub mod aging_wip;
+pub mod assignees;
+pub mod control_chart;
+pub mod csv;
+pub mod cumulative_flow;
+pub mod duration_policy;
+pub mod estimates;
+pub mod filters;
+pub mod flow_efficiency;
+pub mod offline;
+pub mod outlier_reasons;
+pub mod sprints;
+pub mod statistics;
+#[cfg(test)]
+pub(crate) mod testing;
+pub mod timeline;
+pub mod transitions;
+pub mod velocity;
+pub mod working_hours;
+
+use crate::{
+    domain::{
+        duration_policy::{DurationPolicy, DurationPolicyRecord, FullTime},
+        estimates::apply_estimate_field,
+        filters::{TaskFilter, filter_sub_tasks},
+        outlier_reasons::{OutlierReason, explain_outliers},
+        timeline::{completed_at, current_period, status_timeline, work_started_at},
+        transitions::TaskTransitions,
+    },
+    services::{
+        clickup::{
+            ClickUpTaskResponseBody, IN_PROGRESS_ORDER_INDEX, SubTask, SubTaskLoadError, TotalTime,
+        },
+        workspace_settings::{EstimateField, StatusCategory},
+    },
+};
+use chrono::{DateTime, TimeDelta, Utc};
+use serde::Serialize;
+use std::{collections::HashMap, sync::Arc};
+
+/// Multiple of the tree's median days per point past which a task is
+/// flagged as an outlier, in either direction.
+pub const OUTLIER_FACTOR: f32 = 2.0;
+
+/// Hours logged per day in a development status below which a task is
+/// flagged as under-tracked.
+pub const MIN_TRACKED_HOURS_PER_DEV_DAY: f32 = 1.0;
+
+/// Multiple of the time estimate past which logged time is flagged.
+pub const OVER_ESTIMATE_FACTOR: f32 = 1.5;
+
+const MILLISECONDS_PER_HOUR: f32 = 3_600_000.0;
+
+#[derive(Debug, Clone, Serialize)]
+pub struct Task {
+    pub id: String,
+    pub number: String,
+    pub name: String,
+    pub url: String,
+    pub date_created: DateTime<Utc>,
+    pub points: f32,
+    pub total_points: f32,
+    pub time_in_dev_status: i64,
+    pub total_time_in_dev_status: i64,
+    pub time_estimate_hours: Option<f32>,
+    pub total_time_estimate_hours: f32,
+    /// Read from the task's `time_spent`, which is ClickUp's own total of
+    /// every time entry on the task, by anyone. ClickUp's time entries
+    /// endpoint is per workspace, needs a date range and only returns the
+    /// caller's own entries unless told otherwise, so fetching the entries
+    /// would cost extra requests to arrive at the same total.
+    pub time_tracked_hours: f32,
+    pub total_time_tracked_hours: f32,
+    pub status_times: Vec<StatusTime>,
+    pub rework_loops: usize,
+    /// Times the task was taken back out of a completed status.
+    pub reopened_count: usize,
+    pub work_started_at: Option<DateTime<Utc>>,
+    pub is_completed: bool,
+    pub completed_at: Option<DateTime<Utc>>,
+    pub is_in_progress: bool,
+    pub assignees: Vec<Assignee>,
+    pub outlier: Option<Outlier>,
+    /// Why the task was slower than the norm, empty unless it was.
+    pub outlier_reasons: Vec<OutlierReason>,
+    pub time_discrepancy: Option<TimeDiscrepancy>,
+    /// How durations were counted, set on the root of an analysis.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub duration_policy: Option<DurationPolicyRecord>,
+    /// Why the task wasn't fetched, e.g. `could not load: 403`. Such a task
+    /// only has the id, name and creation date its parent listed, and counts
+    /// for nothing.
+    pub load_error: Option<String>,
+    /// Whether the task or any of its descendants wasn't fetched, so the
+    /// totals are missing their share. Tasks already counted elsewhere in the
+    /// tree don't make it incomplete.
+    pub is_incomplete: bool,
+    pub sub_tasks: Vec<Task>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Assignee {
+    pub id: i64,
+    pub name: String,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct StatusTime {
+    pub status: String,
+    pub status_type: String,
+    pub minutes: i64,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Outlier {
+    SlowerThanNorm,
+    FasterThanNorm,
+    Unestimated,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TimeDiscrepancy {
+    /// Far less time was logged than the task spent in development.
+    UnderTracked,
+    /// More time was logged than estimated, past [`OVER_ESTIMATE_FACTOR`].
+    OverEstimate,
+}
+
+impl From<ClickUpTaskResponseBody> for Task {
+    fn from(value: ClickUpTaskResponseBody) -> Self {
+        Task::new(value, &FullTime)
+    }
+}
+
+impl Task {
+    /// Builds the task tree, counting the time in each status with
+    /// `duration_policy`.
+    pub fn new(value: ClickUpTaskResponseBody, duration_policy: &dyn DurationPolicy) -> Self {
+        let time_in_dev_status = get_days_in_dev_status(&value, duration_policy);
+        let transitions = TaskTransitions::for_task(&value);
+
+        let total_time_in_dev_status = match &value.sub_tasks {
+            Some(sub_tasks) => sub_tasks.iter().fold(0, |acc, t| {
+                if let Some(task) = &t.task {
+                    return acc + get_days_in_dev_status(task, duration_policy);
+                }
+
+                acc
+            }),
+            None => 0,
+        } + time_in_dev_status;
+
+        let sub_tasks: Vec<Task> = match value.sub_tasks {
+            Some(sub_tasks) => sub_tasks
+                .iter()
+                .filter_map(|t| {
+                    if let Some(sub_task) = &t.task {
+                        return Some(Task::new(sub_task.to_owned(), duration_policy));
+                    }
+                    if let Some(load_error) = &t.load_error {
+                        return Some(Task::unloaded(t, load_error));
+                    }
+
+                    None
+                })
+                .collect(),
+            None => vec![],
+        };
+
+        let points = value.points.unwrap_or_default();
+
+        let total_points = sub_tasks.iter().fold(points, |acc, t| acc + t.total_points);
+
+        let time_estimate_hours = value
+            .time_estimate
+            .map(|ms| ms as f32 / MILLISECONDS_PER_HOUR);
+        let total_time_estimate_hours = sub_tasks
+            .iter()
+            .fold(time_estimate_hours.unwrap_or_default(), |acc, t| {
+                acc + t.total_time_estimate_hours
+            });
+
+        let time_tracked_hours =
+            value.time_spent.unwrap_or_default() as f32 / MILLISECONDS_PER_HOUR;
+        let total_time_tracked_hours = sub_tasks.iter().fold(time_tracked_hours, |acc, t| {
+            acc + t.total_time_tracked_hours
+        });
+
+        let status_times = match &value.time_in_status {
+            Some(time_in_status) => time_in_status
+                .status_history
+                .iter()
+                .map(|s| StatusTime {
+                    status: s.status.clone(),
+                    status_type: s.status_type.clone(),
+                    minutes: status_minutes(&s.total_time, duration_policy),
+                })
+                .collect(),
+            None => vec![],
+        };
+
+        let (rework_loops, reopened_count) = match transitions {
+            Some(transitions) => {
+                let reopened_count = transitions
+                    .transitions
+                    .iter()
+                    .filter(|t| t.is_reopen)
+                    .count();
+
+                (transitions.rework_loops, reopened_count)
+            }
+            None => (0, 0),
+        };
+
+        let (is_completed, completed_at, is_in_progress, work_started_at) =
+            match &value.time_in_status {
+                Some(time_in_status) => {
+                    let timeline = status_timeline(time_in_status);
+                    match current_period(&timeline, time_in_status) {
+                        Some(current) => (
+                            current.is_completed(),
+                            completed_at(&timeline, time_in_status),
+                            current.is_in_progress(),
+                            work_started_at(&timeline),
+                        ),
+                        None => (false, None, false, work_started_at(&timeline)),
+                    }
+                }
+                None => (false, None, false, None),
+            };
+
+        let assignees = value
+            .assignees
+            .iter()
+            .map(|user| Assignee {
+                id: user.id,
+                name: user.display_name(),
+            })
+            .collect();
+
+        let number = match value.custom_id {
+            Some(number) => number,
+            None => value.id.clone(),
+        };
+
+        Self {
+            id: value.id,
+            number,
+            name: value.name,
+            url: value.url,
+            date_created: value.date_created,
+            points,
+            total_points,
+            time_in_dev_status,
+            total_time_in_dev_status,
+            time_estimate_hours,
+            total_time_estimate_hours,
+            time_tracked_hours,
+            total_time_tracked_hours,
+            status_times,
+            rework_loops,
+            reopened_count,
+            work_started_at,
+            is_completed,
+            completed_at,
+            is_in_progress,
+            assignees,
+            outlier: None,
+            outlier_reasons: vec![],
+            time_discrepancy: None,
+            duration_policy: None,
+            load_error: None,
+            is_incomplete: sub_tasks.iter().any(|t| t.is_incomplete),
+            sub_tasks,
+        }
+    }
+
+    /// Stands in for a sub task that wasn't fetched, so the tree still
+    /// shows it.
+    fn unloaded(value: &SubTask, load_error: &SubTaskLoadError) -> Self {
+        Self {
+            id: value.id.clone(),
+            number: match &value.custom_id {
+                Some(number) => number.clone(),
+                None => value.id.clone(),
+            },
+            name: value.name.clone(),
+            url: format!("https://app.clickup.com/t/{}", value.id),
+            date_created: value.date_created,
+            points: 0.0,
+            total_points: 0.0,
+            time_in_dev_status: 0,
+            total_time_in_dev_status: 0,
+            time_estimate_hours: None,
+            total_time_estimate_hours: 0.0,
+            time_tracked_hours: 0.0,
+            total_time_tracked_hours: 0.0,
+            status_times: vec![],
+            rework_loops: 0,
+            reopened_count: 0,
+            work_started_at: None,
+            is_completed: false,
+            completed_at: None,
+            is_in_progress: false,
+            assignees: vec![],
+            outlier: None,
+            outlier_reasons: vec![],
+            time_discrepancy: None,
+            duration_policy: None,
+            load_error: Some(load_error.to_string()),
+            is_incomplete: load_error.leaves_tree_incomplete(),
+            sub_tasks: vec![],
+        }
+    }
+}
+
+/// How a fetched task tree is turned into a [`Task`] analysis.
+#[derive(Debug, Clone)]
+pub struct AnalysisOptions {
+    pub duration_policy: Arc<dyn DurationPolicy>,
+    pub task_filter: TaskFilter,
+    /// Sprint points are used as the estimate when unset.
+    pub estimate_field: Option<EstimateField>,
+    /// Keyed by lowercase status name, used to call out waiting time.
+    pub status_categories: HashMap<String, StatusCategory>,
+}
+
+impl Default for AnalysisOptions {
+    fn default() -> Self {
+        Self {
+            duration_policy: Arc::new(FullTime),
+            task_filter: TaskFilter::default(),
+            estimate_field: None,
+            status_categories: HashMap::new(),
+        }
+    }
+}
+
+/// Filters the task tree, reads its estimates and flags anything that looks
+/// off, ready to be rendered.
+pub fn analyze_task(mut task: ClickUpTaskResponseBody, options: &AnalysisOptions) -> Task {
+    filter_sub_tasks(&mut task, &options.task_filter);
+    if let Some(estimate_field) = &options.estimate_field {
+        apply_estimate_field(&mut task, estimate_field);
+    }
+
+    let mut task = Task::new(task, options.duration_policy.as_ref());
+    task.duration_policy = Some(options.duration_policy.record());
+    flag_outliers(&mut task);
+    explain_outliers(&mut task, &options.status_categories);
+    flag_time_discrepancies(&mut task);
+
+    task
+}
+
+impl Task {
+    /// Returns this task followed by all of its descendants, depth first.
+    pub fn flatten(&self) -> Vec<&Task> {
+        let mut tasks = vec![self];
+        for sub_task in &self.sub_tasks {
+            tasks.extend(sub_task.flatten());
+        }
+
+        tasks
+    }
+
+    pub fn days_per_point(&self) -> Option<f32> {
+        if self.points > 0.0 {
+            Some(self.time_in_dev_status as f32 / self.points)
+        } else {
+            None
+        }
+    }
+}
+
+/// Flags tasks whose days per point is more than [`OUTLIER_FACTOR`] away from
+/// the median of the tree, and tasks that took time without an estimate.
+pub fn flag_outliers(task: &mut Task) {
+    fn collect_days_per_point(task: &Task, days_per_point: &mut Vec<f32>) {
+        if let Some(ratio) = task.days_per_point() {
+            days_per_point.push(ratio);
+        }
+
+        for sub_task in &task.sub_tasks {
+            collect_days_per_point(sub_task, days_per_point);
+        }
+    }
+
+    fn flag_outliers_iter(task: &mut Task, median: Option<f32>) {
+        task.outlier = match (task.days_per_point(), median) {
+            (None, _) if task.time_in_dev_status > 0 => Some(Outlier::Unestimated),
+            (Some(ratio), Some(median)) if median > 0.0 && ratio > median * OUTLIER_FACTOR => {
+                Some(Outlier::SlowerThanNorm)
+            }
+            (Some(ratio), Some(median)) if ratio < median / OUTLIER_FACTOR => {
+                Some(Outlier::FasterThanNorm)
+            }
+            _ => None,
+        };
+
+        for sub_task in &mut task.sub_tasks {
+            flag_outliers_iter(sub_task, median);
+        }
+    }
+
+    let mut days_per_point = vec![];
+    collect_days_per_point(task, &mut days_per_point);
+    days_per_point.sort_by(|a, b| a.total_cmp(b));
+    let median = days_per_point.get(days_per_point.len() / 2).copied();
+
+    flag_outliers_iter(task, median);
+}
+
+/// Flags tasks whose logged time doesn't line up with their time in a
+/// development status or with their time estimate.
+pub fn flag_time_discrepancies(task: &mut Task) {
+    let dev_days = task.time_in_dev_status as f32;
+    task.time_discrepancy = match task.time_estimate_hours {
+        _ if dev_days >= 1.0
+            && task.time_tracked_hours < dev_days * MIN_TRACKED_HOURS_PER_DEV_DAY =>
+        {
+            Some(TimeDiscrepancy::UnderTracked)
+        }
+        Some(estimate) if task.time_tracked_hours > estimate * OVER_ESTIMATE_FACTOR => {
+            Some(TimeDiscrepancy::OverEstimate)
+        }
+        _ => None,
+    };
+
+    for sub_task in &mut task.sub_tasks {
+        flag_time_discrepancies(sub_task);
+    }
+}
+
+pub fn generate_points_vs_time_spent_analysis(task: &Task) -> String {
+    fn generate_points_vs_time_spent_analysis_iter(task: &Task, mut prefix: String) -> String {
+        if let Some(load_error) = &task.load_error {
+            return format!("\n{prefix}{} {} - {load_error}", task.number, task.name);
+        }
+
+        let mut result = format!(
+            "\n{prefix}{} {} - points: {} ({}), time_spent: {} ({}){}",
+            task.number,
+            task.name,
+            task.points,
+            task.total_points,
+            task.time_in_dev_status,
+            task.total_time_in_dev_status,
+            if task.is_incomplete {
+                " incomplete"
+            } else {
+                ""
+            },
+        );
+
+        for reason in &task.outlier_reasons {
+            result.push_str(&format!("\n{prefix}  - {}", reason.description()));
+        }
+
+        prefix.push('\t');
+
+        for sub_task in &task.sub_tasks {
+            let nested_result =
+                generate_points_vs_time_spent_analysis_iter(sub_task, prefix.clone());
+            result.push_str(&nested_result);
+        }
+
+        result
+    }
+
+    let result = generate_points_vs_time_spent_analysis_iter(task, "".to_string());
+    match &task.duration_policy {
+        Some(duration_policy) => format!("Durations: {}{result}", duration_policy.description()),
+        None => result,
+    }
+}
+
+fn status_minutes(total_time: &TotalTime, duration_policy: &dyn DurationPolicy) -> i64 {
+    duration_policy.minutes(
+        total_time.since,
+        total_time.since + TimeDelta::minutes(total_time.by_minute),
+    )
+}
+
+fn get_days_in_dev_status(
+    task: &ClickUpTaskResponseBody,
+    duration_policy: &dyn DurationPolicy,
+) -> i64 {
+    let Some(time_in_status) = &task.time_in_status else {
+        return 0;
+    };
+
+    time_in_status
+        .status_history
+        .iter()
+        .filter_map(|s| match &s.order_index {
+            Some(order_index) => {
+                if *order_index >= IN_PROGRESS_ORDER_INDEX {
+                    Some(status_minutes(&s.total_time, duration_policy) / 60 / 24)
+                } else {
+                    None
+                }
+            }
+            None => None,
+        })
+        .reduce(|acc, n| acc + n)
+        .unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::services::clickup::ClickUpServiceError;
+    use serde_json::json;
+
+    /// A task two days in progress with `points`, an hour tracked and two
+    /// estimated, listing `sub_task_ids`.
+    fn task(id: &str, points: f32, sub_task_ids: &[&str]) -> ClickUpTaskResponseBody {
+        let sub_tasks: Vec<_> = sub_task_ids
+            .iter()
+            .map(|sub_task_id| {
+                json!({
+                    "id": sub_task_id,
+                    "custom_id": null,
+                    "name": format!("Task {sub_task_id}"),
+                    "points": 8,
+                    "date_created": "1740960000000"
+                })
+            })
+            .collect();
+
+        serde_json::from_value(json!({
+            "id": id,
+            "custom_id": null,
+            "name": format!("Task {id}"),
+            "url": format!("https://app.clickup.com/t/{id}"),
+            "text_content": "",
+            "description": "",
+            "points": points,
+            "time_estimate": 7_200_000,
+            "time_spent": 3_600_000,
+            "date_created": "1740960000000",
+            "subtasks": (!sub_tasks.is_empty()).then_some(sub_tasks),
+            "priority": null,
+            "status": null,
+            "time_in_status": {
+                "current_status": {
+                    "status": "in progress",
+                    "total_time": { "by_minute": 2880, "since": "1741046400000" }
+                },
+                "status_history": [
+                    {
+                        "status": "in progress",
+                        "type": "custom",
+                        "orderindex": IN_PROGRESS_ORDER_INDEX,
+                        "total_time": { "by_minute": 2880, "since": "1741046400000" }
+                    }
+                ]
+            }
+        }))
+        .unwrap()
+    }
+
+    /// `parent` with its sub tasks filled in from `loaded`, and every other
+    /// sub task marked with `load_error`.
+    fn tree(
+        mut parent: ClickUpTaskResponseBody,
+        loaded: Vec<ClickUpTaskResponseBody>,
+        load_error: SubTaskLoadError,
+    ) -> ClickUpTaskResponseBody {
+        for sub_task in parent.sub_tasks.iter_mut().flatten() {
+            match loaded.iter().find(|t| t.id == sub_task.id) {
+                Some(task) => sub_task.task = Some(task.clone()),
+                None => sub_task.load_error = Some(load_error.clone()),
+            }
+        }
+
+        parent
+    }
+
+    #[test]
+    fn unloaded_sub_tasks_mark_every_ancestor_incomplete() {
+        let load_error = ClickUpServiceError::UnexpectedStatus(403, String::new()).load_error();
+        let child = tree(task("child", 2.0, &["hidden"]), vec![], load_error.clone());
+        let root = tree(
+            task("root", 1.0, &["child", "deleted"]),
+            vec![child],
+            load_error,
+        );
+
+        let task = Task::from(root);
+
+        assert!(task.is_incomplete);
+        assert_eq!(task.load_error, None);
+        let child = &task.sub_tasks[0];
+        assert!(child.is_incomplete);
+        assert_eq!(child.load_error, None);
+        let deleted = &task.sub_tasks[1];
+        assert!(deleted.is_incomplete);
+        assert_eq!(deleted.load_error.as_deref(), Some("could not load: 403"));
+        assert_eq!(deleted.name, "Task deleted");
+        assert_eq!(
+            child.sub_tasks[0].load_error.as_deref(),
+            Some("could not load: 403")
+        );
+    }
+
+    #[test]
+    fn unloaded_sub_tasks_count_for_nothing_in_the_rollups() {
+        let load_error = SubTaskLoadError::NotFetched("not loaded".to_string());
+        let child = tree(task("child", 2.0, &["hidden"]), vec![], load_error.clone());
+        let root = tree(
+            task("root", 1.0, &["child", "skipped"]),
+            vec![child],
+            load_error,
+        );
+
+        let task = Task::from(root);
+
+        // The unloaded sub tasks listed 8 points each, which aren't counted.
+        assert_eq!(task.total_points, 3.0);
+        assert_eq!(task.total_time_estimate_hours, 4.0);
+        assert_eq!(task.total_time_tracked_hours, 2.0);
+        assert_eq!(task.total_time_in_dev_status, 4);
+        let skipped = &task.sub_tasks[1];
+        assert_eq!(skipped.points, 0.0);
+        assert_eq!(skipped.total_points, 0.0);
+        assert_eq!(skipped.time_estimate_hours, None);
+        assert_eq!(skipped.days_per_point(), None);
+    }
+
+    #[test]
+    fn sub_tasks_already_in_the_tree_are_not_missing() {
+        let child = task("child", 2.0, &[]);
+        let mut root = tree(
+            task("root", 1.0, &["child", "child again"]),
+            vec![child],
+            SubTaskLoadError::AlreadyInTree,
+        );
+        root.sub_tasks.as_mut().unwrap()[1].id = "child".to_string();
+
+        let task = Task::from(root);
+
+        assert!(!task.is_incomplete);
+        assert_eq!(task.total_points, 3.0);
+        let duplicate = &task.sub_tasks[1];
+        assert!(!duplicate.is_incomplete);
+        assert_eq!(
+            duplicate.load_error.as_deref(),
+            Some("not loaded, already in the tree")
+        );
+    }
+}
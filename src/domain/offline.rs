@@ -0,0 +1,421 @@
+use crate::services::clickup::{
+    ClickUpBulkTimeInStatusResponseBody, ClickUpTaskResponseBody, ClickUpTimeInStatusResponseBody,
+};
+use serde_json::Value;
+use std::{
+    collections::{HashMap, HashSet},
+    io::Read,
+    path::Path,
+};
+
+/// Suffix of files holding the time in status of the task named before it,
+/// e.g. `86abc123.time_in_status.json`.
+pub const TIME_IN_STATUS_SUFFIX: &str = ".time_in_status.json";
+
+#[derive(Debug)]
+pub enum OfflineDatasetError {
+    ReadError(std::io::Error),
+    ParseError(String, serde_json::Error),
+    /// Neither a task, a list of tasks nor time in status.
+    UnrecognizedFile(String),
+    InvalidArchive,
+    Empty,
+}
+
+/// Tasks and their time in status saved from the ClickUp API, so they can be
+/// analysed without a token.
+///
+/// Files can hold a task (`GET /task/{id}`), a list of tasks
+/// (`GET /list/{id}/task`), the time in status of one task, named with
+/// [`TIME_IN_STATUS_SUFFIX`], or the time in status of many tasks keyed by id
+/// (`GET /task/bulk_time_in_status/task_ids`). Tasks can also carry their
+/// time in status inline as `time_in_status`.
+#[derive(Debug, Clone, Default)]
+pub struct OfflineDataset {
+    tasks: HashMap<String, ClickUpTaskResponseBody>,
+    /// Task ids in the order they were read.
+    task_ids: Vec<String>,
+    time_in_status: HashMap<String, ClickUpTimeInStatusResponseBody>,
+}
+
+impl OfflineDataset {
+    /// Reads every `.json` file under `path`, or the `.tar` archive or `.json`
+    /// file at `path`.
+    pub fn from_path(path: &Path) -> Result<Self, OfflineDatasetError> {
+        let mut dataset = Self::default();
+        if path.is_dir() {
+            dataset.add_directory(path)?;
+        } else {
+            let name = path.to_string_lossy().to_string();
+            let bytes = std::fs::read(path).map_err(OfflineDatasetError::ReadError)?;
+            dataset.add_file(&name, &bytes)?;
+        }
+
+        dataset.finish()
+    }
+
+    /// Reads an uploaded `.tar` archive or `.json` file, told apart by their
+    /// content.
+    pub fn from_upload(bytes: &[u8]) -> Result<Self, OfflineDatasetError> {
+        let mut dataset = Self::default();
+        let name = match bytes.iter().find(|b| !b.is_ascii_whitespace()) {
+            Some(b'{' | b'[') => "upload.json",
+            _ => "upload.tar",
+        };
+        dataset.add_file(name, bytes)?;
+
+        dataset.finish()
+    }
+
+    fn add_directory(&mut self, path: &Path) -> Result<(), OfflineDatasetError> {
+        let mut entries: Vec<_> = std::fs::read_dir(path)
+            .map_err(OfflineDatasetError::ReadError)?
+            .collect::<Result<_, _>>()
+            .map_err(OfflineDatasetError::ReadError)?;
+        entries.sort_by_key(|e| e.path());
+
+        for entry in entries {
+            let path = entry.path();
+            if path.is_dir() {
+                self.add_directory(&path)?;
+            } else if path.extension().is_some_and(|e| e == "json") {
+                let bytes = std::fs::read(&path).map_err(OfflineDatasetError::ReadError)?;
+                self.add_file(&path.to_string_lossy(), &bytes)?;
+            }
+        }
+
+        Ok(())
+    }
+
+    fn add_file(&mut self, name: &str, bytes: &[u8]) -> Result<(), OfflineDatasetError> {
+        if name.ends_with(".tar") {
+            for (name, bytes) in tar_entries(bytes)? {
+                if name.ends_with(".json") {
+                    self.add_file(&name, &bytes)?;
+                }
+            }
+            return Ok(());
+        }
+
+        let parse_error = |e| OfflineDatasetError::ParseError(name.to_string(), e);
+        let value: Value = serde_json::from_slice(bytes).map_err(parse_error)?;
+
+        let file_name = name.rsplit(['/', '\\']).next().unwrap_or(name);
+        if let Some(task_id) = file_name.strip_suffix(TIME_IN_STATUS_SUFFIX) {
+            let time_in_status = serde_json::from_value(value).map_err(parse_error)?;
+            self.time_in_status
+                .insert(task_id.to_string(), time_in_status);
+            return Ok(());
+        }
+
+        match &value {
+            Value::Object(object) if object.contains_key("tasks") => {
+                let tasks: Vec<ClickUpTaskResponseBody> =
+                    serde_json::from_value(object["tasks"].clone()).map_err(parse_error)?;
+                for task in tasks {
+                    self.add_task(task);
+                }
+            }
+            Value::Object(object) if object.contains_key("id") => {
+                self.add_task(serde_json::from_value(value).map_err(parse_error)?);
+            }
+            Value::Object(object)
+                if !object.is_empty()
+                    && object.values().all(|v| v.get("status_history").is_some()) =>
+            {
+                let time_in_status: ClickUpBulkTimeInStatusResponseBody =
+                    serde_json::from_value(value).map_err(parse_error)?;
+                self.time_in_status.extend(time_in_status);
+            }
+            _ => return Err(OfflineDatasetError::UnrecognizedFile(name.to_string())),
+        }
+
+        Ok(())
+    }
+
+    fn add_task(&mut self, task: ClickUpTaskResponseBody) {
+        if !self.tasks.contains_key(&task.id) {
+            self.task_ids.push(task.id.clone());
+        }
+        self.tasks.insert(task.id.clone(), task);
+    }
+
+    /// Moves the separately saved time in status onto the tasks.
+    fn finish(mut self) -> Result<Self, OfflineDatasetError> {
+        if self.tasks.is_empty() {
+            return Err(OfflineDatasetError::Empty);
+        }
+
+        for (task_id, task) in &mut self.tasks {
+            if let Some(time_in_status) = self.time_in_status.remove(task_id) {
+                task.time_in_status = Some(time_in_status);
+            }
+        }
+
+        Ok(self)
+    }
+
+    pub fn task_count(&self) -> usize {
+        self.tasks.len()
+    }
+
+    pub fn time_in_status_count(&self) -> usize {
+        self.tasks
+            .values()
+            .filter(|t| t.time_in_status.is_some())
+            .count()
+    }
+
+    /// Every task in the dataset, sub tasks included, as a list report would
+    /// fetch them. Tasks saved without their time in status are left out.
+    pub fn tasks(&self) -> Vec<ClickUpTaskResponseBody> {
+        self.task_ids
+            .iter()
+            .filter_map(|id| self.tasks.get(id))
+            .filter(|t| t.time_in_status.is_some())
+            .cloned()
+            .collect()
+    }
+
+    /// Tasks that aren't a sub task of another task in the dataset.
+    pub fn root_tasks(&self) -> Vec<&ClickUpTaskResponseBody> {
+        let sub_task_ids: HashSet<&str> = self
+            .tasks
+            .values()
+            .flat_map(|t| t.sub_tasks.iter().flatten())
+            .map(|s| s.id.as_str())
+            .collect();
+
+        self.task_ids
+            .iter()
+            .filter(|id| !sub_task_ids.contains(id.as_str()))
+            .filter_map(|id| self.tasks.get(id))
+            .collect()
+    }
+
+    /// The task with its sub tasks filled in from the dataset, as
+    /// [`ClickUpApi::get_task`](crate::services::clickup::ClickUpApi::get_task)
+    /// would return it. Only tasks saved with their time in status are
+    /// included, so sub tasks without it are left empty.
+    pub fn task_tree(&self, task_id: &str) -> Option<ClickUpTaskResponseBody> {
+        fn fill_sub_tasks(
+            dataset: &OfflineDataset,
+            task: &mut ClickUpTaskResponseBody,
+            ancestors: &mut Vec<String>,
+        ) {
+            ancestors.push(task.id.clone());
+            if let Some(sub_tasks) = &mut task.sub_tasks {
+                for sub_task in sub_tasks {
+                    // A task listed as its own ancestor would never finish.
+                    if ancestors.contains(&sub_task.id) {
+                        continue;
+                    }
+                    if let Some(saved) = dataset
+                        .tasks
+                        .get(&sub_task.id)
+                        .filter(|t| t.time_in_status.is_some())
+                    {
+                        let mut saved = saved.clone();
+                        fill_sub_tasks(dataset, &mut saved, ancestors);
+                        sub_task.task = Some(saved);
+                    }
+                }
+            }
+            ancestors.pop();
+        }
+
+        let mut task = self
+            .tasks
+            .get(task_id)
+            .or_else(|| {
+                self.tasks
+                    .values()
+                    .find(|t| t.custom_id.as_deref() == Some(task_id))
+            })
+            .filter(|t| t.time_in_status.is_some())?
+            .clone();
+        fill_sub_tasks(self, &mut task, &mut vec![]);
+
+        Some(task)
+    }
+}
+
+/// The regular files of an uncompressed tar archive, which must hold at least
+/// one and end after a complete entry.
+fn tar_entries(bytes: &[u8]) -> Result<Vec<(String, Vec<u8>)>, OfflineDatasetError> {
+    let invalid_archive = |_| OfflineDatasetError::InvalidArchive;
+
+    let mut entries = vec![];
+    let mut archive = tar::Archive::new(bytes);
+    for entry in archive.entries().map_err(invalid_archive)? {
+        let mut entry = entry.map_err(invalid_archive)?;
+        if !entry.header().entry_type().is_file() {
+            continue;
+        }
+
+        let name = entry
+            .path()
+            .map_err(invalid_archive)?
+            .to_string_lossy()
+            .to_string();
+        let mut data = vec![];
+        entry.read_to_end(&mut data).map_err(invalid_archive)?;
+        if data.len() as u64 != entry.size() {
+            return Err(OfflineDatasetError::InvalidArchive);
+        }
+        entries.push((name, data));
+    }
+
+    if entries.is_empty() {
+        return Err(OfflineDatasetError::InvalidArchive);
+    }
+
+    Ok(entries)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const TAR_BLOCK_SIZE: usize = 512;
+
+    /// A ustar header for an entry of `size` bytes.
+    fn tar_header(name: &str, prefix: &str, size: usize, type_flag: u8) -> Vec<u8> {
+        let mut header = vec![0; TAR_BLOCK_SIZE];
+        header[..name.len()].copy_from_slice(name.as_bytes());
+        header[124..135].copy_from_slice(format!("{size:011o}").as_bytes());
+        header[156] = type_flag;
+        header[257..263].copy_from_slice(b"ustar\0");
+        header[263..265].copy_from_slice(b"00");
+        header[345..345 + prefix.len()].copy_from_slice(prefix.as_bytes());
+
+        header[148..156].fill(b' ');
+        let checksum: u32 = header.iter().map(|b| *b as u32).sum();
+        header[148..155].copy_from_slice(format!("{checksum:06o}\0").as_bytes());
+
+        header
+    }
+
+    fn tar_entry(name: &str, prefix: &str, data: &[u8]) -> Vec<u8> {
+        let mut entry = tar_header(name, prefix, data.len(), b'0');
+        entry.extend_from_slice(data);
+        entry.resize(entry.len().next_multiple_of(TAR_BLOCK_SIZE), 0);
+        entry
+    }
+
+    fn tar_archive(entries: &[Vec<u8>]) -> Vec<u8> {
+        let mut archive = entries.concat();
+        archive.extend_from_slice(&[0; 2 * TAR_BLOCK_SIZE]);
+        archive
+    }
+
+    fn task_json(id: &str) -> String {
+        format!(
+            r#"{{
+                "id": "{id}",
+                "custom_id": null,
+                "name": "Task {id}",
+                "url": "https://app.clickup.com/t/{id}",
+                "text_content": "",
+                "description": "",
+                "points": null,
+                "time_estimate": null,
+                "time_spent": null,
+                "date_created": "1740960000000",
+                "subtasks": null,
+                "priority": null,
+                "status": null,
+                "time_in_status": null
+            }}"#
+        )
+    }
+
+    #[test]
+    fn reads_regular_files_and_skips_directories() {
+        let archive = tar_archive(&[
+            tar_header("export/", "", 0, b'5'),
+            tar_entry("export/a.json", "", b"{}"),
+            tar_entry("export/b.json", "", &[b' '; 600]),
+        ]);
+
+        let entries = tar_entries(&archive).unwrap();
+
+        let names: Vec<&str> = entries.iter().map(|(name, _)| name.as_str()).collect();
+        assert_eq!(names, ["export/a.json", "export/b.json"]);
+        assert_eq!(entries[0].1, b"{}");
+        assert_eq!(entries[1].1.len(), 600);
+    }
+
+    #[test]
+    fn joins_the_ustar_prefix_to_the_name() {
+        let archive = tar_archive(&[tar_entry("a.json", "exports/2025-03", b"{}")]);
+
+        let entries = tar_entries(&archive).unwrap();
+
+        assert_eq!(entries[0].0, "exports/2025-03/a.json");
+    }
+
+    #[test]
+    fn rejects_truncated_archives() {
+        let archive = tar_archive(&[tar_entry("a.json", "", &[b' '; 600])]);
+
+        // Cut inside the entry's data.
+        assert!(matches!(
+            tar_entries(&archive[..TAR_BLOCK_SIZE + 100]),
+            Err(OfflineDatasetError::InvalidArchive)
+        ));
+        // Cut inside the next entry's header.
+        let mut archive = tar_entry("a.json", "", b"{}");
+        archive.extend_from_slice(&tar_header("b.json", "", 2, b'0')[..200]);
+        assert!(matches!(
+            tar_entries(&archive),
+            Err(OfflineDatasetError::InvalidArchive)
+        ));
+    }
+
+    #[test]
+    fn rejects_malformed_archives() {
+        let mut bad_checksum = tar_entry("a.json", "", b"{}");
+        bad_checksum[0] = b'b';
+        let mut bad_size = tar_header("a.json", "", 0, b'0');
+        bad_size[124..136].copy_from_slice(b"not a size\0\0");
+        let mut huge_size = tar_header("a.json", "", 0, b'0');
+        huge_size[124..136].copy_from_slice(b"77777777777\0");
+        huge_size[148..156].fill(b' ');
+        let checksum: u32 = huge_size.iter().map(|b| *b as u32).sum();
+        huge_size[148..155].copy_from_slice(format!("{checksum:06o}\0").as_bytes());
+
+        for archive in [
+            bad_checksum,
+            bad_size,
+            tar_archive(&[huge_size]),
+            vec![b'x'; 3 * TAR_BLOCK_SIZE],
+            tar_archive(&[]),
+            vec![],
+        ] {
+            assert!(matches!(
+                tar_entries(&archive),
+                Err(OfflineDatasetError::InvalidArchive)
+            ));
+        }
+    }
+
+    #[test]
+    fn reads_uploaded_archives_and_json() {
+        let archive = tar_archive(&[
+            tar_entry("a.json", "", task_json("a").as_bytes()),
+            tar_entry("notes.txt", "", b"not json"),
+            tar_entry("b.json", "", task_json("b").as_bytes()),
+        ]);
+
+        let from_archive = OfflineDataset::from_upload(&archive).unwrap();
+        let from_json = OfflineDataset::from_upload(task_json("a").as_bytes()).unwrap();
+
+        assert_eq!(from_archive.task_count(), 2);
+        assert_eq!(from_json.task_count(), 1);
+        assert!(matches!(
+            OfflineDataset::from_upload(b"plain text"),
+            Err(OfflineDatasetError::InvalidArchive)
+        ));
+    }
+}
@@ -0,0 +1,234 @@
+use crate::{
+    domain::{Outlier, Task, timeline::minutes_to_days},
+    services::workspace_settings::StatusCategory,
+};
+use serde::Serialize;
+use std::collections::HashMap;
+
+/// Days in a waiting status from which the wait is called out.
+pub const MIN_WAITING_DAYS: f32 = 1.0;
+
+/// A likely reason a task took longer than the norm for its estimate.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(tag = "reason", rename_all = "snake_case")]
+pub enum OutlierReason {
+    /// The status that took the largest share of the task's time in progress.
+    MostTimeIn {
+        status: String,
+        days: f32,
+        /// Percentage of the time spent in progress.
+        share: f32,
+    },
+    /// Sub tasks created after work on the task had started.
+    SubTasksAddedLate { count: usize },
+    /// The task was taken back out of a completed status.
+    Reopened { times: usize },
+    /// The task moved back to an earlier status without being completed.
+    SentBack { times: usize },
+    /// The task sat in a status tagged as waiting.
+    Waiting { status: String, days: f32 },
+}
+
+impl OutlierReason {
+    pub fn description(&self) -> String {
+        match self {
+            OutlierReason::MostTimeIn {
+                status,
+                days,
+                share,
+            } => format!("Most time went to {status}: {days:.1} days ({share:.0}%)"),
+            OutlierReason::SubTasksAddedLate { count } => {
+                format!("{count} sub task(s) were added after work started")
+            }
+            OutlierReason::Reopened { times } => format!("Reopened {times} time(s)"),
+            OutlierReason::SentBack { times } => {
+                format!("Moved back in the workflow {times} time(s)")
+            }
+            OutlierReason::Waiting { status, days } => {
+                format!("Sat in {status} for {days:.1} days")
+            }
+        }
+    }
+}
+
+/// Attaches the likely reasons to every task flagged as slower than the norm.
+pub fn explain_outliers(task: &mut Task, status_categories: &HashMap<String, StatusCategory>) {
+    task.outlier_reasons = match task.outlier {
+        Some(Outlier::SlowerThanNorm) => outlier_reasons(task, status_categories),
+        _ => vec![],
+    };
+
+    for sub_task in &mut task.sub_tasks {
+        explain_outliers(sub_task, status_categories);
+    }
+}
+
+fn outlier_reasons(
+    task: &Task,
+    status_categories: &HashMap<String, StatusCategory>,
+) -> Vec<OutlierReason> {
+    let mut reasons = vec![];
+
+    let in_progress_times: Vec<_> = task
+        .status_times
+        .iter()
+        .filter(|s| !matches!(s.status_type.as_str(), "open" | "done" | "closed"))
+        .collect();
+    let in_progress_minutes: i64 = in_progress_times.iter().map(|s| s.minutes).sum();
+    if let Some(longest) = in_progress_times.iter().max_by_key(|s| s.minutes)
+        && in_progress_minutes > 0
+    {
+        reasons.push(OutlierReason::MostTimeIn {
+            status: longest.status.clone(),
+            days: minutes_to_days(longest.minutes),
+            share: longest.minutes as f32 / in_progress_minutes as f32 * 100.0,
+        });
+    }
+
+    if let Some(work_started_at) = task.work_started_at {
+        let count = task
+            .sub_tasks
+            .iter()
+            .filter(|t| t.date_created > work_started_at)
+            .count();
+        if count > 0 {
+            reasons.push(OutlierReason::SubTasksAddedLate { count });
+        }
+    }
+
+    if task.reopened_count > 0 {
+        reasons.push(OutlierReason::Reopened {
+            times: task.reopened_count,
+        });
+    } else if task.rework_loops > 0 {
+        reasons.push(OutlierReason::SentBack {
+            times: task.rework_loops,
+        });
+    }
+
+    let mut waiting_times: Vec<_> = in_progress_times
+        .iter()
+        .filter(|s| {
+            status_categories.get(&s.status.to_lowercase()) == Some(&StatusCategory::Waiting)
+                && minutes_to_days(s.minutes) >= MIN_WAITING_DAYS
+        })
+        .collect();
+    waiting_times.sort_by_key(|s| std::cmp::Reverse(s.minutes));
+    let waiting = waiting_times.iter().map(|s| OutlierReason::Waiting {
+        status: s.status.clone(),
+        days: minutes_to_days(s.minutes),
+    });
+    reasons.extend(waiting);
+
+    reasons
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::testing::{at, done_task, parse, task_json};
+
+    /// A day in `to do`, then 3 days in `in progress`, 2 in `review` and half
+    /// a day `blocked` before it was done, flagged as slower than the norm.
+    fn slow_task() -> Task {
+        let mut task = Task::from(parse(task_json(
+            "a",
+            1.0,
+            &[
+                ("to do", "open", 24),
+                ("in progress", "custom", 72),
+                ("review", "custom", 48),
+                ("blocked", "custom", 12),
+                ("done", "done", 0),
+            ],
+        )));
+        task.outlier = Some(Outlier::SlowerThanNorm);
+        task
+    }
+
+    fn sub_task_created_at(id: &str, hours: i64) -> Task {
+        let mut sub_task = Task::from(done_task(id, 1.0, 1));
+        sub_task.date_created = at(hours);
+        sub_task
+    }
+
+    fn reasons(task: &Task) -> Vec<OutlierReason> {
+        outlier_reasons(task, &HashMap::new())
+    }
+
+    #[test]
+    fn calls_out_the_status_that_took_the_most_time() {
+        let reasons = reasons(&slow_task());
+
+        let OutlierReason::MostTimeIn {
+            status,
+            days,
+            share,
+        } = &reasons[0]
+        else {
+            panic!("{reasons:?}");
+        };
+        assert_eq!((status.as_str(), *days), ("in progress", 3.0));
+        assert!((share - 54.55).abs() < 0.01);
+        assert_eq!(reasons.len(), 1);
+    }
+
+    #[test]
+    fn calls_out_sub_tasks_added_after_work_started() {
+        let mut task = slow_task();
+        assert_eq!(task.work_started_at, Some(at(24)));
+        task.sub_tasks = vec![
+            sub_task_created_at("b", 0),
+            sub_task_created_at("c", 24),
+            sub_task_created_at("d", 48),
+        ];
+
+        assert_eq!(
+            reasons(&task)[1],
+            OutlierReason::SubTasksAddedLate { count: 1 }
+        );
+    }
+
+    #[test]
+    fn calls_out_reopening_over_sending_back() {
+        let mut task = slow_task();
+        task.rework_loops = 2;
+        assert_eq!(reasons(&task)[1], OutlierReason::SentBack { times: 2 });
+
+        task.reopened_count = 1;
+        let reasons = reasons(&task);
+        assert_eq!(reasons[1], OutlierReason::Reopened { times: 1 });
+        assert_eq!(reasons.len(), 2);
+    }
+
+    #[test]
+    fn calls_out_waits_of_a_day_or_more() {
+        let status_categories = HashMap::from([
+            ("review".to_string(), StatusCategory::Waiting),
+            ("blocked".to_string(), StatusCategory::Waiting),
+        ]);
+
+        let reasons = outlier_reasons(&slow_task(), &status_categories);
+
+        assert_eq!(
+            reasons[1..],
+            [OutlierReason::Waiting {
+                status: "review".to_string(),
+                days: 2.0
+            }]
+        );
+        assert_eq!(reasons[1].description(), "Sat in review for 2.0 days");
+    }
+
+    #[test]
+    fn only_explains_tasks_slower_than_the_norm() {
+        let mut task = slow_task();
+        task.sub_tasks = vec![sub_task_created_at("b", 48)];
+        task.sub_tasks[0].outlier = Some(Outlier::FasterThanNorm);
+
+        explain_outliers(&mut task, &HashMap::new());
+
+        assert_eq!(task.outlier_reasons.len(), 2);
+        assert!(task.sub_tasks[0].outlier_reasons.is_empty());
+    }
+}
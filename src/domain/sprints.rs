@@ -0,0 +1,263 @@
+use crate::{
+    domain::{Task, statistics::mean, timeline::minutes_to_days},
+    services::clickup::ClickUpList,
+};
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+
+/// A ClickUp Sprints list, which has a start and end date.
+#[derive(Debug, Clone, Serialize)]
+pub struct Sprint {
+    pub id: String,
+    pub name: String,
+    pub start: DateTime<Utc>,
+    pub end: DateTime<Utc>,
+}
+
+impl Sprint {
+    /// Returns `None` for lists without both dates, which aren't sprints.
+    pub fn from_list(list: &ClickUpList) -> Option<Self> {
+        Some(Self {
+            id: list.id.clone(),
+            name: list.name.clone(),
+            start: list.start_date?,
+            end: list.due_date?,
+        })
+    }
+
+    /// Tasks created after the sprint started were added mid-sprint rather
+    /// than committed to in planning.
+    pub fn is_committed(&self, task: &Task) -> bool {
+        task.date_created <= self.start
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct SprintVelocity {
+    pub sprint: Sprint,
+    pub committed_points: f32,
+    pub added_points: f32,
+    pub completed_points: f32,
+}
+
+impl SprintVelocity {
+    pub fn new(sprint: &Sprint, tasks: &[Task]) -> Self {
+        let mut velocity = Self {
+            sprint: sprint.clone(),
+            committed_points: 0.0,
+            added_points: 0.0,
+            completed_points: 0.0,
+        };
+
+        for task in tasks {
+            if sprint.is_committed(task) {
+                velocity.committed_points += task.total_points;
+            } else {
+                velocity.added_points += task.total_points;
+            }
+            if task.is_completed {
+                velocity.completed_points += task.total_points;
+            }
+        }
+
+        velocity
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct StatusDays {
+    pub status: String,
+    pub days: f32,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct SprintTask {
+    pub id: String,
+    pub number: String,
+    pub name: String,
+    pub url: String,
+    pub points: f32,
+    pub status_days: Vec<StatusDays>,
+}
+
+impl From<&Task> for SprintTask {
+    fn from(value: &Task) -> Self {
+        Self {
+            id: value.id.clone(),
+            number: value.number.clone(),
+            name: value.name.clone(),
+            url: value.url.clone(),
+            points: value.total_points,
+            status_days: value
+                .status_times
+                .iter()
+                .map(|s| StatusDays {
+                    status: s.status.clone(),
+                    days: minutes_to_days(s.minutes),
+                })
+                .collect(),
+        }
+    }
+}
+
+/// Committed against completed work for a sprint, with the velocity of the
+/// sprints before it for comparison.
+#[derive(Debug, Clone, Serialize)]
+pub struct SprintReport {
+    pub velocity: SprintVelocity,
+    pub committed_task_count: usize,
+    pub completed_task_count: usize,
+    /// Tasks that weren't completed, which carry over to the next sprint.
+    pub carry_over: Vec<SprintTask>,
+    /// Tasks created after the sprint started.
+    pub added: Vec<SprintTask>,
+    pub completed: Vec<SprintTask>,
+    /// Oldest first, ending with this sprint.
+    pub velocity_history: Vec<SprintVelocity>,
+    /// Mean completed points of the sprints before this one.
+    pub average_velocity: Option<f32>,
+}
+
+impl SprintReport {
+    /// `sprints` is oldest first, and the report is for the last sprint.
+    pub fn new(sprints: &[(Sprint, Vec<Task>)]) -> Option<Self> {
+        let (sprint, tasks) = sprints.last()?;
+
+        let velocity_history: Vec<SprintVelocity> = sprints
+            .iter()
+            .map(|(sprint, tasks)| SprintVelocity::new(sprint, tasks))
+            .collect();
+        let previous_velocities: Vec<f32> = velocity_history[..velocity_history.len() - 1]
+            .iter()
+            .map(|v| v.completed_points)
+            .collect();
+
+        Some(Self {
+            velocity: SprintVelocity::new(sprint, tasks),
+            committed_task_count: tasks.iter().filter(|t| sprint.is_committed(t)).count(),
+            completed_task_count: tasks.iter().filter(|t| t.is_completed).count(),
+            carry_over: tasks
+                .iter()
+                .filter(|t| !t.is_completed)
+                .map(SprintTask::from)
+                .collect(),
+            added: tasks
+                .iter()
+                .filter(|t| !sprint.is_committed(t))
+                .map(SprintTask::from)
+                .collect(),
+            completed: tasks
+                .iter()
+                .filter(|t| t.is_completed)
+                .map(SprintTask::from)
+                .collect(),
+            average_velocity: mean(&previous_velocities),
+            velocity_history,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::testing::{at, done_task, parse, task_json};
+    use serde_json::json;
+
+    fn sprint(id: &str, start_hours: i64) -> Sprint {
+        Sprint {
+            id: id.to_string(),
+            name: format!("Sprint {id}"),
+            start: at(start_hours),
+            end: at(start_hours + 14 * 24),
+        }
+    }
+
+    /// A task still in `to do`, created `hours` after the others.
+    fn task_created_at(id: &str, points: f32, hours: i64) -> Task {
+        let mut task = task_json(id, points, &[("to do", "open", 24)]);
+        task["date_created"] = json!(at(hours).timestamp_millis().to_string());
+        Task::from(parse(task))
+    }
+
+    fn ids(tasks: &[SprintTask]) -> Vec<&str> {
+        tasks.iter().map(|t| t.id.as_str()).collect()
+    }
+
+    #[test]
+    fn splits_committed_from_added_and_carried_over_work() {
+        let mut added_and_done = task_json(
+            "c",
+            2.0,
+            &[("in progress", "custom", 24), ("done", "done", 0)],
+        );
+        added_and_done["date_created"] = json!(at(48).timestamp_millis().to_string());
+        let in_progress = task_json(
+            "b",
+            5.0,
+            &[("to do", "open", 24), ("in progress", "custom", 48)],
+        );
+        let sprints = [(
+            sprint("3", 0),
+            vec![
+                Task::from(done_task("a", 3.0, 2)),
+                Task::from(parse(in_progress)),
+                Task::from(parse(added_and_done)),
+                task_created_at("d", 1.0, 72),
+            ],
+        )];
+
+        let report = SprintReport::new(&sprints).unwrap();
+
+        assert_eq!(report.velocity.committed_points, 8.0);
+        assert_eq!(report.velocity.added_points, 3.0);
+        assert_eq!(report.velocity.completed_points, 5.0);
+        assert_eq!(report.committed_task_count, 2);
+        assert_eq!(report.completed_task_count, 2);
+        assert_eq!(ids(&report.carry_over), ["b", "d"]);
+        assert_eq!(ids(&report.added), ["c", "d"]);
+        assert_eq!(ids(&report.completed), ["a", "c"]);
+        assert_eq!(report.average_velocity, None);
+
+        let in_progress_days: Vec<_> = report.completed[0]
+            .status_days
+            .iter()
+            .filter(|s| s.status == "in progress")
+            .map(|s| s.days)
+            .collect();
+        assert_eq!(in_progress_days, [2.0]);
+    }
+
+    #[test]
+    fn tasks_created_as_the_sprint_starts_are_committed() {
+        let sprint = sprint("1", 24);
+
+        assert!(sprint.is_committed(&task_created_at("a", 1.0, 24)));
+        assert!(!sprint.is_committed(&task_created_at("b", 1.0, 25)));
+    }
+
+    #[test]
+    fn compares_with_the_velocity_of_earlier_sprints() {
+        let sprints = [
+            (sprint("1", 0), vec![Task::from(done_task("a", 3.0, 1))]),
+            (
+                sprint("2", 0),
+                vec![
+                    Task::from(done_task("b", 5.0, 1)),
+                    task_created_at("c", 2.0, 0),
+                ],
+            ),
+            (sprint("3", 0), vec![Task::from(done_task("d", 1.0, 1))]),
+        ];
+
+        let report = SprintReport::new(&sprints).unwrap();
+
+        let history: Vec<_> = report
+            .velocity_history
+            .iter()
+            .map(|v| (v.sprint.id.as_str(), v.committed_points, v.completed_points))
+            .collect();
+        assert_eq!(history, [("1", 3.0, 3.0), ("2", 7.0, 5.0), ("3", 1.0, 1.0)]);
+        assert_eq!(report.average_velocity, Some(4.0));
+        assert!(SprintReport::new(&[]).is_none());
+    }
+}
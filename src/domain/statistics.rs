@@ -0,0 +1,72 @@
+/// Returns the `p`th percentile (0 to 100) of `values` using the nearest-rank
+/// method, or `None` if there are no values.
+pub fn percentile(values: &[f32], p: f32) -> Option<f32> {
+    if values.is_empty() {
+        return None;
+    }
+
+    let mut sorted = values.to_vec();
+    sorted.sort_by(|a, b| a.total_cmp(b));
+    let rank = ((p / 100.0) * sorted.len() as f32).ceil() as usize;
+
+    Some(sorted[rank.clamp(1, sorted.len()) - 1])
+}
+
+pub fn mean(values: &[f32]) -> Option<f32> {
+    if values.is_empty() {
+        return None;
+    }
+
+    Some(values.iter().sum::<f32>() / values.len() as f32)
+}
+
+/// Population standard deviation.
+pub fn standard_deviation(values: &[f32]) -> Option<f32> {
+    let mean = mean(values)?;
+    let variance = values.iter().map(|v| (v - mean).powi(2)).sum::<f32>() / values.len() as f32;
+
+    Some(variance.sqrt())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn empty_values_have_no_statistics() {
+        assert_eq!(percentile(&[], 50.0), None);
+        assert_eq!(mean(&[]), None);
+        assert_eq!(standard_deviation(&[]), None);
+    }
+
+    #[test]
+    fn single_value_is_every_percentile() {
+        assert_eq!(percentile(&[4.0], 0.0), Some(4.0));
+        assert_eq!(percentile(&[4.0], 50.0), Some(4.0));
+        assert_eq!(percentile(&[4.0], 100.0), Some(4.0));
+        assert_eq!(mean(&[4.0]), Some(4.0));
+        assert_eq!(standard_deviation(&[4.0]), Some(0.0));
+    }
+
+    #[test]
+    fn percentile_between_ranks_takes_the_next_value_up() {
+        // Nearest rank never interpolates: the 85th percentile of 4 values is
+        // rank ceil(3.4) = 4, not a value between the 3rd and 4th.
+        let values = [3.0, 1.0, 4.0, 2.0];
+
+        assert_eq!(percentile(&values, 0.0), Some(1.0));
+        assert_eq!(percentile(&values, 25.0), Some(1.0));
+        assert_eq!(percentile(&values, 50.0), Some(2.0));
+        assert_eq!(percentile(&values, 51.0), Some(3.0));
+        assert_eq!(percentile(&values, 85.0), Some(4.0));
+        assert_eq!(percentile(&values, 100.0), Some(4.0));
+    }
+
+    #[test]
+    fn mean_and_population_standard_deviation() {
+        let values = [2.0, 4.0, 4.0, 4.0, 5.0, 5.0, 7.0, 9.0];
+
+        assert_eq!(mean(&values), Some(5.0));
+        assert_eq!(standard_deviation(&values), Some(2.0));
+    }
+}
@@ -0,0 +1,94 @@
+//! Builds ClickUp tasks for the domain and component tests.
+
+use crate::services::clickup::{ClickUpTaskResponseBody, IN_PROGRESS_ORDER_INDEX};
+use chrono::{DateTime, TimeDelta, TimeZone, Utc};
+use serde_json::{Value, json};
+
+/// `hours` after 2025-03-03 00:00 UTC, a Monday, when every task is created.
+pub fn at(hours: i64) -> DateTime<Utc> {
+    Utc.with_ymd_and_hms(2025, 3, 3, 0, 0, 0).unwrap() + TimeDelta::hours(hours)
+}
+
+/// A task with `points` that went through `statuses` one after the other from
+/// its creation, each as `(name, type, hours)`, and is still in the last one.
+/// `custom` statuses are development statuses. Returned as JSON so tests can
+/// set the fields they need before [`parse`]-ing it.
+pub fn task_json(id: &str, points: f32, statuses: &[(&str, &str, i64)]) -> Value {
+    let mut since = 0;
+    let status_history: Vec<_> = statuses
+        .iter()
+        .enumerate()
+        .map(|(i, (name, status_type, hours))| {
+            let order_index = match *status_type {
+                "open" => 0,
+                "custom" => IN_PROGRESS_ORDER_INDEX + i as i32,
+                _ => 100 + i as i32,
+            };
+            let status = json!({
+                "status": name,
+                "type": status_type,
+                "orderindex": order_index,
+                "total_time": {
+                    "by_minute": hours * 60,
+                    "since": at(since).timestamp_millis().to_string()
+                }
+            });
+            since += hours;
+            status
+        })
+        .collect();
+    let (current_name, current_type, _) = statuses.last().copied().unwrap_or(("to do", "open", 0));
+    let current_total_time = status_history
+        .last()
+        .map(|s| s["total_time"].clone())
+        .unwrap_or(json!({ "by_minute": 0, "since": at(0).timestamp_millis().to_string() }));
+
+    json!({
+        "id": id,
+        "custom_id": null,
+        "name": format!("Task {id}"),
+        "url": format!("https://app.clickup.com/t/{id}"),
+        "text_content": "",
+        "description": "",
+        "points": points,
+        "time_estimate": null,
+        "time_spent": null,
+        "date_created": at(0).timestamp_millis().to_string(),
+        "subtasks": null,
+        "priority": null,
+        "status": { "status": current_name, "type": current_type },
+        "time_in_status": {
+            "current_status": { "status": current_name, "total_time": current_total_time },
+            "status_history": status_history
+        }
+    })
+}
+
+pub fn parse(value: Value) -> ClickUpTaskResponseBody {
+    serde_json::from_value(value).unwrap()
+}
+
+/// A task a day in `to do`, `days` in `in progress` and now `done`.
+pub fn done_task(id: &str, points: f32, days: i64) -> ClickUpTaskResponseBody {
+    parse(task_json(
+        id,
+        points,
+        &[
+            ("to do", "open", 24),
+            ("in progress", "custom", days * 24),
+            ("done", "done", 0),
+        ],
+    ))
+}
+
+/// `task` as an entry of its parent's `subtasks`, already fetched.
+pub fn sub_task_json(task: Value) -> Value {
+    json!({
+        "id": task["id"],
+        "custom_id": null,
+        "name": task["name"],
+        "points": task["points"],
+        "date_created": task["date_created"],
+        "task": task
+    })
+}
@@ -0,0 +1,282 @@
+use crate::{
+    domain::duration_policy::DurationPolicy,
+    services::clickup::{ClickUpTimeInStatusResponseBody, IN_PROGRESS_ORDER_INDEX},
+};
+use chrono::{DateTime, TimeDelta, Utc};
+
+/// A stretch of time a task spent in one status, rebuilt from the `since`
+/// timestamp and total minutes ClickUp reports per status.
+#[derive(Debug, Clone)]
+pub struct StatusPeriod {
+    pub status: String,
+    pub status_type: String,
+    pub order_index: Option<i32>,
+    pub start: DateTime<Utc>,
+    pub end: DateTime<Utc>,
+}
+
+impl StatusPeriod {
+    pub fn is_completed(&self) -> bool {
+        matches!(self.status_type.as_str(), "done" | "closed")
+    }
+
+    pub fn is_in_progress(&self) -> bool {
+        !self.is_completed()
+            && self.status_type != "open"
+            && self
+                .order_index
+                .is_some_and(|order_index| order_index >= IN_PROGRESS_ORDER_INDEX)
+    }
+}
+
+/// Returns the periods a task spent in each status, ordered by when they
+/// started.
+///
+/// ClickUp only reports one entry per status, so a task that visited a status
+/// twice shows up as a single period starting at the first visit.
+pub fn status_timeline(time_in_status: &ClickUpTimeInStatusResponseBody) -> Vec<StatusPeriod> {
+    let mut periods: Vec<StatusPeriod> = time_in_status
+        .status_history
+        .iter()
+        .map(|s| StatusPeriod {
+            status: s.status.clone(),
+            status_type: s.status_type.clone(),
+            order_index: s.order_index,
+            start: s.total_time.since,
+            end: s.total_time.since + TimeDelta::minutes(s.total_time.by_minute),
+        })
+        .collect();
+
+    let current_status = &time_in_status.current_status;
+    if !periods.iter().any(|p| p.status == current_status.status) {
+        periods.push(StatusPeriod {
+            status: current_status.status.clone(),
+            status_type: String::new(),
+            order_index: None,
+            start: current_status.total_time.since,
+            end: current_status.total_time.since
+                + TimeDelta::minutes(current_status.total_time.by_minute),
+        });
+    }
+
+    periods.sort_by_key(|p| p.start);
+    periods
+}
+
+/// Returns the status a task was in at `instant`, or `None` if it didn't exist
+/// yet.
+pub fn status_at(timeline: &[StatusPeriod], instant: DateTime<Utc>) -> Option<&StatusPeriod> {
+    timeline.iter().rev().find(|p| p.start <= instant)
+}
+
+/// Returns the period for the status the task is currently in.
+pub fn current_period<'a>(
+    timeline: &'a [StatusPeriod],
+    time_in_status: &ClickUpTimeInStatusResponseBody,
+) -> Option<&'a StatusPeriod> {
+    timeline
+        .iter()
+        .find(|p| p.status == time_in_status.current_status.status)
+}
+
+/// When the task first entered an in progress status.
+pub fn work_started_at(timeline: &[StatusPeriod]) -> Option<DateTime<Utc>> {
+    timeline
+        .iter()
+        .find(|p| p.is_in_progress())
+        .map(|p| p.start)
+}
+
+/// When the task entered the completed status it is currently in.
+pub fn completed_at(
+    timeline: &[StatusPeriod],
+    time_in_status: &ClickUpTimeInStatusResponseBody,
+) -> Option<DateTime<Utc>> {
+    current_period(timeline, time_in_status)
+        .filter(|p| p.is_completed())
+        .map(|p| p.start)
+}
+
+/// Days counted by `duration_policy` between the task first entering an in
+/// progress status and being completed, or `None` if it isn't completed or
+/// never started.
+pub fn cycle_time_days(
+    timeline: &[StatusPeriod],
+    time_in_status: &ClickUpTimeInStatusResponseBody,
+    duration_policy: &dyn DurationPolicy,
+) -> Option<f32> {
+    let completed = current_period(timeline, time_in_status).filter(|p| p.is_completed())?;
+    let started = work_started_at(timeline)?;
+
+    Some(minutes_to_days(
+        duration_policy.minutes(started, completed.start),
+    ))
+}
+
+pub fn minutes_to_days(minutes: i64) -> f32 {
+    minutes as f32 / 60.0 / 24.0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        domain::duration_policy::FullTime,
+        services::clickup::{CurrentStatus, StatusHistory, TotalTime},
+    };
+    use chrono::TimeZone;
+
+    fn at(hours: i64) -> DateTime<Utc> {
+        Utc.with_ymd_and_hms(2025, 3, 3, 0, 0, 0).unwrap() + TimeDelta::hours(hours)
+    }
+
+    fn status(
+        name: &str,
+        status_type: &str,
+        order_index: i32,
+        since: i64,
+        hours: i64,
+    ) -> StatusHistory {
+        StatusHistory {
+            status: name.to_string(),
+            status_type: status_type.to_string(),
+            total_time: TotalTime {
+                by_minute: hours * 60,
+                since: at(since),
+            },
+            order_index: Some(order_index),
+        }
+    }
+
+    fn time_in_status(
+        current: &str,
+        status_history: Vec<StatusHistory>,
+    ) -> ClickUpTimeInStatusResponseBody {
+        let total_time = status_history
+            .iter()
+            .find(|s| s.status == current)
+            .map(|s| s.total_time.clone())
+            .unwrap_or(TotalTime {
+                by_minute: 0,
+                since: at(0),
+            });
+
+        ClickUpTimeInStatusResponseBody {
+            current_status: CurrentStatus {
+                status: current.to_string(),
+                total_time,
+            },
+            status_history,
+        }
+    }
+
+    #[test]
+    fn empty_history_only_has_the_current_status() {
+        let time_in_status = time_in_status("to do", vec![]);
+
+        let timeline = status_timeline(&time_in_status);
+
+        assert_eq!(timeline.len(), 1);
+        assert_eq!(timeline[0].status, "to do");
+        assert_eq!(work_started_at(&timeline), None);
+        assert_eq!(completed_at(&timeline, &time_in_status), None);
+        assert_eq!(cycle_time_days(&timeline, &time_in_status, &FullTime), None);
+    }
+
+    #[test]
+    fn periods_are_ordered_by_start() {
+        let time_in_status = time_in_status(
+            "done",
+            vec![
+                status("done", "done", 4, 72, 0),
+                status("to do", "open", 0, 0, 24),
+                status("in progress", "custom", IN_PROGRESS_ORDER_INDEX, 24, 48),
+            ],
+        );
+
+        let timeline = status_timeline(&time_in_status);
+
+        let statuses: Vec<&str> = timeline.iter().map(|p| p.status.as_str()).collect();
+        assert_eq!(statuses, ["to do", "in progress", "done"]);
+        assert_eq!(timeline[1].end, at(72));
+        assert_eq!(status_at(&timeline, at(30)).unwrap().status, "in progress");
+        assert!(status_at(&timeline, at(-1)).is_none());
+        assert_eq!(work_started_at(&timeline), Some(at(24)));
+        assert_eq!(completed_at(&timeline, &time_in_status), Some(at(72)));
+        assert_eq!(
+            cycle_time_days(&timeline, &time_in_status, &FullTime),
+            Some(2.0)
+        );
+    }
+
+    #[test]
+    fn revisited_status_is_one_period_from_the_first_visit() {
+        // In progress for a day, back in review for a day, then in progress
+        // again for a day: ClickUp reports in progress once, with two days in
+        // total since the first visit.
+        let time_in_status = time_in_status(
+            "complete",
+            vec![
+                status("to do", "open", 0, 0, 24),
+                status("in progress", "custom", IN_PROGRESS_ORDER_INDEX, 24, 48),
+                status("review", "custom", IN_PROGRESS_ORDER_INDEX + 1, 48, 24),
+                status("complete", "closed", 5, 96, 0),
+            ],
+        );
+
+        let timeline = status_timeline(&time_in_status);
+
+        let in_progress: Vec<&StatusPeriod> = timeline
+            .iter()
+            .filter(|p| p.status == "in progress")
+            .collect();
+        assert_eq!(in_progress.len(), 1);
+        assert_eq!(in_progress[0].start, at(24));
+        assert_eq!(in_progress[0].end, at(72));
+        assert_eq!(work_started_at(&timeline), Some(at(24)));
+        assert_eq!(
+            cycle_time_days(&timeline, &time_in_status, &FullTime),
+            Some(3.0)
+        );
+    }
+
+    #[test]
+    fn current_status_missing_from_history_is_added() {
+        let mut time_in_status = time_in_status("to do", vec![status("to do", "open", 0, 0, 24)]);
+        time_in_status.current_status = CurrentStatus {
+            status: "blocked".to_string(),
+            total_time: TotalTime {
+                by_minute: 60,
+                since: at(24),
+            },
+        };
+
+        let timeline = status_timeline(&time_in_status);
+
+        assert_eq!(timeline.len(), 2);
+        assert_eq!(
+            current_period(&timeline, &time_in_status).unwrap().start,
+            at(24)
+        );
+        assert!(!timeline[1].is_in_progress());
+    }
+
+    #[test]
+    fn status_periods_are_classified_by_type_and_order() {
+        let timeline = status_timeline(&time_in_status(
+            "to do",
+            vec![
+                status("to do", "open", IN_PROGRESS_ORDER_INDEX, 0, 1),
+                status("ready", "custom", IN_PROGRESS_ORDER_INDEX - 1, 1, 1),
+                status("doing", "custom", IN_PROGRESS_ORDER_INDEX, 2, 1),
+                status("done", "done", IN_PROGRESS_ORDER_INDEX + 1, 3, 1),
+            ],
+        ));
+
+        let in_progress: Vec<bool> = timeline.iter().map(|p| p.is_in_progress()).collect();
+        let completed: Vec<bool> = timeline.iter().map(|p| p.is_completed()).collect();
+        assert_eq!(in_progress, [false, false, true, false]);
+        assert_eq!(completed, [false, false, false, true]);
+        assert_eq!(minutes_to_days(36 * 60), 1.5);
+    }
+}
@@ -0,0 +1,388 @@
+use crate::{
+    domain::timeline::{StatusPeriod, status_timeline},
+    services::clickup::{
+        ClickUpHistoryStatus, ClickUpTaskResponseBody, ClickUpTimeInStatusResponseBody,
+    },
+};
+use chrono::{DateTime, TimeDelta, Utc};
+use serde::Serialize;
+
+/// Slack allowed between a status's total time and the gap before the next
+/// status started, since ClickUp rounds to the minute.
+const REVISIT_TOLERANCE: TimeDelta = TimeDelta::hours(1);
+
+/// Where the transitions were rebuilt from.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TransitionSource {
+    /// The task's status change history items, which ClickUp's REST API
+    /// doesn't return but exports of its webhook events include. Every
+    /// transition and revisit is known.
+    History,
+    /// The `since` timestamps of the time in status, which only show one visit
+    /// per status, so revisits are inferred and rework is a lower bound.
+    SinceTimestamps,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct StatusTransition {
+    pub from: String,
+    pub to: String,
+    pub at: DateTime<Utc>,
+    /// The task moved to an earlier status in the workflow.
+    pub is_backward: bool,
+    /// The task was taken back out of a completed status.
+    pub is_reopen: bool,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct TaskTransitions {
+    pub source: TransitionSource,
+    pub transitions: Vec<StatusTransition>,
+    /// Statuses the task must have left and come back to, because it spent
+    /// more time in them than the gap before the next status started.
+    pub revisited_statuses: Vec<String>,
+    /// A lower bound on the number of times the task went back for rework.
+    pub rework_loops: usize,
+}
+
+impl TaskTransitions {
+    /// Rebuilds the task's transitions from its status history when it has
+    /// any, and from its time in status otherwise.
+    pub fn for_task(task: &ClickUpTaskResponseBody) -> Option<Self> {
+        let timeline = history_timeline(task);
+        if !timeline.is_empty() {
+            return Some(Self::from_history(&timeline));
+        }
+
+        task.time_in_status.as_ref().map(Self::new)
+    }
+
+    /// Transitions between the periods of a timeline with one period per
+    /// visit, as rebuilt from a status history.
+    fn from_history(timeline: &[StatusPeriod]) -> Self {
+        let transitions: Vec<StatusTransition> = timeline
+            .windows(2)
+            .map(|pair| transition(&pair[0], &pair[1], pair[1].start))
+            .collect();
+
+        let mut revisited_statuses: Vec<String> = vec![];
+        for (i, period) in timeline.iter().enumerate() {
+            if timeline[..i].iter().any(|p| p.status == period.status)
+                && !revisited_statuses.contains(&period.status)
+            {
+                revisited_statuses.push(period.status.clone());
+            }
+        }
+
+        let backward_transitions = transitions.iter().filter(|t| t.is_backward).count();
+
+        Self {
+            source: TransitionSource::History,
+            transitions,
+            rework_loops: backward_transitions.max(revisited_statuses.len()),
+            revisited_statuses,
+        }
+    }
+
+    /// Infers the transitions from the time in status, see
+    /// [`TransitionSource::SinceTimestamps`].
+    pub fn new(time_in_status: &ClickUpTimeInStatusResponseBody) -> Self {
+        let timeline = status_timeline(time_in_status);
+
+        let mut transitions: Vec<StatusTransition> = timeline
+            .windows(2)
+            .map(|pair| transition(&pair[0], &pair[1], pair[1].start))
+            .collect();
+
+        let mut revisited_statuses = vec![];
+        for (i, period) in timeline.iter().enumerate() {
+            let Some(next) = timeline.get(i + 1) else {
+                continue;
+            };
+            if period.end - next.start > REVISIT_TOLERANCE {
+                revisited_statuses.push(period.status.clone());
+            }
+        }
+
+        // The current status's `since` is when the task last entered it, which
+        // is later than the history entry if the task came back to it.
+        let current_status = &time_in_status.current_status;
+        if let Some(current) = timeline.iter().find(|p| p.status == current_status.status)
+            && current_status.total_time.since - current.start > REVISIT_TOLERANCE
+            && let Some(previous) = timeline
+                .iter()
+                .rfind(|p| p.status != current.status && p.start < current_status.total_time.since)
+        {
+            transitions.push(transition(
+                previous,
+                current,
+                current_status.total_time.since,
+            ));
+            if !revisited_statuses.contains(&current.status) {
+                revisited_statuses.push(current.status.clone());
+            }
+        }
+
+        let backward_transitions = transitions.iter().filter(|t| t.is_backward).count();
+
+        Self {
+            source: TransitionSource::SinceTimestamps,
+            transitions,
+            rework_loops: backward_transitions.max(revisited_statuses.len()),
+            revisited_statuses,
+        }
+    }
+}
+
+/// One period per visit, from the task's status change history items, or
+/// nothing if it has none. The first status is taken to start when the task
+/// was created and the current one to last until now.
+fn history_timeline(task: &ClickUpTaskResponseBody) -> Vec<StatusPeriod> {
+    let mut changes: Vec<(DateTime<Utc>, ClickUpHistoryStatus, ClickUpHistoryStatus)> = task
+        .history_items
+        .iter()
+        .filter_map(|item| {
+            let (before, after) = item.status_change()?;
+            Some((item.date, before, after))
+        })
+        .collect();
+    changes.sort_by_key(|(at, _, _)| *at);
+
+    let Some((first_change_at, first_status, _)) = changes.first() else {
+        return vec![];
+    };
+
+    let mut timeline = vec![history_period(
+        first_status,
+        task.date_created,
+        *first_change_at,
+    )];
+    for (i, (at, _, after)) in changes.iter().enumerate() {
+        let end = match changes.get(i + 1) {
+            Some((next_at, _, _)) => *next_at,
+            None => Utc::now(),
+        };
+        timeline.push(history_period(after, *at, end));
+    }
+
+    timeline
+}
+
+fn history_period(
+    status: &ClickUpHistoryStatus,
+    start: DateTime<Utc>,
+    end: DateTime<Utc>,
+) -> StatusPeriod {
+    StatusPeriod {
+        status: status.status.clone(),
+        status_type: status.status_type.clone(),
+        order_index: status.order_index,
+        start,
+        end,
+    }
+}
+
+fn transition(from: &StatusPeriod, to: &StatusPeriod, at: DateTime<Utc>) -> StatusTransition {
+    StatusTransition {
+        from: from.status.clone(),
+        to: to.status.clone(),
+        at,
+        is_backward: matches!(
+            (from.order_index, to.order_index),
+            (Some(from), Some(to)) if to < from
+        ),
+        is_reopen: from.is_completed() && !to.is_completed(),
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct TaskRework {
+    pub id: String,
+    pub number: String,
+    pub name: String,
+    pub url: String,
+    pub transitions: TaskTransitions,
+}
+
+/// Rework loops across every task in a list.
+#[derive(Debug, Clone, Serialize)]
+pub struct ReworkReport {
+    /// [`TransitionSource::History`] only if every task had a history.
+    pub source: TransitionSource,
+    pub task_count: usize,
+    pub tasks_with_rework: usize,
+    pub rework_loops: usize,
+    /// Most rework first.
+    pub tasks: Vec<TaskRework>,
+}
+
+impl ReworkReport {
+    pub fn new(tasks: &[ClickUpTaskResponseBody]) -> Self {
+        let mut tasks: Vec<TaskRework> = tasks
+            .iter()
+            .filter_map(|task| {
+                Some(TaskRework {
+                    id: task.id.clone(),
+                    number: task.custom_id.clone().unwrap_or_else(|| task.id.clone()),
+                    name: task.name.clone(),
+                    url: task.url.clone(),
+                    transitions: TaskTransitions::for_task(task)?,
+                })
+            })
+            .collect();
+        tasks.sort_by_key(|t| std::cmp::Reverse(t.transitions.rework_loops));
+
+        let every_task_has_history = !tasks.is_empty()
+            && tasks
+                .iter()
+                .all(|t| t.transitions.source == TransitionSource::History);
+
+        Self {
+            source: if every_task_has_history {
+                TransitionSource::History
+            } else {
+                TransitionSource::SinceTimestamps
+            },
+            task_count: tasks.len(),
+            tasks_with_rework: tasks
+                .iter()
+                .filter(|t| t.transitions.rework_loops > 0)
+                .count(),
+            rework_loops: tasks.iter().map(|t| t.transitions.rework_loops).sum(),
+            tasks,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn status(name: &str, status_type: &str, order_index: i32) -> serde_json::Value {
+        json!({ "status": name, "type": status_type, "orderindex": order_index })
+    }
+
+    fn task(history_items: serde_json::Value) -> ClickUpTaskResponseBody {
+        serde_json::from_value(json!({
+            "id": "abc",
+            "custom_id": null,
+            "name": "Task",
+            "url": "https://app.clickup.com/t/abc",
+            "text_content": "",
+            "description": "",
+            "points": null,
+            "time_estimate": null,
+            "time_spent": null,
+            "date_created": "1740960000000",
+            "subtasks": null,
+            "priority": null,
+            "status": null,
+            "time_in_status": {
+                "current_status": {
+                    "status": "done",
+                    "total_time": { "by_minute": 0, "since": "1741219200000" }
+                },
+                "status_history": [
+                    {
+                        "status": "to do",
+                        "type": "open",
+                        "orderindex": 0,
+                        "total_time": { "by_minute": 1440, "since": "1740960000000" }
+                    },
+                    {
+                        "status": "in progress",
+                        "type": "custom",
+                        "orderindex": 5,
+                        "total_time": { "by_minute": 2880, "since": "1741046400000" }
+                    },
+                    {
+                        "status": "done",
+                        "type": "done",
+                        "orderindex": 7,
+                        "total_time": { "by_minute": 0, "since": "1741219200000" }
+                    }
+                ]
+            },
+            "history_items": history_items
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn history_gives_every_visit() {
+        let to_do = status("to do", "open", 0);
+        let in_progress = status("in progress", "custom", 5);
+        let qa = status("qa", "custom", 6);
+        let done = status("done", "done", 7);
+        let task = task(json!([
+            { "field": "status", "date": "1741046400000", "before": to_do, "after": in_progress },
+            { "field": "status", "date": "1741089600000", "before": in_progress, "after": qa },
+            { "field": "assignee", "date": "1741100000000", "before": null, "after": null },
+            { "field": "status", "date": "1741132800000", "before": qa, "after": in_progress },
+            { "field": "status", "date": "1741176000000", "before": in_progress, "after": qa },
+            { "field": "status", "date": "1741219200000", "before": qa, "after": done }
+        ]));
+
+        let transitions = TaskTransitions::for_task(&task).unwrap();
+
+        assert_eq!(transitions.source, TransitionSource::History);
+        let moves: Vec<(&str, &str)> = transitions
+            .transitions
+            .iter()
+            .map(|t| (t.from.as_str(), t.to.as_str()))
+            .collect();
+        assert_eq!(
+            moves,
+            [
+                ("to do", "in progress"),
+                ("in progress", "qa"),
+                ("qa", "in progress"),
+                ("in progress", "qa"),
+                ("qa", "done"),
+            ]
+        );
+        let backward: Vec<bool> = transitions
+            .transitions
+            .iter()
+            .map(|t| t.is_backward)
+            .collect();
+        assert_eq!(backward, [false, false, true, false, false]);
+        assert_eq!(transitions.revisited_statuses, ["in progress", "qa"]);
+        assert_eq!(transitions.rework_loops, 2);
+    }
+
+    #[test]
+    fn falls_back_to_since_timestamps_without_status_history() {
+        let task = task(json!([
+            { "field": "assignee", "date": "1741100000000", "before": null, "after": null }
+        ]));
+
+        let transitions = TaskTransitions::for_task(&task).unwrap();
+
+        assert_eq!(transitions.source, TransitionSource::SinceTimestamps);
+        assert_eq!(transitions.transitions.len(), 2);
+        assert_eq!(transitions.rework_loops, 0);
+    }
+
+    #[test]
+    fn report_is_only_exact_when_every_task_has_history() {
+        let with_history = task(json!([{
+            "field": "status",
+            "date": "1741046400000",
+            "before": status("to do", "open", 0),
+            "after": status("done", "done", 7)
+        }]));
+        let without_history = task(json!([]));
+
+        assert_eq!(
+            ReworkReport::new(std::slice::from_ref(&with_history)).source,
+            TransitionSource::History
+        );
+        assert_eq!(
+            ReworkReport::new(&[with_history, without_history]).source,
+            TransitionSource::SinceTimestamps
+        );
+    }
+}
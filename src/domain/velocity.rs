@@ -0,0 +1,287 @@
+use crate::domain::{
+    Task,
+    csv::csv_row,
+    duration_policy::{DurationPolicy, DurationPolicyRecord, FullTime},
+    sprints::Sprint,
+    statistics::{mean, standard_deviation},
+};
+use chrono::{DateTime, Datelike, Days, NaiveDate, NaiveTime, Utc};
+use serde::{Deserialize, Serialize};
+
+/// A stretch of time that completed work is counted in.
+#[derive(Debug, Clone, Serialize)]
+pub struct VelocityPeriod {
+    pub label: String,
+    pub start: DateTime<Utc>,
+    pub end: DateTime<Utc>,
+}
+
+impl VelocityPeriod {
+    /// Returns `count` weeks starting on Mondays, oldest first, ending with
+    /// the week that contains `to`.
+    pub fn weeks(to: NaiveDate, count: usize) -> Vec<Self> {
+        let last_monday = to - Days::new(to.weekday().num_days_from_monday() as u64);
+
+        (0..count)
+            .rev()
+            .map(|weeks_ago| {
+                let monday = last_monday - Days::new(7 * weeks_ago as u64);
+                Self {
+                    label: format!("Week of {monday}"),
+                    start: monday.and_time(NaiveTime::MIN).and_utc(),
+                    end: (monday + Days::new(7)).and_time(NaiveTime::MIN).and_utc(),
+                }
+            })
+            .collect()
+    }
+}
+
+impl From<&Sprint> for VelocityPeriod {
+    fn from(value: &Sprint) -> Self {
+        Self {
+            label: value.name.clone(),
+            start: value.start,
+            end: value.end,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct VelocityPoint {
+    pub period: VelocityPeriod,
+    pub completed_points: f32,
+    pub completed_tasks: usize,
+    /// Rolling over the window ending with this period, `None` until there
+    /// are enough periods to fill the window.
+    pub points_average: Option<f32>,
+    pub points_standard_deviation: Option<f32>,
+    pub tasks_average: Option<f32>,
+    pub tasks_standard_deviation: Option<f32>,
+}
+
+/// Completed points and tasks per period, for capacity planning.
+#[derive(Debug, Clone, Serialize)]
+pub struct VelocityTrend {
+    /// Always [`FullTime`], since tasks are counted in the period they were
+    /// completed in on the calendar, whatever the time formula.
+    pub duration_policy: DurationPolicyRecord,
+    /// Number of periods the rolling statistics cover.
+    pub window: usize,
+    /// Oldest first.
+    pub points: Vec<VelocityPoint>,
+    pub points_average: Option<f32>,
+    pub points_standard_deviation: Option<f32>,
+    pub tasks_average: Option<f32>,
+    pub tasks_standard_deviation: Option<f32>,
+}
+
+impl VelocityTrend {
+    /// Counts each task towards the period it was completed in. Tasks that
+    /// aren't completed, or were completed outside every period, are ignored.
+    pub fn new(tasks: &[Task], periods: Vec<VelocityPeriod>, window: usize) -> Self {
+        let window = window.max(1);
+
+        let mut points: Vec<VelocityPoint> = periods
+            .into_iter()
+            .map(|period| {
+                let completed: Vec<&Task> = tasks
+                    .iter()
+                    .filter(|t| {
+                        t.completed_at
+                            .is_some_and(|c| period.start <= c && c < period.end)
+                    })
+                    .collect();
+
+                VelocityPoint {
+                    completed_points: completed.iter().map(|t| t.total_points).sum(),
+                    completed_tasks: completed.len(),
+                    period,
+                    points_average: None,
+                    points_standard_deviation: None,
+                    tasks_average: None,
+                    tasks_standard_deviation: None,
+                }
+            })
+            .collect();
+
+        let completed_points: Vec<f32> = points.iter().map(|p| p.completed_points).collect();
+        let completed_tasks: Vec<f32> = points.iter().map(|p| p.completed_tasks as f32).collect();
+
+        for (i, point) in points.iter_mut().enumerate().skip(window - 1) {
+            let range = i + 1 - window..=i;
+            point.points_average = mean(&completed_points[range.clone()]);
+            point.points_standard_deviation = standard_deviation(&completed_points[range.clone()]);
+            point.tasks_average = mean(&completed_tasks[range.clone()]);
+            point.tasks_standard_deviation = standard_deviation(&completed_tasks[range]);
+        }
+
+        Self {
+            duration_policy: FullTime.record(),
+            window,
+            points,
+            points_average: mean(&completed_points),
+            points_standard_deviation: standard_deviation(&completed_points),
+            tasks_average: mean(&completed_tasks),
+            tasks_standard_deviation: standard_deviation(&completed_tasks),
+        }
+    }
+
+    pub fn to_csv(&self) -> String {
+        let format_optional = |value: Option<f32>| match value {
+            Some(value) => format!("{value:.2}"),
+            None => String::new(),
+        };
+
+        let mut csv = csv_row([
+            "period",
+            "start",
+            "end",
+            "completed_points",
+            "completed_tasks",
+            "points_rolling_average",
+            "points_rolling_standard_deviation",
+            "tasks_rolling_average",
+            "tasks_rolling_standard_deviation",
+        ]);
+        for point in &self.points {
+            csv.push_str(&csv_row([
+                point.period.label.clone(),
+                point.period.start.to_rfc3339(),
+                point.period.end.to_rfc3339(),
+                point.completed_points.to_string(),
+                point.completed_tasks.to_string(),
+                format_optional(point.points_average),
+                format_optional(point.points_standard_deviation),
+                format_optional(point.tasks_average),
+                format_optional(point.tasks_standard_deviation),
+            ]));
+        }
+
+        csv
+    }
+}
+
+/// Which of the completed work measures to chart.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum VelocityMetric {
+    #[default]
+    Points,
+    Tasks,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::testing::{done_task, parse, task_json};
+
+    /// Four weeks from Monday 2025-03-03 with 5, 1, 3 and 0 points completed
+    /// by 2, 1, 1 and 0 tasks.
+    fn trend(window: usize) -> VelocityTrend {
+        let tasks = [
+            Task::from(done_task("a", 2.0, 1)),
+            Task::from(done_task("b", 3.0, 2)),
+            Task::from(done_task("c", 1.0, 7)),
+            Task::from(done_task("d", 3.0, 14)),
+            Task::from(parse(task_json(
+                "e",
+                8.0,
+                &[("to do", "open", 24), ("in progress", "custom", 24)],
+            ))),
+        ];
+        let to = NaiveDate::from_ymd_opt(2025, 3, 26).unwrap();
+
+        VelocityTrend::new(&tasks, VelocityPeriod::weeks(to, 4), window)
+    }
+
+    #[test]
+    fn counts_completed_work_in_the_week_it_was_completed() {
+        let trend = trend(2);
+
+        let points: Vec<_> = trend
+            .points
+            .iter()
+            .map(|p| {
+                (
+                    p.period.label.as_str(),
+                    p.completed_points,
+                    p.completed_tasks,
+                )
+            })
+            .collect();
+        assert_eq!(
+            points,
+            [
+                ("Week of 2025-03-03", 5.0, 2),
+                ("Week of 2025-03-10", 1.0, 1),
+                ("Week of 2025-03-17", 3.0, 1),
+                ("Week of 2025-03-24", 0.0, 0),
+            ]
+        );
+    }
+
+    #[test]
+    fn rolls_statistics_over_the_window() {
+        let trend = trend(2);
+
+        let points_rolling: Vec<_> = trend
+            .points
+            .iter()
+            .map(|p| (p.points_average, p.points_standard_deviation))
+            .collect();
+        assert_eq!(
+            points_rolling,
+            [
+                (None, None),
+                (Some(3.0), Some(2.0)),
+                (Some(2.0), Some(1.0)),
+                (Some(1.5), Some(1.5)),
+            ]
+        );
+        let tasks_rolling: Vec<_> = trend
+            .points
+            .iter()
+            .map(|p| (p.tasks_average, p.tasks_standard_deviation))
+            .collect();
+        assert_eq!(
+            tasks_rolling,
+            [
+                (None, None),
+                (Some(1.5), Some(0.5)),
+                (Some(1.0), Some(0.0)),
+                (Some(0.5), Some(0.5)),
+            ]
+        );
+
+        assert_eq!(trend.points_average, Some(2.25));
+        assert!((trend.points_standard_deviation.unwrap() - 1.920).abs() < 0.001);
+        assert_eq!(trend.tasks_average, Some(1.0));
+        assert!((trend.tasks_standard_deviation.unwrap() - 0.707).abs() < 0.001);
+    }
+
+    #[test]
+    fn windows_are_at_least_one_period() {
+        let single = trend(0);
+
+        assert_eq!(single.window, 1);
+        assert!(single.points.iter().all(|p| {
+            p.points_average == Some(p.completed_points) && p.points_standard_deviation == Some(0.0)
+        }));
+        assert!(trend(5).points.iter().all(|p| p.points_average.is_none()));
+    }
+
+    #[test]
+    fn leaves_empty_rolling_cells_blank_in_the_csv() {
+        let csv = trend(2).to_csv();
+        let lines: Vec<_> = csv.lines().collect();
+
+        assert_eq!(
+            lines[1],
+            "Week of 2025-03-03,2025-03-03T00:00:00+00:00,2025-03-10T00:00:00+00:00,5,2,,,,"
+        );
+        assert_eq!(
+            lines[2],
+            "Week of 2025-03-10,2025-03-10T00:00:00+00:00,2025-03-17T00:00:00+00:00,1,1,3.00,2.00,1.50,0.50"
+        );
+    }
+}
@@ -0,0 +1,155 @@
+use chrono::{DateTime, NaiveDate, NaiveTime, TimeDelta, Utc, Weekday};
+use chrono_tz::Tz;
+
+const MINUTES_PER_DAY: i64 = 24 * 60;
+
+pub const WEEKDAYS: [Weekday; 5] = [
+    Weekday::Mon,
+    Weekday::Tue,
+    Weekday::Wed,
+    Weekday::Thu,
+    Weekday::Fri,
+];
+
+/// Minutes between `start` and `end` on the days that count in `time_zone`,
+/// inside `window` when there is one. Windowed minutes are scaled so that a
+/// full window counts as a day.
+pub fn counted_minutes(
+    start: DateTime<Utc>,
+    end: DateTime<Utc>,
+    time_zone: Tz,
+    is_counted_day: impl Fn(NaiveDate) -> bool,
+    window: Option<(NaiveTime, NaiveTime)>,
+) -> i64 {
+    if end <= start {
+        return 0;
+    }
+
+    let start = start.with_timezone(&time_zone);
+    let end = end.with_timezone(&time_zone);
+
+    let mut minutes = 0;
+    let mut date = start.date_naive();
+    while date <= end.date_naive() {
+        let (window_start, window_end) = match window {
+            Some((window_start, window_end)) => {
+                (date.and_time(window_start), date.and_time(window_end))
+            }
+            None => (
+                date.and_time(NaiveTime::MIN),
+                date.and_time(NaiveTime::MIN) + TimeDelta::days(1),
+            ),
+        };
+
+        if is_counted_day(date)
+            && let Some(window_start) = window_start.and_local_timezone(time_zone).earliest()
+            && let Some(window_end) = window_end.and_local_timezone(time_zone).earliest()
+        {
+            let from = window_start.max(start);
+            let to = window_end.min(end);
+            if to > from {
+                minutes += (to - from).num_minutes();
+            }
+        }
+
+        date = match date.succ_opt() {
+            Some(date) => date,
+            None => break,
+        };
+    }
+
+    match window {
+        Some((window_start, window_end)) => {
+            minutes * MINUTES_PER_DAY / (window_end - window_start).num_minutes().max(1)
+        }
+        None => minutes,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::testing::at;
+    use chrono::{Datelike, TimeZone};
+
+    const NINE_TO_FIVE: Option<(NaiveTime, NaiveTime)> = Some((
+        NaiveTime::from_hms_opt(9, 0, 0).unwrap(),
+        NaiveTime::from_hms_opt(17, 0, 0).unwrap(),
+    ));
+
+    fn is_weekday(date: NaiveDate) -> bool {
+        WEEKDAYS.contains(&date.weekday())
+    }
+
+    #[test]
+    fn scales_a_full_window_to_a_day() {
+        let minutes =
+            |from, to| counted_minutes(at(from), at(to), Tz::UTC, is_weekday, NINE_TO_FIVE);
+
+        assert_eq!(minutes(9, 17), MINUTES_PER_DAY);
+        assert_eq!(minutes(0, 24), MINUTES_PER_DAY);
+        assert_eq!(minutes(9, 13), MINUTES_PER_DAY / 2);
+        // Monday 09:00 to Wednesday 13:00.
+        assert_eq!(minutes(9, 48 + 13), MINUTES_PER_DAY * 5 / 2);
+        assert_eq!(minutes(17, 24 + 9), 0);
+        assert_eq!(minutes(13, 9), 0);
+    }
+
+    #[test]
+    fn counts_across_midnight() {
+        // Monday 22:00 to Tuesday 02:00.
+        assert_eq!(
+            counted_minutes(at(22), at(26), Tz::UTC, is_weekday, None),
+            4 * 60
+        );
+
+        // Monday 20:00 to Tuesday 02:00 UTC is 15:00 to 21:00 on Monday in
+        // New York, two hours of which are in the window.
+        assert_eq!(
+            counted_minutes(
+                at(20),
+                at(26),
+                chrono_tz::America::New_York,
+                is_weekday,
+                NINE_TO_FIVE
+            ),
+            MINUTES_PER_DAY / 4
+        );
+    }
+
+    #[test]
+    fn follows_daylight_saving_time() {
+        // Paris moves to summer time on Sunday 2025-03-30, so its 09:00 is
+        // 08:00 UTC on the Friday before and 07:00 UTC on the Monday after.
+        let utc = |day, hour| Utc.with_ymd_and_hms(2025, 3, day, hour, 0, 0).unwrap();
+        let minutes = |start, end| {
+            counted_minutes(
+                start,
+                end,
+                chrono_tz::Europe::Paris,
+                is_weekday,
+                NINE_TO_FIVE,
+            )
+        };
+
+        assert_eq!(minutes(utc(28, 8), utc(28, 16)), MINUTES_PER_DAY);
+        assert_eq!(minutes(utc(31, 7), utc(31, 15)), MINUTES_PER_DAY);
+        assert_eq!(minutes(utc(28, 8), utc(31, 15)), 2 * MINUTES_PER_DAY);
+    }
+
+    #[test]
+    fn skips_the_days_that_dont_count() {
+        // Friday noon to Monday noon.
+        let minutes = counted_minutes(at(4 * 24 + 12), at(7 * 24 + 12), Tz::UTC, is_weekday, None);
+        assert_eq!(minutes, MINUTES_PER_DAY);
+
+        let minutes = counted_minutes(
+            at(4 * 24 + 12),
+            at(7 * 24 + 12),
+            Tz::UTC,
+            |date| is_weekday(date) && date.day() != 7,
+            None,
+        );
+        assert_eq!(minutes, MINUTES_PER_DAY / 2);
+    }
+}
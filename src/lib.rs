@@ -1,13 +1,25 @@
-use crate::services::clickup::ClickUpService;
+use crate::domain::Task;
+use crate::services::clickup::{ClickUpApi, TaskTreeLimits};
+use crate::services::jobs::JobService;
+use crate::services::offline_datasets::OfflineDatasetService;
+use crate::services::workspace_settings::WorkspaceSettingsStore;
 use std::sync::Arc;
+use tower_sessions_redis_store::fred::prelude::Pool;
 
+pub mod components;
+pub mod constants;
 pub mod domain;
 pub mod routes;
 pub mod services;
-pub mod constants;
-pub mod components;
 
 #[derive(Clone)]
 pub struct AppState {
-    pub click_up_service: Arc<ClickUpService>
+    pub click_up_service: Arc<dyn ClickUpApi>,
+    /// How much of a task tree the task form fetches.
+    pub task_tree_limits: TaskTreeLimits,
+    pub job_service: JobService<Task>,
+    pub workspace_settings_service: Arc<dyn WorkspaceSettingsStore>,
+    pub offline_dataset_service: OfflineDatasetService,
+    /// The Redis the sessions are stored in, pinged by the readiness probe.
+    pub redis_pool: Pool,
 }
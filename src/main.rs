@@ -1,13 +1,31 @@
+use axum::extract::DefaultBodyLimit;
 use axum::routing::{post, put};
 use axum::{Router, routing::get, serve};
+use chrono::TimeDelta;
 use clickup_time_in_status_analyzer::AppState;
+use clickup_time_in_status_analyzer::routes::lists::{
+    aging_wip, assignees, control_chart, cumulative_flow, flow_efficiency, rework, sprint_report,
+    task_charts, velocity,
+};
 use clickup_time_in_status_analyzer::routes::pages::home;
 use clickup_time_in_status_analyzer::routes::session::put_workspace;
-use clickup_time_in_status_analyzer::routes::{health, login, oauth_redirect, task};
-use clickup_time_in_status_analyzer::services::clickup::ClickUpService;
+use clickup_time_in_status_analyzer::routes::{
+    MAX_OFFLINE_DATASET_BYTES, get_estimate_field, get_job, get_job_assignees, get_job_chart,
+    get_job_events, get_working_hours, health, live, login, oauth_redirect, post_offline_dataset,
+    post_offline_task, put_calendar, put_estimate_field, put_status_category, put_working_hours,
+    ready, task,
+};
+use clickup_time_in_status_analyzer::services::clickup::{
+    CachingClickUpApi, Cassette, ClickUpApi, ClickUpService, TaskTreeLimits,
+};
+use clickup_time_in_status_analyzer::services::jobs::JobService;
+use clickup_time_in_status_analyzer::services::offline_datasets::OfflineDatasetService;
+use clickup_time_in_status_analyzer::services::workspace_settings::WorkspaceSettingsService;
 use std::error::Error;
 use std::sync::Arc;
-use tower_sessions::{Session, SessionManagerLayer};
+use std::time::Duration;
+use tokio_util::sync::CancellationToken;
+use tower_sessions::SessionManagerLayer;
 use tower_sessions_redis_store::{RedisStore, fred::prelude::*};
 
 // static TASK: &str = "86aea18zr";
@@ -15,6 +33,12 @@ use tower_sessions_redis_store::{RedisStore, fred::prelude::*};
 // static TASK: &str = "86aebe0xh";
 // static TASK: &str = "86aefze6c";
 
+const DEFAULT_SHUTDOWN_DRAIN_TIMEOUT_SECS: u64 = 30;
+
+/// How long cancelled analyses get to record that they failed before the
+/// process exits anyway.
+const CANCELLED_JOB_GRACE: Duration = Duration::from_secs(5);
+
 #[tokio::main]
 async fn main() -> Result<(), Box<dyn Error>> {
     let click_up_client_id =
@@ -23,14 +47,53 @@ async fn main() -> Result<(), Box<dyn Error>> {
         .expect("failed to find CLICK_UP_CLIENT_SECRET env var.");
     let click_up_redirect_uri = std::env::var("CLICK_UP_REDIRECT_URI")
         .expect("failed to find CLICK_UP_REDIRECT_URI env var.");
-    let click_up_service = ClickUpService::new(
+    let shutdown_drain_timeout = match std::env::var("SHUTDOWN_DRAIN_TIMEOUT_SECS") {
+        Ok(secs) => Duration::from_secs(
+            secs.parse()
+                .expect("SHUTDOWN_DRAIN_TIMEOUT_SECS must be a whole number of seconds."),
+        ),
+        Err(_) => Duration::from_secs(DEFAULT_SHUTDOWN_DRAIN_TIMEOUT_SECS),
+    };
+    let default_task_tree_limits = TaskTreeLimits::default();
+    let task_tree_limits = TaskTreeLimits {
+        max_depth: match std::env::var("TASK_TREE_MAX_DEPTH") {
+            Ok(levels) => levels
+                .parse()
+                .expect("TASK_TREE_MAX_DEPTH must be a whole number of levels."),
+            Err(_) => default_task_tree_limits.max_depth,
+        },
+        max_tasks: match std::env::var("TASK_TREE_MAX_TASKS") {
+            Ok(tasks) => tasks
+                .parse()
+                .expect("TASK_TREE_MAX_TASKS must be a whole number of tasks."),
+            Err(_) => default_task_tree_limits.max_tasks,
+        },
+    };
+    let click_up_cache_ttl = std::env::var("CLICK_UP_CACHE_TTL_SECS").ok().map(|secs| {
+        TimeDelta::seconds(
+            secs.parse()
+                .expect("CLICK_UP_CACHE_TTL_SECS must be a whole number of seconds."),
+        )
+    });
+
+    // Cancelled once the drain timeout has elapsed, aborting any ClickUp
+    // fetches that are still running.
+    let analysis_cancellation_token = CancellationToken::new();
+
+    let mut click_up_service = ClickUpService::new(
         &click_up_client_id,
         &click_up_client_secret,
         &click_up_redirect_uri,
-    );
-    let app_state = AppState {
-        click_up_service: Arc::new(click_up_service),
-    };
+    )
+    .with_cancellation_token(analysis_cancellation_token.clone());
+    // Set this to serve a cassette recorded with the CLI without network
+    // access. Recording is left to the CLI, a shared server would mix every
+    // user's traffic into one file.
+    if let Ok(path) = std::env::var("CLICK_UP_REPLAY_CASSETTE") {
+        let cassette = Cassette::replay(&path)
+            .unwrap_or_else(|e| panic!("failed to load the cassette {path}: {e:?}"));
+        click_up_service = click_up_service.with_cassette(cassette);
+    }
 
     // let session_store = MemoryStore::default();
 
@@ -39,7 +102,23 @@ async fn main() -> Result<(), Box<dyn Error>> {
     let redis_conn = pool.connect();
     pool.wait_for_connect().await?;
 
-    let session_store = RedisStore::new(pool);
+    let click_up_service: Arc<dyn ClickUpApi> = match click_up_cache_ttl {
+        Some(ttl) => Arc::new(CachingClickUpApi::new(click_up_service, ttl)),
+        None => Arc::new(click_up_service),
+    };
+
+    let job_service = JobService::new();
+
+    let app_state = AppState {
+        click_up_service,
+        task_tree_limits,
+        job_service: job_service.clone(),
+        workspace_settings_service: Arc::new(WorkspaceSettingsService::new(pool.clone())),
+        offline_dataset_service: OfflineDatasetService::new(),
+        redis_pool: pool.clone(),
+    };
+
+    let session_store = RedisStore::new(pool.clone());
 
     let session_layer = SessionManagerLayer::new(session_store)
         .with_secure(false)
@@ -47,19 +126,109 @@ async fn main() -> Result<(), Box<dyn Error>> {
 
     let app = Router::new()
         .route("/api/v1/health", get(health))
+        .route("/api/v1/health/live", get(live))
+        .route("/api/v1/health/ready", get(ready))
         .route("/login", get(login))
         .route("/oauth/redirect", get(oauth_redirect))
         .route("/home", get(home))
         .route("/task", post(task))
+        .route("/jobs/{job_id}", get(get_job))
+        .route("/jobs/{job_id}/events", get(get_job_events))
+        .route("/jobs/{job_id}/charts/{chart}", get(get_job_chart))
+        .route("/jobs/{job_id}/assignees", get(get_job_assignees))
         .route("/session/workspace", put(put_workspace))
+        .route("/lists/cumulative-flow", get(cumulative_flow))
+        .route("/lists/aging-wip", get(aging_wip))
+        .route("/lists/flow-efficiency", get(flow_efficiency))
+        .route("/lists/rework", get(rework))
+        .route("/lists/assignees", get(assignees))
+        .route("/lists/sprint-report", get(sprint_report))
+        .route("/lists/velocity", get(velocity))
+        .route("/lists/task-charts", get(task_charts))
+        .route("/lists/control-chart", get(control_chart))
+        .route("/workspace/status-categories", put(put_status_category))
+        .route(
+            "/workspace/estimate-field",
+            get(get_estimate_field).put(put_estimate_field),
+        )
+        .route(
+            "/workspace/working-hours",
+            get(get_working_hours).put(put_working_hours),
+        )
+        .route("/workspace/calendar", put(put_calendar))
+        .route(
+            "/offline/datasets",
+            post(post_offline_dataset).layer(DefaultBodyLimit::max(MAX_OFFLINE_DATASET_BYTES)),
+        )
+        .route(
+            "/offline/datasets/{dataset_id}/task",
+            post(post_offline_task),
+        )
         .layer(session_layer)
         .with_state(app_state);
 
     let listener = tokio::net::TcpListener::bind("0.0.0.0:13000").await?;
 
-    let server = serve(listener, app);
+    let shutdown_requested = CancellationToken::new();
+
+    tokio::spawn({
+        let shutdown_requested = shutdown_requested.clone();
+        let analysis_cancellation_token = analysis_cancellation_token.clone();
+        async move {
+            shutdown_signal().await;
+            println!("shutdown requested, draining for {shutdown_drain_timeout:?}");
+            shutdown_requested.cancel();
+
+            tokio::time::sleep(shutdown_drain_timeout).await;
+            println!("drain timeout elapsed, cancelling in-flight analyses");
+            analysis_cancellation_token.cancel();
+        }
+    });
+
+    let server = serve(listener, app).with_graceful_shutdown(shutdown_requested.cancelled_owned());
 
     server.await?;
 
+    // Analyses run detached from the requests that started them, so they are
+    // drained separately, within the same timeout.
+    tokio::select! {
+        _ = job_service.drain() => {}
+        _ = analysis_cancellation_token.cancelled() => {
+            if tokio::time::timeout(CANCELLED_JOB_GRACE, job_service.drain())
+                .await
+                .is_err()
+            {
+                println!("analyses still running after being cancelled, exiting anyway");
+            }
+        }
+    }
+
+    pool.quit().await?;
+    redis_conn.await??;
+
     Ok(())
 }
+
+async fn shutdown_signal() {
+    let ctrl_c = async {
+        tokio::signal::ctrl_c()
+            .await
+            .expect("failed to install SIGINT handler.");
+    };
+
+    #[cfg(unix)]
+    let terminate = async {
+        tokio::signal::unix::signal(tokio::signal::unix::SignalKind::terminate())
+            .expect("failed to install SIGTERM handler.")
+            .recv()
+            .await;
+    };
+
+    #[cfg(not(unix))]
+    let terminate = std::future::pending::<()>();
+
+    tokio::select! {
+        _ = ctrl_c => {},
+        _ = terminate => {},
+    }
+}
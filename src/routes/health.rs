@@ -1,5 +1,133 @@
-use axum::{http::StatusCode, response::IntoResponse};
+use crate::AppState;
+use axum::{
+    Json,
+    extract::{Query, State},
+    http::StatusCode,
+    response::IntoResponse,
+};
+use serde::{Deserialize, Serialize};
+use std::{future::Future, time::Duration};
+use tokio::time::{Instant, timeout};
+use tower_sessions_redis_store::fred::prelude::*;
+
+const DEPENDENCY_CHECK_TIMEOUT: Duration = Duration::from_secs(2);
 
 pub async fn health() -> impl IntoResponse {
     (StatusCode::OK, "OK")
 }
+
+pub async fn live() -> impl IntoResponse {
+    (StatusCode::OK, "OK")
+}
+
+#[derive(Deserialize)]
+pub struct ReadyQueryParams {
+    #[serde(default)]
+    click_up: bool,
+}
+
+#[derive(Serialize, Clone, Copy, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum DependencyStatus {
+    Ok,
+    Unavailable,
+}
+
+#[derive(Serialize)]
+pub struct DependencyCheck {
+    status: DependencyStatus,
+    latency_ms: u128,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    error: Option<String>,
+}
+
+#[derive(Serialize)]
+pub struct ReadyResponseBody {
+    status: DependencyStatus,
+    redis: DependencyCheck,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    click_up: Option<DependencyCheck>,
+}
+
+/// Reports whether the dependencies needed to serve requests are reachable.
+/// The ClickUp API is only probed when `?click_up=true` is passed, so a ClickUp
+/// outage doesn't pull every replica out of rotation by default.
+pub async fn ready(
+    State(app_state): State<AppState>,
+    Query(query_params): Query<ReadyQueryParams>,
+) -> impl IntoResponse {
+    let redis = check_dependency(async {
+        app_state
+            .redis_pool
+            .ping::<String>(None)
+            .await
+            .map(|_| ())
+            .map_err(|e| e.to_string())
+    })
+    .await;
+
+    let click_up = if query_params.click_up {
+        Some(
+            check_dependency(async {
+                app_state
+                    .click_up_service
+                    .ping()
+                    .await
+                    .map_err(|e| format!("{e:?}"))
+            })
+            .await,
+        )
+    } else {
+        None
+    };
+
+    let is_ready = redis.status == DependencyStatus::Ok
+        && click_up
+            .as_ref()
+            .is_none_or(|c| c.status == DependencyStatus::Ok);
+
+    let (status_code, status) = if is_ready {
+        (StatusCode::OK, DependencyStatus::Ok)
+    } else {
+        (
+            StatusCode::SERVICE_UNAVAILABLE,
+            DependencyStatus::Unavailable,
+        )
+    };
+
+    (
+        status_code,
+        Json(ReadyResponseBody {
+            status,
+            redis,
+            click_up,
+        }),
+    )
+}
+
+async fn check_dependency<F>(check: F) -> DependencyCheck
+where
+    F: Future<Output = Result<(), String>>,
+{
+    let start = Instant::now();
+    let result = timeout(DEPENDENCY_CHECK_TIMEOUT, check).await;
+    let latency_ms = start.elapsed().as_millis();
+
+    match result {
+        Ok(Ok(())) => DependencyCheck {
+            status: DependencyStatus::Ok,
+            latency_ms,
+            error: None,
+        },
+        Ok(Err(e)) => DependencyCheck {
+            status: DependencyStatus::Unavailable,
+            latency_ms,
+            error: Some(e),
+        },
+        Err(_) => DependencyCheck {
+            status: DependencyStatus::Unavailable,
+            latency_ms,
+            error: Some("timed out".to_string()),
+        },
+    }
+}
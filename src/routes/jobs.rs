@@ -0,0 +1,282 @@
+use crate::{
+    AppState,
+    components::{
+        AssigneeBreakdownReport, JobProgress, JobProgressBar, Message, TaskAnalysis,
+        charts::{ScatterChart, StackedBarChart},
+    },
+    constants::session::RECENT_JOB_IDS,
+    domain::assignees::AssigneeBreakdown,
+    routes::{
+        HtmlCheckbox,
+        lists::{ReportFormat, html_response, json_response},
+    },
+    services::jobs::{JobSnapshot, JobState},
+};
+use askama::Template;
+use axum::{
+    extract::{Path, Query, State},
+    http::{StatusCode, header},
+    response::{
+        Html, IntoResponse,
+        sse::{Event, KeepAlive, Sse},
+    },
+};
+use serde::Deserialize;
+use std::convert::Infallible;
+use tower_sessions::Session;
+
+/// Jobs can only be opened from the session that started them, while they are
+/// among its [`RECENT_JOB_IDS`].
+async fn is_session_job(session: &Session, job_id: &str) -> bool {
+    let recent_job_ids: Vec<String> = session
+        .get(RECENT_JOB_IDS)
+        .await
+        .unwrap_or_default()
+        .unwrap_or_default();
+
+    recent_job_ids.iter().any(|id| id == job_id)
+}
+
+#[derive(Deserialize)]
+pub struct JobQueryParams {
+    #[serde(default)]
+    format: ReportFormat,
+}
+
+/// Renders the job's progress or analysis. With `?format=json` the analysed
+/// task tree is returned instead, or `202 Accepted` while it is running.
+pub async fn get_job(
+    session: Session,
+    State(app_state): State<AppState>,
+    Path(job_id): Path<String>,
+    Query(query_params): Query<JobQueryParams>,
+) -> impl IntoResponse {
+    let job = match is_session_job(&session, &job_id)
+        .await
+        .then(|| app_state.job_service.get(&job_id))
+        .flatten()
+    {
+        Some(job) if query_params.format == ReportFormat::Json => {
+            return match job.state {
+                JobState::Running => (StatusCode::ACCEPTED, "Running").into_response(),
+                JobState::Completed(task) => json_response(task),
+                JobState::Failed(message) => (StatusCode::BAD_GATEWAY, message).into_response(),
+            };
+        }
+        Some(job) => job,
+        None if query_params.format == ReportFormat::Json => {
+            return (StatusCode::NOT_FOUND, "Not Found").into_response();
+        }
+        None => {
+            return (
+                StatusCode::OK,
+                Html("<p>This analysis could not be found, it may have expired.</p>"),
+            )
+                .into_response();
+        }
+    };
+
+    let html_response_body = match job.state {
+        JobState::Running => {
+            let job_progress = JobProgress {
+                job_id: job.id,
+                progress: job.progress,
+            };
+
+            match job_progress.render() {
+                Ok(html_response_body) => html_response_body,
+                Err(_) => {
+                    return (StatusCode::INTERNAL_SERVER_ERROR, "Internal Server Error")
+                        .into_response();
+                }
+            }
+        }
+        JobState::Completed(task) => match TaskAnalysis::new(job.id, &task).render() {
+            Ok(html_response_body) => html_response_body,
+            Err(_) => {
+                return (StatusCode::INTERNAL_SERVER_ERROR, "Internal Server Error")
+                    .into_response();
+            }
+        },
+        JobState::Failed(message) => return html_response(Message { message: &message }),
+    };
+
+    (StatusCode::OK, Html(html_response_body)).into_response()
+}
+
+/// Serves a chart of a completed job as a standalone `.svg` download.
+pub async fn get_job_chart(
+    session: Session,
+    State(app_state): State<AppState>,
+    Path((job_id, chart)): Path<(String, String)>,
+) -> impl IntoResponse {
+    if !is_session_job(&session, &job_id).await {
+        return (StatusCode::NOT_FOUND, "Not Found").into_response();
+    }
+
+    let task = match app_state.job_service.get(&job_id) {
+        Some(JobSnapshot {
+            state: JobState::Completed(task),
+            ..
+        }) => task,
+        _ => return (StatusCode::NOT_FOUND, "Not Found").into_response(),
+    };
+
+    let svg = match chart.as_str() {
+        "scatter.svg" => ScatterChart::from(&task).render(),
+        "status.svg" => StackedBarChart::from(&task).render(),
+        _ => return (StatusCode::NOT_FOUND, "Not Found").into_response(),
+    };
+
+    let svg = match svg {
+        Ok(svg) => svg,
+        Err(_) => {
+            return (StatusCode::INTERNAL_SERVER_ERROR, "Internal Server Error").into_response();
+        }
+    };
+
+    (
+        StatusCode::OK,
+        [
+            (header::CONTENT_TYPE, "image/svg+xml".to_string()),
+            (
+                header::CONTENT_DISPOSITION,
+                format!("attachment; filename=\"{}-{chart}\"", task.number),
+            ),
+        ],
+        svg,
+    )
+        .into_response()
+}
+
+#[derive(Deserialize)]
+pub struct JobAssigneesQueryParams {
+    anonymize: HtmlCheckbox,
+    #[serde(default)]
+    format: ReportFormat,
+}
+
+pub async fn get_job_assignees(
+    session: Session,
+    State(app_state): State<AppState>,
+    Path(job_id): Path<String>,
+    Query(query_params): Query<JobAssigneesQueryParams>,
+) -> impl IntoResponse {
+    if !is_session_job(&session, &job_id).await {
+        return (StatusCode::NOT_FOUND, "Not Found").into_response();
+    }
+
+    let task = match app_state.job_service.get(&job_id) {
+        Some(JobSnapshot {
+            state: JobState::Completed(task),
+            ..
+        }) => task,
+        _ => return (StatusCode::NOT_FOUND, "Not Found").into_response(),
+    };
+
+    let assignee_breakdown =
+        AssigneeBreakdown::new(std::slice::from_ref(&task), query_params.anonymize.0);
+
+    match query_params.format {
+        ReportFormat::Json => json_response(assignee_breakdown),
+        _ => html_response(AssigneeBreakdownReport::new(
+            assignee_breakdown,
+            format!("/jobs/{job_id}/assignees?"),
+        )),
+    }
+}
+
+/// Streams `progress` events with a rendered progress bar until the job
+/// finishes, then sends a single `done` event and closes.
+pub async fn get_job_events(
+    session: Session,
+    State(app_state): State<AppState>,
+    Path(job_id): Path<String>,
+) -> impl IntoResponse {
+    if !is_session_job(&session, &job_id).await {
+        return (StatusCode::NOT_FOUND, "Not Found").into_response();
+    }
+
+    let mut progress = match app_state.job_service.subscribe(&job_id) {
+        Some(progress) => progress,
+        None => return (StatusCode::NOT_FOUND, "Not Found").into_response(),
+    };
+
+    // Make sure a late subscriber gets the current progress straight away.
+    progress.mark_changed();
+
+    let stream = futures::stream::unfold(Some(progress), |progress| async move {
+        let mut progress = progress?;
+
+        let (event, progress) = match progress.changed().await {
+            Ok(()) => {
+                let progress_bar = JobProgressBar {
+                    progress: *progress.borrow_and_update(),
+                };
+                let event = Event::default()
+                    .event("progress")
+                    .data(progress_bar.render().unwrap_or_default());
+
+                (event, Some(progress))
+            }
+            Err(_) => (Event::default().event("done").data(""), None),
+        };
+
+        Some((Ok::<_, Infallible>(event), progress))
+    });
+
+    Sse::new(stream)
+        .keep_alive(KeepAlive::default())
+        .into_response()
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        routes::testing::{TestApp, fake_task},
+        services::clickup::FakeClickUpApi,
+    };
+    use axum::http::StatusCode;
+
+    async fn start_analysis(app: &mut TestApp, task_id: &str) -> String {
+        let (status, _, body) = app
+            .send_form("POST", "/task", &format!("task_id={task_id}&time_formula="))
+            .await;
+        assert_eq!(status, StatusCode::OK);
+        app.app_state.job_service.drain().await;
+
+        body.strip_prefix(r#"<div id="job_"#)
+            .and_then(|rest| rest.split('"').next())
+            .unwrap()
+            .to_string()
+    }
+
+    #[tokio::test]
+    async fn renders_a_failed_analysis_as_a_message() {
+        let mut app = TestApp::new(FakeClickUpApi::new());
+        app.sign_in().await;
+
+        let job_id = start_analysis(&mut app, "missing").await;
+        let (status, _, body) = app.get(&format!("/jobs/{job_id}")).await;
+
+        assert_eq!(status, StatusCode::OK);
+        assert!(body.trim().starts_with("<p>Something went wrong"));
+    }
+
+    #[tokio::test]
+    async fn other_sessions_cant_open_the_job() {
+        let mut app = TestApp::new(FakeClickUpApi::new().with_task(fake_task("task", 1.0, &[])));
+        app.sign_in().await;
+        let job_id = start_analysis(&mut app, "task").await;
+
+        app.forget_session();
+        app.sign_in().await;
+
+        let (status, _, _) = app.get(&format!("/jobs/{job_id}?format=json")).await;
+        assert_eq!(status, StatusCode::NOT_FOUND);
+        let (_, _, body) = app.get(&format!("/jobs/{job_id}")).await;
+        assert!(body.contains("could not be found"));
+        let (status, _, _) = app.get(&format!("/jobs/{job_id}/charts/scatter.svg")).await;
+        assert_eq!(status, StatusCode::NOT_FOUND);
+    }
+}
@@ -0,0 +1,71 @@
+use crate::{
+    AppState,
+    components::AgingWipReport,
+    domain::aging_wip::AgingWip,
+    domain::filters::TaskFilter,
+    routes::TaskFilterParams,
+    routes::lists::{
+        DatasetParams, DurationPolicyParams, ReportFormat, error_response, fetch_tasks,
+        html_response, json_response, report_duration_policy,
+    },
+};
+use axum::{
+    extract::{Query, State},
+    http::StatusCode,
+    response::IntoResponse,
+};
+use chrono::Utc;
+use serde::Deserialize;
+use tower_sessions::Session;
+
+#[derive(Deserialize)]
+pub struct AgingWipQueryParams {
+    #[serde(default)]
+    list_id: String,
+    #[serde(default)]
+    format: ReportFormat,
+}
+
+pub async fn aging_wip(
+    session: Session,
+    State(app_state): State<AppState>,
+    Query(query_params): Query<AgingWipQueryParams>,
+    Query(task_filter): Query<TaskFilterParams>,
+    Query(dataset): Query<DatasetParams>,
+    Query(duration_policy): Query<DurationPolicyParams>,
+) -> impl IntoResponse {
+    let format = query_params.format;
+    if !matches!(format, ReportFormat::Html | ReportFormat::Json) {
+        return error_response(
+            format,
+            StatusCode::BAD_REQUEST,
+            "The aging WIP report is only available as HTML or JSON.",
+        );
+    }
+
+    let duration_policy =
+        match report_duration_policy(&session, &app_state, &duration_policy, format).await {
+            Ok(duration_policy) => duration_policy,
+            Err(response) => return response,
+        };
+
+    let tasks = match fetch_tasks(
+        &session,
+        &app_state,
+        dataset.or_list(&query_params.list_id),
+        &TaskFilter::from(task_filter),
+        format,
+    )
+    .await
+    {
+        Ok(tasks) => tasks,
+        Err(response) => return response,
+    };
+
+    let aging_wip = AgingWip::new(&tasks, Utc::now(), duration_policy.as_ref());
+
+    match format {
+        ReportFormat::Json => json_response(aging_wip),
+        _ => html_response(AgingWipReport::from(aging_wip)),
+    }
+}
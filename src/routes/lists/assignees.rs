@@ -0,0 +1,75 @@
+use crate::{
+    AppState,
+    components::AssigneeBreakdownReport,
+    domain::filters::TaskFilter,
+    domain::{Task, assignees::AssigneeBreakdown},
+    routes::TaskFilterParams,
+    routes::{
+        HtmlCheckbox,
+        lists::{
+            DatasetParams, ReportFormat, download_query, error_response, fetch_tasks,
+            html_response, json_response,
+        },
+    },
+};
+use axum::{
+    extract::{Query, RawQuery, State},
+    http::StatusCode,
+    response::IntoResponse,
+};
+use serde::Deserialize;
+use tower_sessions::Session;
+
+#[derive(Deserialize)]
+pub struct AssigneesQueryParams {
+    #[serde(default)]
+    list_id: String,
+    anonymize: HtmlCheckbox,
+    #[serde(default)]
+    format: ReportFormat,
+}
+
+pub async fn assignees(
+    session: Session,
+    State(app_state): State<AppState>,
+    Query(query_params): Query<AssigneesQueryParams>,
+    Query(task_filter): Query<TaskFilterParams>,
+    Query(dataset): Query<DatasetParams>,
+    RawQuery(raw_query): RawQuery,
+) -> impl IntoResponse {
+    let format = query_params.format;
+    if !matches!(format, ReportFormat::Html | ReportFormat::Json) {
+        return error_response(
+            format,
+            StatusCode::BAD_REQUEST,
+            "The assignee breakdown is only available as HTML or JSON.",
+        );
+    }
+
+    let tasks = match fetch_tasks(
+        &session,
+        &app_state,
+        dataset.or_list(&query_params.list_id),
+        &TaskFilter::from(task_filter),
+        format,
+    )
+    .await
+    {
+        Ok(tasks) => tasks,
+        Err(response) => return response,
+    };
+
+    let tasks: Vec<Task> = tasks.into_iter().map(Task::from).collect();
+    let assignee_breakdown = AssigneeBreakdown::new(&tasks, query_params.anonymize.0);
+
+    match format {
+        ReportFormat::Json => json_response(assignee_breakdown),
+        _ => {
+            let query = download_query(raw_query, &["anonymize"]);
+            html_response(AssigneeBreakdownReport::new(
+                assignee_breakdown,
+                format!("/lists/assignees?{query}&"),
+            ))
+        }
+    }
+}
@@ -0,0 +1,87 @@
+use crate::{
+    AppState,
+    components::{ControlChartReport, charts::ControlChartView},
+    domain::{control_chart::ControlChart, filters::TaskFilter},
+    routes::{
+        TaskFilterParams,
+        lists::{
+            DatasetParams, DurationPolicyParams, ReportFormat, download_query, error_response,
+            fetch_tasks, file_response, html_response, json_response, report_duration_policy,
+        },
+    },
+};
+use askama::Template;
+use axum::{
+    extract::{Query, RawQuery, State},
+    http::StatusCode,
+    response::IntoResponse,
+};
+use serde::Deserialize;
+use tower_sessions::Session;
+
+#[derive(Deserialize)]
+pub struct ControlChartQueryParams {
+    #[serde(default)]
+    list_id: String,
+    #[serde(default)]
+    format: ReportFormat,
+}
+
+pub async fn control_chart(
+    session: Session,
+    State(app_state): State<AppState>,
+    Query(query_params): Query<ControlChartQueryParams>,
+    Query(task_filter): Query<TaskFilterParams>,
+    Query(dataset): Query<DatasetParams>,
+    Query(duration_policy): Query<DurationPolicyParams>,
+    RawQuery(raw_query): RawQuery,
+) -> impl IntoResponse {
+    let format = query_params.format;
+    if format == ReportFormat::Csv {
+        return error_response(
+            format,
+            StatusCode::BAD_REQUEST,
+            "The control chart is only available as HTML, SVG or JSON.",
+        );
+    }
+
+    let duration_policy =
+        match report_duration_policy(&session, &app_state, &duration_policy, format).await {
+            Ok(duration_policy) => duration_policy,
+            Err(response) => return response,
+        };
+
+    let tasks = match fetch_tasks(
+        &session,
+        &app_state,
+        dataset.or_list(&query_params.list_id),
+        &TaskFilter::from(task_filter),
+        format,
+    )
+    .await
+    {
+        Ok(tasks) => tasks,
+        Err(response) => return response,
+    };
+
+    let control_chart = ControlChart::new(&tasks, duration_policy.as_ref());
+    let file_name = format!("control-chart-{}", query_params.list_id);
+
+    match format {
+        ReportFormat::Json => json_response(control_chart),
+        ReportFormat::Svg => match ControlChartView::from(&control_chart).render() {
+            Ok(svg) => file_response("image/svg+xml", format!("{file_name}.svg"), svg),
+            Err(_) => (StatusCode::INTERNAL_SERVER_ERROR, "Internal Server Error").into_response(),
+        },
+        _ => {
+            let query = download_query(raw_query, &[]);
+            html_response(ControlChartReport {
+                duration_policy: control_chart.duration_policy.description(),
+                chart: ControlChartView::from(&control_chart),
+                completed_task_count: control_chart.tasks.len(),
+                outlier_count: control_chart.tasks.iter().filter(|t| t.is_outlier).count(),
+                query,
+            })
+        }
+    }
+}
@@ -0,0 +1,154 @@
+use crate::{
+    AppState,
+    components::{CumulativeFlowReport, charts::StackedAreaChart},
+    domain::cumulative_flow::CumulativeFlow,
+    domain::filters::TaskFilter,
+    routes::TaskFilterParams,
+    routes::lists::{
+        DatasetParams, ReportFormat, download_query, empty_string_as_none, error_response,
+        fetch_tasks, file_response, html_response, json_response,
+    },
+};
+use askama::Template;
+use axum::{
+    extract::{Query, RawQuery, State},
+    http::StatusCode,
+    response::IntoResponse,
+};
+use chrono::{Days, NaiveDate, Utc};
+use serde::Deserialize;
+use tower_sessions::Session;
+
+const DEFAULT_RANGE_DAYS: u64 = 30;
+const MAX_RANGE_DAYS: i64 = 366;
+
+#[derive(Deserialize)]
+pub struct CumulativeFlowQueryParams {
+    #[serde(default)]
+    list_id: String,
+    #[serde(default, deserialize_with = "empty_string_as_none")]
+    from: Option<NaiveDate>,
+    #[serde(default, deserialize_with = "empty_string_as_none")]
+    to: Option<NaiveDate>,
+    #[serde(default)]
+    format: ReportFormat,
+}
+
+pub async fn cumulative_flow(
+    session: Session,
+    State(app_state): State<AppState>,
+    Query(query_params): Query<CumulativeFlowQueryParams>,
+    Query(task_filter): Query<TaskFilterParams>,
+    Query(dataset): Query<DatasetParams>,
+    RawQuery(raw_query): RawQuery,
+) -> impl IntoResponse {
+    let format = query_params.format;
+
+    let to = query_params.to.unwrap_or_else(|| Utc::now().date_naive());
+    let from = query_params
+        .from
+        .unwrap_or(to - Days::new(DEFAULT_RANGE_DAYS));
+    if from > to || (to - from).num_days() > MAX_RANGE_DAYS {
+        return error_response(
+            format,
+            StatusCode::BAD_REQUEST,
+            "The date range must start before it ends and cover at most a year.",
+        );
+    }
+
+    let tasks = match fetch_tasks(
+        &session,
+        &app_state,
+        dataset.or_list(&query_params.list_id),
+        &TaskFilter::from(task_filter),
+        format,
+    )
+    .await
+    {
+        Ok(tasks) => tasks,
+        Err(response) => return response,
+    };
+
+    let cumulative_flow = CumulativeFlow::new(&tasks, from, to);
+    let file_name = format!("cumulative-flow-{}-{from}-{to}", query_params.list_id);
+
+    match format {
+        ReportFormat::Json => json_response(cumulative_flow),
+        ReportFormat::Csv => file_response(
+            "text/csv",
+            format!("{file_name}.csv"),
+            cumulative_flow.to_csv(),
+        ),
+        ReportFormat::Svg => match StackedAreaChart::from(&cumulative_flow).render() {
+            Ok(svg) => file_response("image/svg+xml", format!("{file_name}.svg"), svg),
+            Err(_) => (StatusCode::INTERNAL_SERVER_ERROR, "Internal Server Error").into_response(),
+        },
+        ReportFormat::Html => html_response(CumulativeFlowReport {
+            duration_policy: cumulative_flow.duration_policy.description(),
+            chart: StackedAreaChart::from(&cumulative_flow),
+            // Pins the dates, which default to the last month, so a download
+            // made tomorrow covers the same range.
+            query: url::form_urlencoded::Serializer::new(download_query(
+                raw_query,
+                &["from", "to"],
+            ))
+            .append_pair("from", &from.to_string())
+            .append_pair("to", &to.to_string())
+            .finish(),
+        }),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        domain::testing::task_json, routes::testing::TestApp, services::clickup::FakeClickUpApi,
+    };
+    use axum::http::StatusCode;
+    use serde_json::json;
+
+    #[tokio::test]
+    async fn downloads_keep_the_dataset_and_filters() {
+        let mut app = TestApp::new(FakeClickUpApi::new());
+        app.sign_in().await;
+        let statuses = [("to do", "open", 36), ("done", "done", 0)];
+        let mut bug = task_json("bug", 1.0, &statuses);
+        bug["tags"] = json!([{ "name": "bug" }]);
+        let feature = task_json("feature", 1.0, &statuses);
+        let (_, _, body) = app
+            .send_form(
+                "POST",
+                "/offline/datasets",
+                &json!({ "tasks": [bug, feature] }).to_string(),
+            )
+            .await;
+        let dataset_id = body
+            .split("/offline/datasets/")
+            .nth(1)
+            .and_then(|rest| rest.split('/').next())
+            .unwrap();
+
+        let (status, _, body) = app
+            .get(&format!(
+                "/lists/cumulative-flow?dataset_id={dataset_id}&from=2025-03-03&to=2025-03-04&exclude_tags=bug"
+            ))
+            .await;
+        assert_eq!(status, StatusCode::OK);
+        let csv_link = body
+            .split(r#"<a href=""#)
+            .filter_map(|rest| rest.split('"').next())
+            .find(|href| href.ends_with("format=csv"))
+            .unwrap()
+            .replace("&#38;", "&");
+        assert_eq!(
+            csv_link,
+            format!(
+                "/lists/cumulative-flow?dataset_id={dataset_id}&exclude_tags=bug&from=2025-03-03&to=2025-03-04&format=csv"
+            )
+        );
+
+        let (status, _, csv) = app.get(&csv_link).await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(csv, "date,to do,done\n2025-03-03,1,0\n2025-03-04,0,1\n");
+    }
+}
@@ -0,0 +1,80 @@
+use crate::{
+    AppState,
+    components::FlowEfficiencyReport,
+    domain::filters::TaskFilter,
+    domain::flow_efficiency::FlowEfficiency,
+    routes::TaskFilterParams,
+    routes::{
+        lists::{
+            DatasetParams, DurationPolicyParams, ReportFormat, error_response, fetch_tasks,
+            html_response, json_response,
+        },
+        task::duration_policy,
+        workspace_settings::current_workspace_settings,
+    },
+};
+use axum::{
+    extract::{Query, State},
+    http::StatusCode,
+    response::IntoResponse,
+};
+use serde::Deserialize;
+use tower_sessions::Session;
+
+#[derive(Deserialize)]
+pub struct FlowEfficiencyQueryParams {
+    #[serde(default)]
+    list_id: String,
+    #[serde(default)]
+    format: ReportFormat,
+}
+
+pub async fn flow_efficiency(
+    session: Session,
+    State(app_state): State<AppState>,
+    Query(query_params): Query<FlowEfficiencyQueryParams>,
+    Query(task_filter): Query<TaskFilterParams>,
+    Query(dataset): Query<DatasetParams>,
+    Query(duration_policy_params): Query<DurationPolicyParams>,
+) -> impl IntoResponse {
+    let format = query_params.format;
+    if !matches!(format, ReportFormat::Html | ReportFormat::Json) {
+        return error_response(
+            format,
+            StatusCode::BAD_REQUEST,
+            "The flow efficiency report is only available as HTML or JSON.",
+        );
+    }
+
+    let (workspace_id, settings) = match current_workspace_settings(&session, &app_state).await {
+        Ok(workspace_settings) => workspace_settings,
+        Err(response) => return response,
+    };
+    let duration_policy = match duration_policy(&duration_policy_params.time_formula, &settings) {
+        Ok(duration_policy) => duration_policy,
+        Err(message) => return error_response(format, StatusCode::BAD_REQUEST, message),
+    };
+
+    let tasks = match fetch_tasks(
+        &session,
+        &app_state,
+        dataset.or_list(&query_params.list_id),
+        &TaskFilter::from(task_filter),
+        format,
+    )
+    .await
+    {
+        Ok(tasks) => tasks,
+        Err(response) => return response,
+    };
+
+    let flow_efficiency = FlowEfficiency::new(&tasks, &settings, duration_policy.as_ref());
+
+    match format {
+        ReportFormat::Json => json_response(flow_efficiency),
+        _ => html_response(FlowEfficiencyReport::new(
+            flow_efficiency,
+            workspace_id.is_some(),
+        )),
+    }
+}
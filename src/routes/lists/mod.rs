@@ -0,0 +1,296 @@
+mod aging_wip;
+mod assignees;
+mod control_chart;
+mod cumulative_flow;
+mod flow_efficiency;
+mod rework;
+mod sprint_report;
+mod task_charts;
+mod velocity;
+
+pub use aging_wip::*;
+pub use assignees::*;
+pub use control_chart::*;
+pub use cumulative_flow::*;
+pub use flow_efficiency::*;
+pub use rework::*;
+pub use sprint_report::*;
+pub use task_charts::*;
+pub use velocity::*;
+
+use crate::{
+    AppState,
+    components::Message,
+    constants::session::CLICK_UP_AUTH_TOKEN,
+    domain::{
+        duration_policy::DurationPolicy, estimates::apply_estimate_field, filters::TaskFilter,
+    },
+    routes::{
+        task::{duration_policy, get_task_error_message},
+        workspace_settings::current_workspace_settings,
+    },
+    services::{
+        clickup::{ClickUpTaskResponseBody, GetListTasksRequest, GetWorkspaceTasksRequest},
+        workspace_settings::WorkspaceSettings,
+    },
+};
+use askama::Template;
+use axum::{
+    Json,
+    http::{StatusCode, header},
+    response::{Html, IntoResponse, Response},
+};
+use serde::{Deserialize, Deserializer, Serialize};
+use std::{fmt::Display, str::FromStr, sync::Arc};
+use tower_sessions::Session;
+
+#[derive(Deserialize, Default, Clone, Copy, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum ReportFormat {
+    #[default]
+    Html,
+    Svg,
+    Json,
+    Csv,
+}
+
+/// Treats an empty form field as missing rather than failing to parse it.
+fn empty_string_as_none<'de, D, T>(deserializer: D) -> Result<Option<T>, D::Error>
+where
+    D: Deserializer<'de>,
+    T: FromStr,
+    T::Err: Display,
+{
+    match Option::<String>::deserialize(deserializer)? {
+        Some(value) if !value.is_empty() => {
+            value.parse().map(Some).map_err(serde::de::Error::custom)
+        }
+        _ => Ok(None),
+    }
+}
+
+pub(crate) fn error_response(
+    format: ReportFormat,
+    status_code: StatusCode,
+    message: &str,
+) -> Response {
+    match format {
+        ReportFormat::Html => html_response(Message { message }),
+        _ => (status_code, message.to_string()).into_response(),
+    }
+}
+
+pub(crate) fn json_response<T: Serialize>(body: T) -> Response {
+    Json(body).into_response()
+}
+
+pub(crate) fn file_response(
+    content_type: &'static str,
+    file_name: String,
+    body: String,
+) -> Response {
+    (
+        StatusCode::OK,
+        [
+            (header::CONTENT_TYPE, content_type.to_string()),
+            (
+                header::CONTENT_DISPOSITION,
+                format!("attachment; filename=\"{file_name}\""),
+            ),
+        ],
+        body,
+    )
+        .into_response()
+}
+
+/// Rebuilds a report's query string for its download links, keeping its
+/// source, dataset and filters but not its format or the `dropped` keys.
+pub(crate) fn download_query(raw_query: Option<String>, dropped: &[&str]) -> String {
+    url::form_urlencoded::Serializer::new(String::new())
+        .extend_pairs(
+            url::form_urlencoded::parse(raw_query.unwrap_or_default().as_bytes())
+                .filter(|(key, _)| key != "format" && !dropped.contains(&key.as_ref())),
+        )
+        .finish()
+}
+
+pub(crate) fn html_response<T: Template>(template: T) -> Response {
+    match template.render() {
+        Ok(html_response_body) => Html(html_response_body).into_response(),
+        Err(_) => (StatusCode::INTERNAL_SERVER_ERROR, "Internal Server Error").into_response(),
+    }
+}
+
+/// Where a report's tasks come from.
+#[derive(Clone, Copy)]
+enum TaskSource<'a> {
+    List(&'a str),
+    Space(&'a str),
+    Folder(&'a str),
+    /// An uploaded [`OfflineDataset`](crate::domain::offline::OfflineDataset).
+    Dataset(&'a str),
+}
+
+/// Runs a report against an uploaded dataset instead of ClickUp.
+#[derive(Deserialize)]
+pub struct DatasetParams {
+    #[serde(default)]
+    pub(crate) dataset_id: String,
+}
+
+/// How a report that measures durations counts them, as in the task form.
+#[derive(Deserialize)]
+pub struct DurationPolicyParams {
+    #[serde(default)]
+    pub(crate) time_formula: String,
+}
+
+/// Picks the report's duration policy with the workspace's settings, or
+/// returns the response to send back if it can't be used.
+async fn report_duration_policy(
+    session: &Session,
+    app_state: &AppState,
+    params: &DurationPolicyParams,
+    format: ReportFormat,
+) -> Result<Arc<dyn DurationPolicy>, Response> {
+    let (_, settings) = current_workspace_settings(session, app_state).await?;
+    duration_policy(&params.time_formula, &settings)
+        .map_err(|message| error_response(format, StatusCode::BAD_REQUEST, message))
+}
+
+impl DatasetParams {
+    fn or_list<'a>(&'a self, list_id: &'a str) -> TaskSource<'a> {
+        if self.dataset_id.is_empty() {
+            TaskSource::List(list_id)
+        } else {
+            TaskSource::Dataset(&self.dataset_id)
+        }
+    }
+}
+
+async fn fetch_list_tasks(
+    session: &Session,
+    app_state: &AppState,
+    list_id: &str,
+    task_filter: &TaskFilter,
+    format: ReportFormat,
+) -> Result<Vec<ClickUpTaskResponseBody>, Response> {
+    fetch_tasks(
+        session,
+        app_state,
+        TaskSource::List(list_id),
+        task_filter,
+        format,
+    )
+    .await
+}
+
+/// Fetches every task from `source` that matches `task_filter` with the
+/// session's token, with estimates read from the workspace's estimate field,
+/// or returns the response to send back if that isn't possible.
+async fn fetch_tasks(
+    session: &Session,
+    app_state: &AppState,
+    source: TaskSource<'_>,
+    task_filter: &TaskFilter,
+    format: ReportFormat,
+) -> Result<Vec<ClickUpTaskResponseBody>, Response> {
+    let missing_id_message = match source {
+        TaskSource::List("") => Some("Missing list id."),
+        TaskSource::Space("") => Some("Missing space id."),
+        TaskSource::Folder("") => Some("Missing folder id."),
+        TaskSource::Dataset("") => Some("Missing dataset id."),
+        _ => None,
+    };
+    if let Some(message) = missing_id_message {
+        return Err(error_response(format, StatusCode::BAD_REQUEST, message));
+    }
+
+    // Uploaded datasets don't need a token.
+    if let TaskSource::Dataset(dataset_id) = source {
+        let Some(dataset) = app_state.offline_dataset_service.get(dataset_id) else {
+            return Err(error_response(
+                format,
+                StatusCode::NOT_FOUND,
+                "This dataset could not be found, it may have expired.",
+            ));
+        };
+        let (_, settings) = current_workspace_settings(session, app_state).await?;
+
+        let mut tasks = dataset.tasks();
+        retain_and_estimate(&mut tasks, task_filter, &settings);
+        return Ok(tasks);
+    }
+
+    let token: String = match session.get(CLICK_UP_AUTH_TOKEN).await {
+        Ok(Some(token)) => token,
+        Err(_) | Ok(None) => {
+            return Err(
+                (StatusCode::INTERNAL_SERVER_ERROR, "Internal Server Error.").into_response(),
+            );
+        }
+    };
+
+    let (workspace_id, settings) = current_workspace_settings(session, app_state).await?;
+
+    let tasks = match source {
+        TaskSource::List(list_id) => {
+            app_state
+                .click_up_service
+                .get_list_tasks(
+                    &token,
+                    GetListTasksRequest {
+                        list_id: list_id.to_string(),
+                        include_closed: true,
+                    },
+                )
+                .await
+        }
+        _ => {
+            let Some(workspace_id) = workspace_id else {
+                return Err(error_response(
+                    format,
+                    StatusCode::BAD_REQUEST,
+                    "Choose a workspace before reporting on a space or folder.",
+                ));
+            };
+            let mut request_config = GetWorkspaceTasksRequest {
+                workspace_id,
+                space_ids: vec![],
+                folder_ids: vec![],
+                include_closed: true,
+            };
+            match source {
+                TaskSource::Space(space_id) => request_config.space_ids.push(space_id.to_string()),
+                TaskSource::Folder(folder_id) => {
+                    request_config.folder_ids.push(folder_id.to_string())
+                }
+                _ => {}
+            }
+
+            app_state
+                .click_up_service
+                .get_workspace_tasks(&token, request_config)
+                .await
+        }
+    };
+    let mut tasks = tasks
+        .map_err(|e| error_response(format, StatusCode::BAD_GATEWAY, get_task_error_message(&e)))?;
+
+    retain_and_estimate(&mut tasks, task_filter, &settings);
+    Ok(tasks)
+}
+
+fn retain_and_estimate(
+    tasks: &mut Vec<ClickUpTaskResponseBody>,
+    task_filter: &TaskFilter,
+    settings: &WorkspaceSettings,
+) {
+    tasks.retain(|task| task_filter.matches(task));
+
+    if let Some(estimate_field) = &settings.estimate_field {
+        for task in tasks {
+            apply_estimate_field(task, estimate_field);
+        }
+    }
+}
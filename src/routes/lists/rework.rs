@@ -0,0 +1,98 @@
+use crate::{
+    AppState,
+    components::ReworkReportView,
+    domain::filters::TaskFilter,
+    domain::transitions::ReworkReport,
+    routes::TaskFilterParams,
+    routes::lists::{
+        DatasetParams, ReportFormat, error_response, fetch_tasks, html_response, json_response,
+    },
+};
+use axum::{
+    extract::{Query, State},
+    http::StatusCode,
+    response::IntoResponse,
+};
+use serde::Deserialize;
+use tower_sessions::Session;
+
+#[derive(Deserialize)]
+pub struct ReworkQueryParams {
+    #[serde(default)]
+    list_id: String,
+    #[serde(default)]
+    format: ReportFormat,
+}
+
+pub async fn rework(
+    session: Session,
+    State(app_state): State<AppState>,
+    Query(query_params): Query<ReworkQueryParams>,
+    Query(task_filter): Query<TaskFilterParams>,
+    Query(dataset): Query<DatasetParams>,
+) -> impl IntoResponse {
+    let format = query_params.format;
+    if !matches!(format, ReportFormat::Html | ReportFormat::Json) {
+        return error_response(
+            format,
+            StatusCode::BAD_REQUEST,
+            "The rework report is only available as HTML or JSON.",
+        );
+    }
+
+    let tasks = match fetch_tasks(
+        &session,
+        &app_state,
+        dataset.or_list(&query_params.list_id),
+        &TaskFilter::from(task_filter),
+        format,
+    )
+    .await
+    {
+        Ok(tasks) => tasks,
+        Err(response) => return response,
+    };
+
+    let rework_report = ReworkReport::new(&tasks);
+
+    match format {
+        ReportFormat::Json => json_response(rework_report),
+        _ => html_response(ReworkReportView::from(rework_report)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        routes::testing::{TestApp, fake_task},
+        services::clickup::FakeClickUpApi,
+    };
+    use axum::http::StatusCode;
+
+    #[tokio::test]
+    async fn reports_on_the_list_tasks() {
+        let mut app = TestApp::new(FakeClickUpApi::new().with_list(
+            "list",
+            vec![fake_task("a", 1.0, &[]), fake_task("b", 2.0, &[])],
+        ));
+        app.sign_in().await;
+
+        let (status, _, body) = app.get("/lists/rework?list_id=list&format=json").await;
+
+        assert_eq!(status, StatusCode::OK);
+        let report: serde_json::Value = serde_json::from_str(&body).unwrap();
+        assert_eq!(report["task_count"], 2);
+        assert_eq!(report["rework_loops"], 0);
+    }
+
+    #[tokio::test]
+    async fn explains_a_missing_list_id() {
+        let mut app = TestApp::new(FakeClickUpApi::new());
+        app.sign_in().await;
+
+        let (status, _, body) = app.get("/lists/rework").await;
+
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(body.trim(), "<p>Missing list id.</p>");
+    }
+}
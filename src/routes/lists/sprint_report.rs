@@ -0,0 +1,163 @@
+use crate::{
+    AppState,
+    components::SprintReportView,
+    constants::session::CLICK_UP_AUTH_TOKEN,
+    domain::{
+        Task,
+        filters::TaskFilter,
+        sprints::{Sprint, SprintReport},
+    },
+    routes::{
+        TaskFilterParams,
+        lists::{
+            DatasetParams, ReportFormat, TaskSource, empty_string_as_none, error_response,
+            fetch_list_tasks, fetch_tasks, html_response, json_response,
+        },
+        task::get_task_error_message,
+    },
+};
+use axum::{
+    extract::{Query, State},
+    http::StatusCode,
+    response::IntoResponse,
+};
+use chrono::Utc;
+use futures::future::try_join_all;
+use serde::Deserialize;
+use tower_sessions::Session;
+
+const DEFAULT_SPRINT_COUNT: usize = 6;
+const MAX_SPRINT_COUNT: usize = 12;
+
+#[derive(Deserialize)]
+pub struct SprintReportQueryParams {
+    folder_id: String,
+    /// The sprint to report on, defaults to the latest sprint that started.
+    #[serde(default)]
+    sprint_list_id: String,
+    #[serde(default, deserialize_with = "empty_string_as_none")]
+    sprint_count: Option<usize>,
+    #[serde(default)]
+    format: ReportFormat,
+}
+
+pub async fn sprint_report(
+    session: Session,
+    State(app_state): State<AppState>,
+    Query(query_params): Query<SprintReportQueryParams>,
+    Query(task_filter): Query<TaskFilterParams>,
+    Query(dataset): Query<DatasetParams>,
+) -> impl IntoResponse {
+    let format = query_params.format;
+    if !matches!(format, ReportFormat::Html | ReportFormat::Json) {
+        return error_response(
+            format,
+            StatusCode::BAD_REQUEST,
+            "The sprint report is only available as HTML or JSON.",
+        );
+    }
+
+    if query_params.folder_id.is_empty() {
+        return error_response(format, StatusCode::BAD_REQUEST, "Missing folder id.");
+    }
+
+    let token: String = match session.get(CLICK_UP_AUTH_TOKEN).await {
+        Ok(Some(token)) => token,
+        Err(_) | Ok(None) => {
+            return (StatusCode::INTERNAL_SERVER_ERROR, "Internal Server Error.").into_response();
+        }
+    };
+
+    let mut sprints: Vec<Sprint> = match app_state
+        .click_up_service
+        .get_folder_lists(&token, &query_params.folder_id)
+        .await
+    {
+        Ok(lists) => lists.iter().filter_map(Sprint::from_list).collect(),
+        Err(e) => {
+            return error_response(format, StatusCode::BAD_GATEWAY, get_task_error_message(&e));
+        }
+    };
+    sprints.sort_by_key(|s| s.start);
+
+    let now = Utc::now();
+    let sprint_index = if query_params.sprint_list_id.is_empty() {
+        // Falls back to the first sprint when none have started yet.
+        sprints
+            .iter()
+            .rposition(|s| s.start <= now)
+            .or((!sprints.is_empty()).then_some(0))
+    } else {
+        sprints
+            .iter()
+            .position(|s| s.id == query_params.sprint_list_id)
+    };
+    let Some(sprint_index) = sprint_index else {
+        return error_response(
+            format,
+            StatusCode::NOT_FOUND,
+            "No sprint with a start and end date was found in the folder.",
+        );
+    };
+
+    let sprint_count = query_params
+        .sprint_count
+        .unwrap_or(DEFAULT_SPRINT_COUNT)
+        .clamp(1, MAX_SPRINT_COUNT);
+    let sprints = &sprints[(sprint_index + 1).saturating_sub(sprint_count)..=sprint_index];
+
+    let task_filter = TaskFilter::from(task_filter);
+    let sprint_tasks =
+        if dataset.dataset_id.is_empty() {
+            try_join_all(sprints.iter().map(|sprint| {
+                fetch_list_tasks(&session, &app_state, &sprint.id, &task_filter, format)
+            }))
+            .await
+        } else {
+            // The sprints still come from the folder, and each takes the dataset's
+            // tasks that were in its list.
+            fetch_tasks(
+                &session,
+                &app_state,
+                TaskSource::Dataset(&dataset.dataset_id),
+                &task_filter,
+                format,
+            )
+            .await
+            .map(|tasks| {
+                sprints
+                    .iter()
+                    .map(|sprint| {
+                        tasks
+                            .iter()
+                            .filter(|task| task.is_in_list(&sprint.id))
+                            .cloned()
+                            .collect()
+                    })
+                    .collect()
+            })
+        };
+    let sprint_tasks = match sprint_tasks {
+        Ok(sprint_tasks) => sprint_tasks,
+        Err(response) => return response,
+    };
+
+    let sprints: Vec<(Sprint, Vec<Task>)> = sprints
+        .iter()
+        .cloned()
+        .zip(
+            sprint_tasks
+                .into_iter()
+                .map(|tasks| tasks.into_iter().map(Task::from).collect()),
+        )
+        .collect();
+
+    let Some(sprint_report) = SprintReport::new(&sprints) else {
+        return error_response(format, StatusCode::NOT_FOUND, "No sprint found.");
+    };
+
+    match format {
+        ReportFormat::Json => json_response(sprint_report),
+        _ => html_response(SprintReportView::from(sprint_report)),
+    }
+}
@@ -0,0 +1,162 @@
+use crate::{
+    AppState,
+    components::{
+        TaskChartsReport,
+        charts::{ScatterChart, StackedBarChart},
+    },
+    domain::{Task, filters::TaskFilter},
+    routes::{
+        TaskFilterParams,
+        lists::{
+            DatasetParams, DurationPolicyParams, ReportFormat, download_query, error_response,
+            fetch_tasks, file_response, html_response, report_duration_policy,
+        },
+    },
+};
+use askama::Template;
+use axum::{
+    extract::{Query, RawQuery, State},
+    http::StatusCode,
+    response::IntoResponse,
+};
+use serde::Deserialize;
+use tower_sessions::Session;
+
+#[derive(Deserialize)]
+pub struct TaskChartsQueryParams {
+    #[serde(default)]
+    list_id: String,
+    /// The chart to download with `?format=svg`, `scatter` or `status`.
+    #[serde(default)]
+    chart: String,
+    #[serde(default)]
+    format: ReportFormat,
+}
+
+/// Renders the task tree's scatter and per-status charts for every task in a
+/// list or dataset.
+pub async fn task_charts(
+    session: Session,
+    State(app_state): State<AppState>,
+    Query(query_params): Query<TaskChartsQueryParams>,
+    Query(task_filter): Query<TaskFilterParams>,
+    Query(dataset): Query<DatasetParams>,
+    Query(duration_policy): Query<DurationPolicyParams>,
+    RawQuery(raw_query): RawQuery,
+) -> impl IntoResponse {
+    let format = query_params.format;
+    if !matches!(format, ReportFormat::Html | ReportFormat::Svg) {
+        return error_response(
+            format,
+            StatusCode::BAD_REQUEST,
+            "The task charts are only available as HTML or SVG.",
+        );
+    }
+
+    let duration_policy =
+        match report_duration_policy(&session, &app_state, &duration_policy, format).await {
+            Ok(duration_policy) => duration_policy,
+            Err(response) => return response,
+        };
+
+    let tasks = match fetch_tasks(
+        &session,
+        &app_state,
+        dataset.or_list(&query_params.list_id),
+        &TaskFilter::from(task_filter),
+        format,
+    )
+    .await
+    {
+        Ok(tasks) => tasks,
+        Err(response) => return response,
+    };
+
+    let tasks: Vec<Task> = tasks
+        .into_iter()
+        .map(|task| Task::new(task, duration_policy.as_ref()))
+        .collect();
+
+    match format {
+        ReportFormat::Svg => {
+            let svg = match query_params.chart.as_str() {
+                "scatter" => ScatterChart::from_tasks(&tasks).render(),
+                "status" => StackedBarChart::from_tasks(&tasks).render(),
+                _ => {
+                    return error_response(format, StatusCode::BAD_REQUEST, "Unknown chart.");
+                }
+            };
+            match svg {
+                Ok(svg) => file_response(
+                    "image/svg+xml",
+                    format!("{}-{}.svg", query_params.list_id, query_params.chart),
+                    svg,
+                ),
+                Err(_) => {
+                    (StatusCode::INTERNAL_SERVER_ERROR, "Internal Server Error").into_response()
+                }
+            }
+        }
+        _ => html_response(TaskChartsReport {
+            duration_policy: duration_policy.record().description(),
+            task_count: tasks.len(),
+            scatter_chart: ScatterChart::from_tasks(&tasks),
+            status_chart: StackedBarChart::from_tasks(&tasks),
+            query: download_query(raw_query, &["chart"]),
+        }),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        routes::testing::{TestApp, fake_task},
+        services::clickup::FakeClickUpApi,
+    };
+    use axum::http::{StatusCode, header};
+
+    fn app() -> TestApp {
+        TestApp::new(FakeClickUpApi::new().with_list(
+            "list",
+            vec![fake_task("a", 1.0, &[]), fake_task("b", 3.0, &[])],
+        ))
+    }
+
+    #[tokio::test]
+    async fn embeds_both_charts_for_the_list() {
+        let mut app = app();
+        app.sign_in().await;
+
+        let (status, _, body) = app
+            .get("/lists/task-charts?list_id=list&tags=&time_formula=full_time")
+            .await;
+
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(body.matches("<svg").count(), 2);
+        assert!(body.contains("Task a - points: 1, days: 2"));
+        assert!(body.contains("Task b - points: 3, days: 2"));
+        assert!(body.contains(
+            "/lists/task-charts?list_id=list&#38;tags=&#38;time_formula=full_time&chart=status&format=svg"
+        ));
+    }
+
+    #[tokio::test]
+    async fn downloads_a_chart_as_svg() {
+        let mut app = app();
+        app.sign_in().await;
+
+        let (status, headers, body) = app
+            .get("/lists/task-charts?list_id=list&chart=status&format=svg")
+            .await;
+
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(headers[header::CONTENT_TYPE], "image/svg+xml");
+        assert!(body.starts_with("<svg"));
+        assert!(body.contains("in progress: 2 days"));
+
+        let (status, _, _) = app
+            .get("/lists/task-charts?list_id=list&chart=pie&format=svg")
+            .await;
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+    }
+}
@@ -0,0 +1,179 @@
+use crate::{
+    AppState,
+    components::{VelocityReport, charts::LineChart},
+    constants::session::CLICK_UP_AUTH_TOKEN,
+    domain::{
+        Task,
+        filters::TaskFilter,
+        sprints::Sprint,
+        velocity::{VelocityMetric, VelocityPeriod, VelocityTrend},
+    },
+    routes::{
+        TaskFilterParams,
+        lists::{
+            DatasetParams, ReportFormat, TaskSource, download_query, empty_string_as_none,
+            error_response, fetch_tasks, file_response, html_response, json_response,
+        },
+        task::get_task_error_message,
+    },
+};
+use askama::Template;
+use axum::{
+    extract::{Query, RawQuery, State},
+    http::StatusCode,
+    response::IntoResponse,
+};
+use chrono::Utc;
+use serde::Deserialize;
+use tower_sessions::Session;
+
+const DEFAULT_PERIOD_COUNT: usize = 12;
+const MAX_PERIOD_COUNT: usize = 104;
+const DEFAULT_WINDOW: usize = 4;
+const MAX_WINDOW: usize = 12;
+
+#[derive(Deserialize, Default, Clone, Copy, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum VelocityPeriodKind {
+    #[default]
+    Week,
+    /// The sprints of the Sprints folder given as `folder_id`.
+    Sprint,
+}
+
+#[derive(Deserialize)]
+pub struct VelocityQueryParams {
+    /// One of `list_id`, `space_id` or `folder_id` picks the tasks.
+    #[serde(default)]
+    list_id: String,
+    #[serde(default)]
+    space_id: String,
+    #[serde(default)]
+    folder_id: String,
+    #[serde(default)]
+    period: VelocityPeriodKind,
+    #[serde(default, deserialize_with = "empty_string_as_none")]
+    periods: Option<usize>,
+    #[serde(default, deserialize_with = "empty_string_as_none")]
+    window: Option<usize>,
+    #[serde(default)]
+    metric: VelocityMetric,
+    #[serde(default)]
+    format: ReportFormat,
+}
+
+pub async fn velocity(
+    session: Session,
+    State(app_state): State<AppState>,
+    Query(query_params): Query<VelocityQueryParams>,
+    Query(task_filter): Query<TaskFilterParams>,
+    Query(dataset): Query<DatasetParams>,
+    RawQuery(raw_query): RawQuery,
+) -> impl IntoResponse {
+    let format = query_params.format;
+
+    let source = if !dataset.dataset_id.is_empty() {
+        TaskSource::Dataset(&dataset.dataset_id)
+    } else if !query_params.list_id.is_empty() {
+        TaskSource::List(&query_params.list_id)
+    } else if !query_params.space_id.is_empty() {
+        TaskSource::Space(&query_params.space_id)
+    } else if !query_params.folder_id.is_empty() {
+        TaskSource::Folder(&query_params.folder_id)
+    } else {
+        return error_response(
+            format,
+            StatusCode::BAD_REQUEST,
+            "Missing list, space or folder id.",
+        );
+    };
+
+    let period_count = query_params
+        .periods
+        .unwrap_or(DEFAULT_PERIOD_COUNT)
+        .clamp(1, MAX_PERIOD_COUNT);
+    let window = query_params
+        .window
+        .unwrap_or(DEFAULT_WINDOW)
+        .clamp(1, MAX_WINDOW);
+
+    let periods = match query_params.period {
+        VelocityPeriodKind::Week => VelocityPeriod::weeks(Utc::now().date_naive(), period_count),
+        VelocityPeriodKind::Sprint => {
+            if query_params.folder_id.is_empty() {
+                return error_response(
+                    format,
+                    StatusCode::BAD_REQUEST,
+                    "Velocity per sprint needs the Sprints folder id.",
+                );
+            }
+
+            let token: String = match session.get(CLICK_UP_AUTH_TOKEN).await {
+                Ok(Some(token)) => token,
+                Err(_) | Ok(None) => {
+                    return (StatusCode::INTERNAL_SERVER_ERROR, "Internal Server Error.")
+                        .into_response();
+                }
+            };
+
+            let mut sprints: Vec<Sprint> = match app_state
+                .click_up_service
+                .get_folder_lists(&token, &query_params.folder_id)
+                .await
+            {
+                Ok(lists) => lists.iter().filter_map(Sprint::from_list).collect(),
+                Err(e) => {
+                    return error_response(
+                        format,
+                        StatusCode::BAD_GATEWAY,
+                        get_task_error_message(&e),
+                    );
+                }
+            };
+            let now = Utc::now();
+            sprints.retain(|s| s.start <= now);
+            sprints.sort_by_key(|s| s.start);
+
+            let first = sprints.len().saturating_sub(period_count);
+            sprints[first..].iter().map(VelocityPeriod::from).collect()
+        }
+    };
+
+    let tasks = match fetch_tasks(
+        &session,
+        &app_state,
+        source,
+        &TaskFilter::from(task_filter),
+        format,
+    )
+    .await
+    {
+        Ok(tasks) => tasks,
+        Err(response) => return response,
+    };
+    let tasks: Vec<Task> = tasks.into_iter().map(Task::from).collect();
+
+    let velocity_trend = VelocityTrend::new(&tasks, periods, window);
+
+    match format {
+        ReportFormat::Json => json_response(velocity_trend),
+        ReportFormat::Csv => file_response(
+            "text/csv",
+            "velocity.csv".to_string(),
+            velocity_trend.to_csv(),
+        ),
+        ReportFormat::Svg => {
+            match LineChart::from_velocity_trend(&velocity_trend, query_params.metric).render() {
+                Ok(svg) => file_response("image/svg+xml", "velocity.svg".to_string(), svg),
+                Err(_) => {
+                    (StatusCode::INTERNAL_SERVER_ERROR, "Internal Server Error").into_response()
+                }
+            }
+        }
+        ReportFormat::Html => {
+            // The report links to a download per metric.
+            let query = download_query(raw_query, &["metric"]);
+            html_response(VelocityReport::new(&velocity_trend, query))
+        }
+    }
+}
@@ -1,11 +1,75 @@
 mod health;
+mod jobs;
+pub mod lists;
 mod login;
 mod oauth_redirect;
-mod task;
+mod offline;
 pub mod pages;
 pub mod session;
+mod task;
+#[cfg(test)]
+mod testing;
+mod workspace_settings;
 
-pub use health::health;
+pub use health::{health, live, ready};
+pub use jobs::{get_job, get_job_assignees, get_job_chart, get_job_events};
 pub use login::login;
 pub use oauth_redirect::oauth_redirect;
+pub use offline::{MAX_OFFLINE_DATASET_BYTES, post_offline_dataset, post_offline_task};
 pub use task::task;
+pub use workspace_settings::{
+    get_estimate_field, get_working_hours, put_calendar, put_estimate_field, put_status_category,
+    put_working_hours,
+};
+
+use crate::domain::filters::TaskFilter;
+use serde::Deserialize;
+
+/// A checkbox form field, which browsers send as `on` when checked and leave
+/// out entirely otherwise. API callers may also send `true`.
+#[derive(Deserialize)]
+#[serde(from = "Option<String>")]
+pub(crate) struct HtmlCheckbox(pub bool);
+
+impl From<Option<String>> for HtmlCheckbox {
+    fn from(value: Option<String>) -> Self {
+        match value {
+            Some(v) => match v.as_str() {
+                "on" | "true" => HtmlCheckbox(true),
+                _ => HtmlCheckbox(false),
+            },
+            None => HtmlCheckbox(false),
+        }
+    }
+}
+
+/// Comma separated include and exclude lists, turned into a [`TaskFilter`].
+/// Shared by the task form and the list report query strings.
+#[derive(Deserialize)]
+pub struct TaskFilterParams {
+    #[serde(default)]
+    include_tags: String,
+    #[serde(default)]
+    exclude_tags: String,
+    #[serde(default)]
+    include_priorities: String,
+    #[serde(default)]
+    exclude_priorities: String,
+    #[serde(default)]
+    include_statuses: String,
+    #[serde(default)]
+    exclude_statuses: String,
+}
+
+impl From<TaskFilterParams> for TaskFilter {
+    fn from(value: TaskFilterParams) -> Self {
+        Self {
+            include_tags: TaskFilter::parse_list(&value.include_tags),
+            exclude_tags: TaskFilter::parse_list(&value.exclude_tags),
+            include_priorities: TaskFilter::parse_list(&value.include_priorities),
+            exclude_priorities: TaskFilter::parse_list(&value.exclude_priorities),
+            include_statuses: TaskFilter::parse_list(&value.include_statuses),
+            exclude_statuses: TaskFilter::parse_list(&value.exclude_statuses),
+        }
+    }
+}
@@ -37,3 +37,25 @@ pub async fn oauth_redirect(
         Err(_) => (StatusCode::INTERNAL_SERVER_ERROR, "Internal Server Error").into_response(),
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use crate::{routes::testing::TestApp, services::clickup::FakeClickUpApi};
+    use axum::http::{StatusCode, header};
+
+    #[tokio::test]
+    async fn stores_the_exchanged_token_and_redirects_home() {
+        let mut app = TestApp::new(FakeClickUpApi::new().with_access_token("token"));
+
+        let (status, headers, _) = app.get("/oauth/redirect?code=code").await;
+
+        assert_eq!(status, StatusCode::TEMPORARY_REDIRECT);
+        assert_eq!(headers[header::LOCATION], "/home");
+        // Choosing a workspace reads the token back from the session.
+        let (status, _, body) = app
+            .send_form("PUT", "/session/workspace", "workspace_id=9001")
+            .await;
+        assert_eq!(status, StatusCode::OK);
+        assert!(body.contains("Workspace"));
+    }
+}
@@ -0,0 +1,183 @@
+use crate::{
+    AppState,
+    components::{Message, OfflineDatasetView},
+    constants::session::CLICK_UP_AUTH_TOKEN,
+    domain::{
+        AnalysisOptions, analyze_task,
+        filters::TaskFilter,
+        offline::{OfflineDataset, OfflineDatasetError},
+    },
+    routes::{
+        TaskFilterParams,
+        lists::html_response,
+        task::{duration_policy, job_progress_response},
+        workspace_settings::current_workspace_settings,
+    },
+};
+use axum::{
+    Form,
+    body::Bytes,
+    extract::{Path, State},
+    http::StatusCode,
+    response::{Html, IntoResponse},
+};
+use serde::Deserialize;
+use std::sync::Arc;
+use tower_sessions::Session;
+
+/// Exports are usually larger than the default 2MB body limit.
+pub const MAX_OFFLINE_DATASET_BYTES: usize = 64 * 1024 * 1024;
+
+/// Takes the raw body of an uploaded `.tar` archive or `.json` file.
+pub async fn post_offline_dataset(
+    session: Session,
+    State(app_state): State<AppState>,
+    body: Bytes,
+) -> impl IntoResponse {
+    // Datasets are held in memory, so only signed in users may upload them,
+    // within their session's quota.
+    let owner = match (
+        session.get::<String>(CLICK_UP_AUTH_TOKEN).await,
+        session.id(),
+    ) {
+        (Ok(Some(_)), Some(session_id)) => session_id.to_string(),
+        _ => {
+            return (StatusCode::INTERNAL_SERVER_ERROR, "Internal Server Error.").into_response();
+        }
+    };
+
+    let size = body.len();
+    let dataset = match tokio::task::spawn_blocking(move || OfflineDataset::from_upload(&body))
+        .await
+    {
+        Ok(Ok(dataset)) => Arc::new(dataset),
+        Ok(Err(e)) => {
+            let message = get_offline_dataset_error_message(&e);
+            return html_response(Message { message: &message });
+        }
+        Err(_) => {
+            return (StatusCode::INTERNAL_SERVER_ERROR, "Internal Server Error.").into_response();
+        }
+    };
+
+    let dataset_id = match app_state
+        .offline_dataset_service
+        .insert(&owner, dataset.clone(), size)
+    {
+        Ok(dataset_id) => dataset_id,
+        Err(_) => {
+            return html_response(Message {
+                message: "Too many datasets are being analysed right now, please try again later.",
+            });
+        }
+    };
+
+    html_response(OfflineDatasetView::new(dataset_id, &dataset))
+}
+
+#[derive(Deserialize)]
+pub struct PostOfflineTaskBody {
+    task_id: String,
+    #[serde(default)]
+    time_formula: String,
+    #[serde(flatten)]
+    task_filter: TaskFilterParams,
+}
+
+pub async fn post_offline_task(
+    session: Session,
+    State(app_state): State<AppState>,
+    Path(dataset_id): Path<String>,
+    Form(body): Form<PostOfflineTaskBody>,
+) -> impl IntoResponse {
+    if body.task_id.is_empty() {
+        return (StatusCode::OK, Html("<p>Missing task id.</p>")).into_response();
+    }
+    let dataset = match app_state.offline_dataset_service.get(&dataset_id) {
+        Some(dataset) => dataset,
+        None => {
+            return (
+                StatusCode::OK,
+                Html("<p>This dataset could not be found, it may have expired.</p>"),
+            )
+                .into_response();
+        }
+    };
+
+    let (_, settings) = match current_workspace_settings(&session, &app_state).await {
+        Ok(workspace_settings) => workspace_settings,
+        Err(response) => return response,
+    };
+
+    let duration_policy = match duration_policy(&body.time_formula, &settings) {
+        Ok(duration_policy) => duration_policy,
+        Err(message) => return html_response(Message { message }),
+    };
+
+    let task_id = body.task_id.clone();
+    let analysis_options = AnalysisOptions {
+        duration_policy,
+        task_filter: TaskFilter::from(body.task_filter),
+        estimate_field: settings.estimate_field,
+        status_categories: settings.status_categories,
+    };
+
+    let job_id = app_state
+        .job_service
+        .spawn(body.task_id, move |_| async move {
+            let task = dataset.task_tree(&task_id).ok_or(
+                "This task isn't in the dataset, or was saved without its time in status.",
+            )?;
+
+            Ok(analyze_task(task, &analysis_options))
+        });
+
+    job_progress_response(&session, job_id).await
+}
+
+fn get_offline_dataset_error_message(e: &OfflineDatasetError) -> String {
+    match e {
+        OfflineDatasetError::ParseError(name, e) => format!("{name} could not be read: {e}."),
+        OfflineDatasetError::UnrecognizedFile(name) => {
+            format!("{name} is neither a task, a list of tasks nor time in status.")
+        }
+        OfflineDatasetError::InvalidArchive => {
+            "Upload a single .json file or an uncompressed .tar archive.".to_string()
+        }
+        OfflineDatasetError::Empty => "The upload doesn't contain any task.".to_string(),
+        e => {
+            println!("{e:?}");
+            "Something went wrong, please try again".to_string()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{routes::testing::TestApp, services::clickup::FakeClickUpApi};
+    use axum::http::StatusCode;
+
+    #[tokio::test]
+    async fn uploads_need_a_session() {
+        let mut app = TestApp::new(FakeClickUpApi::new());
+
+        let (status, _, _) = app.send_form("POST", "/offline/datasets", "[]").await;
+
+        assert_eq!(status, StatusCode::INTERNAL_SERVER_ERROR);
+    }
+
+    #[tokio::test]
+    async fn escapes_the_parse_error() {
+        let mut app = TestApp::new(FakeClickUpApi::new());
+        app.sign_in().await;
+
+        let (status, _, body) = app
+            .send_form("POST", "/offline/datasets", r#"{"tasks": "<script>"}"#)
+            .await;
+
+        assert_eq!(status, StatusCode::OK);
+        assert!(body.starts_with("<p>upload.json could not be read"));
+        assert!(body.contains("&#60;script&#62;") || body.contains("&lt;script&gt;"));
+        assert!(!body.contains("<script>"));
+    }
+}
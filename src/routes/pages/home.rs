@@ -1,4 +1,9 @@
-use crate::{AppState, components::Workspace, constants::session::{CLICK_UP_AUTH_TOKEN, CURRENT_WORKSPACE_ID}};
+use crate::{
+    AppState,
+    components::Workspace,
+    constants::session::{CLICK_UP_AUTH_TOKEN, CURRENT_WORKSPACE_ID, RECENT_JOB_IDS},
+    services::jobs::JobState,
+};
 use askama::Template;
 use axum::{
     extract::State,
@@ -12,6 +17,14 @@ use tower_sessions::Session;
 struct HomePage {
     current_workspace_id: String,
     workspaces: Vec<Workspace>,
+    recent_jobs: Vec<RecentJob>,
+}
+
+struct RecentJob {
+    id: String,
+    label: String,
+    created_at: String,
+    status: &'static str,
 }
 
 pub async fn home(session: Session, State(app_state): State<AppState>) -> impl IntoResponse {
@@ -34,14 +47,35 @@ pub async fn home(session: Session, State(app_state): State<AppState>) -> impl I
                 name: t.name.clone(),
             })
             .collect(),
-        Err(e) => {
+        Err(_) => {
             return (StatusCode::INTERNAL_SERVER_ERROR, "Internal Server Error").into_response();
         }
     };
 
+    let recent_job_ids: Vec<String> = session
+        .get(RECENT_JOB_IDS)
+        .await
+        .unwrap_or_default()
+        .unwrap_or_default();
+    let recent_jobs: Vec<RecentJob> = recent_job_ids
+        .iter()
+        .filter_map(|id| app_state.job_service.get(id))
+        .map(|job| RecentJob {
+            id: job.id,
+            label: job.label,
+            created_at: job.created_at.format("%Y-%m-%d %H:%M UTC").to_string(),
+            status: match job.state {
+                JobState::Running => "running",
+                JobState::Completed(_) => "done",
+                JobState::Failed(_) => "failed",
+            },
+        })
+        .collect();
+
     let home_page = HomePage {
         current_workspace_id,
         workspaces,
+        recent_jobs,
     };
 
     let html_response_body = match home_page.render() {
@@ -23,18 +23,13 @@ pub async fn put_workspace(
     State(app_state): State<AppState>,
     Form(body): Form<PutWorkspaceBody>,
 ) -> impl IntoResponse {
-    if let Err(_) = session
-        .insert(
-            crate::constants::session::CURRENT_WORKSPACE_ID,
-            &body.workspace_id,
-        )
-        .await
-    {
-        return (StatusCode::INTERNAL_SERVER_ERROR, "Internal Server Error").into_response();
+    let token: String = match session.get(CLICK_UP_AUTH_TOKEN).await {
+        Ok(Some(token)) => token,
+        Err(_) | Ok(None) => {
+            return (StatusCode::INTERNAL_SERVER_ERROR, "Internal Server Error").into_response();
+        }
     };
 
-    let token: String = session.get(CLICK_UP_AUTH_TOKEN).await.unwrap().unwrap();
-
     let workspaces: Vec<Workspace> = match app_state
         .click_up_service
         .get_authorized_workspaces(token)
@@ -48,11 +43,28 @@ pub async fn put_workspace(
                 name: t.name.clone(),
             })
             .collect(),
-        Err(e) => {
+        Err(_) => {
             return (StatusCode::INTERNAL_SERVER_ERROR, "Internal Server Error").into_response();
         }
     };
 
+    // Everyone in a workspace shares its settings, so only the workspaces the
+    // token can see may be chosen and have their settings changed.
+    if !workspaces.iter().any(|w| w.id == body.workspace_id) {
+        return (StatusCode::FORBIDDEN, "Forbidden").into_response();
+    }
+
+    if session
+        .insert(
+            crate::constants::session::CURRENT_WORKSPACE_ID,
+            &body.workspace_id,
+        )
+        .await
+        .is_err()
+    {
+        return (StatusCode::INTERNAL_SERVER_ERROR, "Internal Server Error").into_response();
+    };
+
     let workspace_select = WorkspaceSelect {
         current_workspace_id: body.workspace_id,
         workspaces,
@@ -67,3 +79,52 @@ pub async fn put_workspace(
 
     Html(html_response_body).into_response()
 }
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        routes::testing::{TestApp, WORKSPACE_ID},
+        services::clickup::FakeClickUpApi,
+    };
+    use axum::http::StatusCode;
+
+    #[tokio::test]
+    async fn refuses_a_workspace_the_token_cant_see() {
+        let mut app = TestApp::new(FakeClickUpApi::new());
+        app.get("/oauth/redirect?code=code").await;
+
+        let (status, _, _) = app
+            .send_form("PUT", "/session/workspace", "workspace_id=someone-elses")
+            .await;
+        assert_eq!(status, StatusCode::FORBIDDEN);
+
+        let (_, _, body) = app
+            .send_form(
+                "PUT",
+                "/workspace/status-categories",
+                "status=review&category=waiting",
+            )
+            .await;
+        assert_eq!(
+            body.trim(),
+            "<span>Choose a workspace before tagging statuses.</span>"
+        );
+        let settings = app
+            .app_state
+            .workspace_settings_service
+            .get("someone-elses")
+            .await
+            .unwrap();
+        assert!(settings.status_categories.is_empty());
+
+        let (status, _, body) = app
+            .send_form(
+                "PUT",
+                "/session/workspace",
+                &format!("workspace_id={WORKSPACE_ID}"),
+            )
+            .await;
+        assert_eq!(status, StatusCode::OK);
+        assert!(body.contains(WORKSPACE_ID));
+    }
+}
@@ -1,39 +1,43 @@
 use crate::{
     AppState,
-    constants::session::{CLICK_UP_AUTH_TOKEN, CURRENT_WORKSPACE_ID},
-    domain::{Task, apply_no_weekends_formula, generate_points_vs_time_spent_analysis},
-    services::clickup::{ClickUpServiceError, GetTaskRequest},
+    components::{JobProgress, Message},
+    constants::session::{CLICK_UP_AUTH_TOKEN, CURRENT_WORKSPACE_ID, RECENT_JOB_IDS},
+    domain::{
+        AnalysisOptions, analyze_task,
+        duration_policy::{BusinessDays, CustomCalendar, DurationPolicy, FullTime, WorkingTime},
+        filters::TaskFilter,
+    },
+    routes::{
+        HtmlCheckbox, TaskFilterParams, lists::html_response,
+        workspace_settings::current_workspace_settings,
+    },
+    services::{
+        clickup::{ClickUpServiceError, FetchProgress, GetTaskRequest},
+        workspace_settings::WorkspaceSettings,
+    },
 };
+use askama::Template;
 use axum::{
     Form,
     extract::State,
     http::StatusCode,
-    response::{Html, IntoResponse},
+    response::{Html, IntoResponse, Response},
 };
+use chrono_tz::Tz;
 use serde::Deserialize;
+use std::sync::Arc;
 use tower_sessions::Session;
 
-#[derive(Deserialize)]
-#[serde(from = "Option<String>")]
-struct HtmlCheckbox(bool);
-
-impl From<Option<String>> for HtmlCheckbox {
-    fn from(value: Option<String>) -> Self {
-        match value {
-            Some(v) => match v.as_str() {
-                "on" => HtmlCheckbox(true),
-                _ => HtmlCheckbox(false),
-            },
-            None => HtmlCheckbox(false),
-        }
-    }
-}
+const MAX_RECENT_JOBS: usize = 10;
 
 #[derive(Deserialize)]
 pub struct PostTaskResponseBody {
     task_id: String,
-    remove_weekends: HtmlCheckbox,
+    #[serde(default)]
+    time_formula: String,
     use_custom_id: HtmlCheckbox,
+    #[serde(flatten)]
+    task_filter: TaskFilterParams,
 }
 
 pub async fn task(
@@ -58,54 +62,183 @@ pub async fn task(
         }
     };
 
+    let (_, settings) = match current_workspace_settings(&session, &app_state).await {
+        Ok(workspace_settings) => workspace_settings,
+        Err(response) => return response,
+    };
+
     if !body.use_custom_id.0 {
         workspace_id = None
     }
 
-    let task = match app_state
-        .click_up_service
-        .get_task(
-            &token,
-            GetTaskRequest {
-                task_id: body.task_id,
-                workspace_id,
+    let duration_policy = match duration_policy(&body.time_formula, &settings) {
+        Ok(duration_policy) => duration_policy,
+        Err(message) => return html_response(Message { message }),
+    };
+
+    let click_up_service = app_state.click_up_service.clone();
+    let task_tree_limits = app_state.task_tree_limits;
+    let task_id = body.task_id.clone();
+    let analysis_options = AnalysisOptions {
+        duration_policy,
+        task_filter: TaskFilter::from(body.task_filter),
+        estimate_field: settings.estimate_field,
+        status_categories: settings.status_categories,
+    };
+
+    let job_id = app_state
+        .job_service
+        .spawn(body.task_id, move |progress| async move {
+            let task = click_up_service
+                .get_task(
+                    &token,
+                    GetTaskRequest {
+                        task_id,
+                        workspace_id,
+                        progress: Some(progress),
+                        limits: task_tree_limits,
+                    },
+                )
+                .await
+                .map_err(|e| get_task_error_message(&e).to_string())?;
+
+            Ok(analyze_task(task, &analysis_options))
+        });
+
+    job_progress_response(&session, job_id).await
+}
+
+/// Picks the duration policy chosen in the task form, or the message to show
+/// when the workspace isn't set up for it.
+pub(crate) fn duration_policy(
+    time_formula: &str,
+    settings: &WorkspaceSettings,
+) -> Result<Arc<dyn DurationPolicy>, &'static str> {
+    let duration_policy: Arc<dyn DurationPolicy> = match time_formula {
+        "" | "full_time" => Arc::new(FullTime),
+        "business_days" => Arc::new(BusinessDays {
+            time_zone: match &settings.working_hours {
+                Some(working_hours) => working_hours.time_zone,
+                None => Tz::UTC,
             },
-        )
+        }),
+        "working_hours" => match settings.working_hours {
+            Some(working_hours) => Arc::new(WorkingTime { working_hours }),
+            None => {
+                return Err("Set the workspace's working hours before counting only working time.");
+            }
+        },
+        "custom_calendar" => match &settings.calendar {
+            Some(calendar) => Arc::new(CustomCalendar {
+                calendar: calendar.clone(),
+                working_hours: settings.working_hours,
+            }),
+            None => return Err("Set the workspace's calendar before counting with it."),
+        },
+        _ => return Err("Unknown time formula."),
+    };
+
+    Ok(duration_policy)
+}
+
+/// Adds the job to the session's recent analyses and renders its progress.
+pub(crate) async fn job_progress_response(session: &Session, job_id: String) -> Response {
+    let mut recent_job_ids: Vec<String> = match session.get(RECENT_JOB_IDS).await {
+        Ok(recent_job_ids) => recent_job_ids.unwrap_or_default(),
+        Err(_) => {
+            return (StatusCode::INTERNAL_SERVER_ERROR, "Internal Server Error.").into_response();
+        }
+    };
+    recent_job_ids.insert(0, job_id.clone());
+    recent_job_ids.truncate(MAX_RECENT_JOBS);
+    if session
+        .insert(RECENT_JOB_IDS, recent_job_ids)
         .await
+        .is_err()
     {
-        Ok(task) => task,
-        Err(e) => {
-            return match e {
-                ClickUpServiceError::TimeInStatusNotEnabled => (
-                    StatusCode::OK,
-                    Html("<p>Time in status is not enabled for the selected workspace.</p>"),
-                )
-                    .into_response(),
-                ClickUpServiceError::CustomIDError => (
-                    StatusCode::OK,
-                    Html("<p>You might be using a custom id without setting the `Use Custom ID` field to true.</p>"),
-                )
-                    .into_response(),
-                e => {
-                    println!("{e:?}");
-                    return (StatusCode::OK, Html("<p>Something went wrong, please review the information in the form and try again</p>")).into_response()},
-            };
+        return (StatusCode::INTERNAL_SERVER_ERROR, "Internal Server Error.").into_response();
+    }
+
+    let job_progress = JobProgress {
+        job_id,
+        progress: FetchProgress::default(),
+    };
+
+    match job_progress.render() {
+        Ok(html_response_body) => (StatusCode::OK, Html(html_response_body)).into_response(),
+        Err(_) => (StatusCode::INTERNAL_SERVER_ERROR, "Internal Server Error.").into_response(),
+    }
+}
+
+pub(crate) fn get_task_error_message(e: &ClickUpServiceError) -> &'static str {
+    match e {
+        ClickUpServiceError::TimeInStatusNotEnabled => {
+            "Time in status is not enabled for the selected workspace."
+        }
+        ClickUpServiceError::CustomIDError => {
+            "You might be using a custom id without setting the `Use Custom ID` field to true."
         }
+        ClickUpServiceError::Cancelled => {
+            "The server is shutting down, please try again in a moment."
+        }
+        e => {
+            println!("{e:?}");
+            "Something went wrong, please review the information in the form and try again"
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        routes::testing::{TestApp, fake_task},
+        services::{clickup::FakeClickUpApi, jobs::JobState},
     };
+    use axum::http::StatusCode;
 
-    let mut task = Task::from(task);
-    if body.remove_weekends.0 {
-        apply_no_weekends_formula(&mut task);
+    #[tokio::test]
+    async fn analyses_the_task_tree_in_the_background() {
+        let mut app = TestApp::new(
+            FakeClickUpApi::new()
+                .with_task(fake_task("parent", 2.0, &["child"]))
+                .with_task(fake_task("child", 3.0, &[])),
+        );
+        app.sign_in().await;
+
+        let (status, _, body) = app
+            .send_form("POST", "/task", "task_id=parent&time_formula=full_time")
+            .await;
+        assert_eq!(status, StatusCode::OK);
+        app.app_state.job_service.drain().await;
+
+        let job_id = body
+            .strip_prefix(r#"<div id="job_"#)
+            .and_then(|rest| rest.split('"').next())
+            .unwrap();
+        let job = app.app_state.job_service.get(job_id).unwrap();
+        let JobState::Completed(task) = job.state else {
+            panic!("the analysis didn't complete");
+        };
+        assert_eq!(task.name, "Task parent");
+        assert_eq!(task.total_points, 5.0);
+        assert_eq!(task.sub_tasks.len(), 1);
+        assert!(!task.is_incomplete);
+
+        let (status, _, body) = app.get(&format!("/jobs/{job_id}?format=json")).await;
+        assert_eq!(status, StatusCode::OK);
+        assert!(body.contains("Task child"));
     }
-    let task_as_string = generate_points_vs_time_spent_analysis(&task);
 
-    let html_response_body = format!(
-        r#"
-    <pre>
-        {task_as_string}
-    </pre>
-        "#
-    );
+    #[tokio::test]
+    async fn rejects_an_unknown_time_formula() {
+        let mut app = TestApp::new(FakeClickUpApi::new().with_task(fake_task("task", 1.0, &[])));
+        app.sign_in().await;
 
-    (StatusCode::OK, Html(html_response_body)).into_response()
+        let (status, _, body) = app
+            .send_form("POST", "/task", "task_id=task&time_formula=%3Cb%3E")
+            .await;
+
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(body.trim(), "<p>Unknown time formula.</p>");
+    }
 }
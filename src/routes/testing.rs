@@ -0,0 +1,204 @@
+//! Runs the routes against [`FakeClickUpApi`] and in-memory stores, with a
+//! cookie jar of one session.
+
+use crate::{
+    AppState,
+    routes::{
+        get_job, get_job_chart,
+        lists::{cumulative_flow, rework, task_charts},
+        oauth_redirect, post_offline_dataset, put_calendar, put_status_category, put_working_hours,
+        session::put_workspace,
+        task,
+    },
+    services::{
+        clickup::{ClickUpTaskResponseBody, ClickUpWorkspace, FakeClickUpApi, TaskTreeLimits},
+        jobs::JobService,
+        offline_datasets::OfflineDatasetService,
+        workspace_settings::InMemoryWorkspaceSettingsStore,
+    },
+};
+use axum::{
+    Router,
+    body::{Body, to_bytes},
+    http::{HeaderMap, Request, StatusCode, header},
+    routing::{get, post, put},
+};
+use serde_json::json;
+use std::sync::Arc;
+use tower::ServiceExt;
+use tower_sessions::{MemoryStore, SessionManagerLayer};
+use tower_sessions_redis_store::fred::prelude::{Config, Pool};
+
+pub const WORKSPACE_ID: &str = "9001";
+
+pub struct TestApp {
+    pub app_state: AppState,
+    router: Router,
+    cookie: Option<String>,
+}
+
+impl TestApp {
+    /// Serves `click_up_api` with one workspace, [`WORKSPACE_ID`].
+    pub fn new(click_up_api: FakeClickUpApi) -> Self {
+        let click_up_api = click_up_api.with_workspace(ClickUpWorkspace {
+            id: WORKSPACE_ID.to_string(),
+            name: "Workspace".to_string(),
+            color: String::new(),
+            avatar: None,
+            members: vec![],
+        });
+        let app_state = AppState {
+            click_up_service: Arc::new(click_up_api),
+            task_tree_limits: TaskTreeLimits::default(),
+            job_service: JobService::new(),
+            workspace_settings_service: Arc::new(InMemoryWorkspaceSettingsStore::new()),
+            offline_dataset_service: OfflineDatasetService::new(),
+            // Never connected, the routes under test don't use it.
+            redis_pool: Pool::new(Config::default(), None, None, None, 1).unwrap(),
+        };
+
+        let router = Router::new()
+            .route("/oauth/redirect", get(oauth_redirect))
+            .route("/task", post(task))
+            .route("/jobs/{job_id}", get(get_job))
+            .route("/jobs/{job_id}/charts/{chart}", get(get_job_chart))
+            .route("/session/workspace", put(put_workspace))
+            .route("/lists/cumulative-flow", get(cumulative_flow))
+            .route("/lists/rework", get(rework))
+            .route("/lists/task-charts", get(task_charts))
+            .route("/offline/datasets", post(post_offline_dataset))
+            .route("/workspace/status-categories", put(put_status_category))
+            .route("/workspace/calendar", put(put_calendar))
+            .route("/workspace/working-hours", put(put_working_hours))
+            .layer(SessionManagerLayer::new(MemoryStore::default()))
+            .with_state(app_state.clone());
+
+        Self {
+            app_state,
+            router,
+            cookie: None,
+        }
+    }
+
+    /// Signs in through the OAuth redirect and picks [`WORKSPACE_ID`].
+    pub async fn sign_in(&mut self) {
+        let (status, _, _) = self.get("/oauth/redirect?code=code").await;
+        assert_eq!(status, StatusCode::TEMPORARY_REDIRECT);
+        let (status, _, _) = self
+            .send_form(
+                "PUT",
+                "/session/workspace",
+                &format!("workspace_id={WORKSPACE_ID}"),
+            )
+            .await;
+        assert_eq!(status, StatusCode::OK);
+    }
+
+    /// Drops the session cookie, so the next request starts a new session.
+    pub fn forget_session(&mut self) {
+        self.cookie = None;
+    }
+
+    pub async fn get(&mut self, uri: &str) -> (StatusCode, HeaderMap, String) {
+        self.send(Request::get(uri).body(Body::empty()).unwrap())
+            .await
+    }
+
+    pub async fn send_form(
+        &mut self,
+        method: &str,
+        uri: &str,
+        body: &str,
+    ) -> (StatusCode, HeaderMap, String) {
+        self.send(
+            Request::builder()
+                .method(method)
+                .uri(uri)
+                .header(header::CONTENT_TYPE, "application/x-www-form-urlencoded")
+                .body(Body::from(body.to_string()))
+                .unwrap(),
+        )
+        .await
+    }
+
+    /// Sends the request with the session cookie, keeping any new one.
+    pub async fn send(&mut self, mut request: Request<Body>) -> (StatusCode, HeaderMap, String) {
+        if let Some(cookie) = &self.cookie {
+            request
+                .headers_mut()
+                .insert(header::COOKIE, cookie.parse().unwrap());
+        }
+
+        let response = self.router.clone().oneshot(request).await.unwrap();
+        if let Some(set_cookie) = response.headers().get(header::SET_COOKIE) {
+            let set_cookie = set_cookie.to_str().unwrap();
+            self.cookie = set_cookie.split(';').next().map(str::to_string);
+        }
+
+        let status = response.status();
+        let headers = response.headers().clone();
+        let body = to_bytes(response.into_body(), usize::MAX).await.unwrap();
+
+        (status, headers, String::from_utf8(body.to_vec()).unwrap())
+    }
+}
+
+/// A task that spent a day in `to do`, two in `in progress` and is done, with
+/// the given sub tasks listed.
+pub fn fake_task(id: &str, points: f32, sub_task_ids: &[&str]) -> ClickUpTaskResponseBody {
+    let sub_tasks: Vec<_> = sub_task_ids
+        .iter()
+        .map(|sub_task_id| {
+            json!({
+                "id": sub_task_id,
+                "custom_id": null,
+                "name": format!("Task {sub_task_id}"),
+                "points": null,
+                "date_created": "1740960000000"
+            })
+        })
+        .collect();
+
+    serde_json::from_value(json!({
+        "id": id,
+        "custom_id": null,
+        "name": format!("Task {id}"),
+        "url": format!("https://app.clickup.com/t/{id}"),
+        "text_content": "",
+        "description": "",
+        "points": points,
+        "time_estimate": null,
+        "time_spent": null,
+        "date_created": "1740960000000",
+        "subtasks": (!sub_tasks.is_empty()).then_some(sub_tasks),
+        "priority": null,
+        "status": { "status": "done", "type": "done" },
+        "time_in_status": {
+            "current_status": {
+                "status": "done",
+                "total_time": { "by_minute": 0, "since": "1741219200000" }
+            },
+            "status_history": [
+                {
+                    "status": "to do",
+                    "type": "open",
+                    "orderindex": 0,
+                    "total_time": { "by_minute": 1440, "since": "1740960000000" }
+                },
+                {
+                    "status": "in progress",
+                    "type": "custom",
+                    "orderindex": 5,
+                    "total_time": { "by_minute": 2880, "since": "1741046400000" }
+                },
+                {
+                    "status": "done",
+                    "type": "done",
+                    "orderindex": 7,
+                    "total_time": { "by_minute": 0, "since": "1741219200000" }
+                }
+            ]
+        }
+    }))
+    .unwrap()
+}
@@ -0,0 +1,436 @@
+use crate::{
+    AppState,
+    components::{EstimateFieldSettings, InlineMessage, WorkingHoursSettings},
+    constants::session::{CLICK_UP_AUTH_TOKEN, CURRENT_WORKSPACE_ID},
+    routes::{lists::html_response, task::get_task_error_message},
+    services::workspace_settings::{
+        EstimateField, StatusCategory, WorkCalendar, WorkingHours, WorkspaceSettings,
+        WorkspaceSettingsUpdate,
+    },
+};
+use axum::{
+    Form,
+    extract::{Query, State},
+    http::StatusCode,
+    response::{Html, IntoResponse, Response},
+};
+use chrono::{NaiveDate, NaiveTime, Weekday};
+use serde::Deserialize;
+use std::collections::HashMap;
+use tower_sessions::Session;
+
+/// Loads the settings of the session's workspace, falling back to the
+/// defaults when no workspace has been chosen yet.
+pub(crate) async fn current_workspace_settings(
+    session: &Session,
+    app_state: &AppState,
+) -> Result<(Option<String>, WorkspaceSettings), Response> {
+    let workspace_id: Option<String> = match session.get(CURRENT_WORKSPACE_ID).await {
+        Ok(workspace_id) => workspace_id,
+        Err(_) => {
+            return Err(
+                (StatusCode::INTERNAL_SERVER_ERROR, "Internal Server Error.").into_response(),
+            );
+        }
+    };
+
+    let settings = match &workspace_id {
+        Some(workspace_id) => match app_state.workspace_settings_service.get(workspace_id).await {
+            Ok(settings) => settings,
+            Err(e) => {
+                println!("{e:?}");
+                return Err(
+                    (StatusCode::INTERNAL_SERVER_ERROR, "Internal Server Error.").into_response(),
+                );
+            }
+        },
+        None => WorkspaceSettings::default(),
+    };
+
+    Ok((workspace_id, settings))
+}
+
+/// Saves `update` to the session's workspace, or asks to choose a workspace
+/// first with `no_workspace_message`.
+async fn update_workspace_settings(
+    session: &Session,
+    app_state: &AppState,
+    update: WorkspaceSettingsUpdate,
+    no_workspace_message: &str,
+) -> Response {
+    let workspace_id: String = match session.get(CURRENT_WORKSPACE_ID).await {
+        Ok(Some(workspace_id)) => workspace_id,
+        Ok(None) => {
+            return html_response(InlineMessage {
+                message: no_workspace_message,
+            });
+        }
+        Err(_) => {
+            return (StatusCode::INTERNAL_SERVER_ERROR, "Internal Server Error.").into_response();
+        }
+    };
+
+    match app_state
+        .workspace_settings_service
+        .update(&workspace_id, update)
+        .await
+    {
+        Ok(()) => (StatusCode::OK, Html("<span>Saved.</span>")).into_response(),
+        Err(e) => {
+            println!("{e:?}");
+            (StatusCode::INTERNAL_SERVER_ERROR, "Internal Server Error.").into_response()
+        }
+    }
+}
+
+#[derive(Deserialize)]
+pub struct PutStatusCategoryBody {
+    status: String,
+    /// Empty to go back to the default for the status.
+    category: String,
+}
+
+pub async fn put_status_category(
+    session: Session,
+    State(app_state): State<AppState>,
+    Form(body): Form<PutStatusCategoryBody>,
+) -> impl IntoResponse {
+    let category = match body.category.as_str() {
+        "active" => Some(StatusCategory::Active),
+        "waiting" => Some(StatusCategory::Waiting),
+        "" => None,
+        _ => return (StatusCode::BAD_REQUEST, "Unknown status category.").into_response(),
+    };
+
+    update_workspace_settings(
+        &session,
+        &app_state,
+        WorkspaceSettingsUpdate::StatusCategory(body.status, category),
+        "Choose a workspace before tagging statuses.",
+    )
+    .await
+}
+
+#[derive(Deserialize)]
+pub struct GetEstimateFieldQueryParams {
+    list_id: String,
+}
+
+/// Lists the custom fields of a list that could be used as the workspace's
+/// estimate, since ClickUp only exposes custom fields per list.
+pub async fn get_estimate_field(
+    session: Session,
+    State(app_state): State<AppState>,
+    Query(query_params): Query<GetEstimateFieldQueryParams>,
+) -> impl IntoResponse {
+    if query_params.list_id.is_empty() {
+        return (
+            StatusCode::OK,
+            Html("<p>Input a list ID to choose one of its custom fields.</p>"),
+        )
+            .into_response();
+    }
+
+    let token: String = match session.get(CLICK_UP_AUTH_TOKEN).await {
+        Ok(Some(token)) => token,
+        Err(_) | Ok(None) => {
+            return (StatusCode::INTERNAL_SERVER_ERROR, "Internal Server Error.").into_response();
+        }
+    };
+
+    let settings = match current_workspace_settings(&session, &app_state).await {
+        Ok((Some(_), settings)) => settings,
+        Ok((None, _)) => {
+            return (
+                StatusCode::OK,
+                Html("<p>Choose a workspace before choosing an estimate field.</p>"),
+            )
+                .into_response();
+        }
+        Err(response) => return response,
+    };
+
+    let fields = match app_state
+        .click_up_service
+        .get_list_custom_fields(&token, &query_params.list_id)
+        .await
+    {
+        Ok(fields) => fields,
+        Err(e) => {
+            return (
+                StatusCode::OK,
+                Html(format!("<p>{}</p>", get_task_error_message(&e))),
+            )
+                .into_response();
+        }
+    };
+
+    html_response(EstimateFieldSettings::new(
+        fields,
+        settings.estimate_field.as_ref(),
+    ))
+}
+
+/// Sets the workspace's estimate field from a form with `field_id`,
+/// `field_name` and a `points[<option name>]` entry per dropdown option. An
+/// empty `field_id` goes back to sprint points.
+pub async fn put_estimate_field(
+    session: Session,
+    State(app_state): State<AppState>,
+    Form(body): Form<Vec<(String, String)>>,
+) -> impl IntoResponse {
+    let mut field_id = String::new();
+    let mut field_name = String::new();
+    let mut option_points = HashMap::new();
+    for (key, value) in body {
+        match key.as_str() {
+            "field_id" => field_id = value,
+            "field_name" => field_name = value,
+            _ => {
+                let option_name = match key
+                    .strip_prefix("points[")
+                    .and_then(|k| k.strip_suffix(']'))
+                {
+                    Some(option_name) => option_name,
+                    None => continue,
+                };
+                if value.is_empty() {
+                    continue;
+                }
+                match value.parse::<f32>() {
+                    Ok(points) if points >= 0.0 => {
+                        option_points.insert(option_name.to_lowercase(), points);
+                    }
+                    _ => {
+                        return (
+                            StatusCode::OK,
+                            Html("<span>Points must be zero or more.</span>"),
+                        )
+                            .into_response();
+                    }
+                }
+            }
+        }
+    }
+
+    let estimate_field = if field_id.is_empty() {
+        None
+    } else {
+        Some(EstimateField {
+            field_id,
+            field_name,
+            option_points,
+        })
+    };
+
+    update_workspace_settings(
+        &session,
+        &app_state,
+        WorkspaceSettingsUpdate::EstimateField(estimate_field),
+        "Choose a workspace before choosing an estimate field.",
+    )
+    .await
+}
+
+pub async fn get_working_hours(
+    session: Session,
+    State(app_state): State<AppState>,
+) -> impl IntoResponse {
+    match current_workspace_settings(&session, &app_state).await {
+        Ok((Some(_), settings)) => html_response(WorkingHoursSettings::new(
+            settings.working_hours.as_ref(),
+            settings.calendar.as_ref(),
+        )),
+        Ok((None, _)) => (
+            StatusCode::OK,
+            Html("<p>Choose a workspace before setting its working hours.</p>"),
+        )
+            .into_response(),
+        Err(response) => response,
+    }
+}
+
+#[derive(Deserialize)]
+pub struct PutWorkingHoursBody {
+    /// Both empty to go back to counting wall-clock time.
+    start: String,
+    end: String,
+    /// An IANA name such as `Europe/Paris`.
+    time_zone: String,
+}
+
+pub async fn put_working_hours(
+    session: Session,
+    State(app_state): State<AppState>,
+    Form(body): Form<PutWorkingHoursBody>,
+) -> impl IntoResponse {
+    let working_hours = if body.start.is_empty() && body.end.is_empty() {
+        None
+    } else {
+        let working_hours = match (
+            NaiveTime::parse_from_str(&body.start, "%H:%M"),
+            NaiveTime::parse_from_str(&body.end, "%H:%M"),
+            body.time_zone.trim().parse(),
+        ) {
+            (Ok(start), Ok(end), Ok(time_zone)) => WorkingHours::new(start, end, time_zone),
+            _ => None,
+        };
+        match working_hours {
+            Some(working_hours) => Some(working_hours),
+            None => {
+                return (
+                    StatusCode::OK,
+                    Html("<span>The hours must start before they end, in a time zone such as Europe/Paris.</span>"),
+                )
+                    .into_response();
+            }
+        }
+    };
+
+    update_workspace_settings(
+        &session,
+        &app_state,
+        WorkspaceSettingsUpdate::WorkingHours(working_hours),
+        "Choose a workspace before setting its working hours.",
+    )
+    .await
+}
+
+/// Sets the workspace's calendar from a form with a `working_days` entry per
+/// checked day and `holidays` as one date per line. With no working days the
+/// calendar is cleared.
+pub async fn put_calendar(
+    session: Session,
+    State(app_state): State<AppState>,
+    Form(body): Form<Vec<(String, String)>>,
+) -> impl IntoResponse {
+    let mut working_days = vec![];
+    let mut holidays = vec![];
+    for (key, value) in body {
+        match key.as_str() {
+            "working_days" => match value.parse::<Weekday>() {
+                Ok(day) if !working_days.contains(&day) => working_days.push(day),
+                Ok(_) => {}
+                Err(_) => {
+                    return (StatusCode::BAD_REQUEST, "Unknown working day.").into_response();
+                }
+            },
+            "holidays" => {
+                for line in value.lines().map(str::trim).filter(|l| !l.is_empty()) {
+                    match NaiveDate::parse_from_str(line, "%Y-%m-%d") {
+                        Ok(date) => holidays.push(date),
+                        Err(_) => {
+                            return html_response(InlineMessage {
+                                message: &format!("{line} isn't a YYYY-MM-DD date."),
+                            });
+                        }
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+    holidays.sort();
+    holidays.dedup();
+
+    let calendar = if working_days.is_empty() {
+        None
+    } else {
+        Some(WorkCalendar {
+            working_days,
+            holidays,
+        })
+    };
+
+    update_workspace_settings(
+        &session,
+        &app_state,
+        WorkspaceSettingsUpdate::Calendar(calendar),
+        "Choose a workspace before setting its calendar.",
+    )
+    .await
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        routes::testing::{TestApp, WORKSPACE_ID},
+        services::{clickup::FakeClickUpApi, workspace_settings::StatusCategory},
+    };
+    use chrono::Weekday;
+
+    #[tokio::test]
+    async fn each_setting_is_saved_on_its_own() {
+        let mut app = TestApp::new(FakeClickUpApi::new());
+        app.sign_in().await;
+
+        for body in [
+            "status=In Review&category=waiting",
+            "status=doing&category=active",
+            "status=doing&category=",
+        ] {
+            let (_, _, body) = app
+                .send_form("PUT", "/workspace/status-categories", body)
+                .await;
+            assert_eq!(body, "<span>Saved.</span>");
+        }
+        let (_, _, body) = app
+            .send_form(
+                "PUT",
+                "/workspace/calendar",
+                "working_days=Sun&working_days=Mon&holidays=2025-12-25",
+            )
+            .await;
+        assert_eq!(body, "<span>Saved.</span>");
+
+        let settings = app
+            .app_state
+            .workspace_settings_service
+            .get(WORKSPACE_ID)
+            .await
+            .unwrap();
+        assert_eq!(settings.status_categories.len(), 1);
+        assert_eq!(
+            settings.status_category("in review"),
+            Some(StatusCategory::Waiting)
+        );
+        let calendar = settings.calendar.unwrap();
+        assert_eq!(calendar.working_days, [Weekday::Sun, Weekday::Mon]);
+        assert_eq!(calendar.holidays.len(), 1);
+    }
+
+    #[tokio::test]
+    async fn working_hours_take_an_iana_time_zone() {
+        let mut app = TestApp::new(FakeClickUpApi::new());
+        app.sign_in().await;
+
+        let (_, _, body) = app
+            .send_form(
+                "PUT",
+                "/workspace/working-hours",
+                "start=09:00&end=17:00&time_zone=%2B01:00",
+            )
+            .await;
+        assert!(body.contains("in a time zone such as Europe/Paris"));
+
+        let (_, _, body) = app
+            .send_form(
+                "PUT",
+                "/workspace/working-hours",
+                "start=09:00&end=17:00&time_zone=Europe/Paris",
+            )
+            .await;
+        assert_eq!(body, "<span>Saved.</span>");
+
+        let settings = app
+            .app_state
+            .workspace_settings_service
+            .get(WORKSPACE_ID)
+            .await
+            .unwrap();
+        assert_eq!(
+            settings.working_hours.unwrap().time_zone,
+            chrono_tz::Europe::Paris
+        );
+    }
+}
@@ -0,0 +1,70 @@
+use crate::services::clickup::{
+    ClickUpCustomField, ClickUpGetWorkspacesResponseBody, ClickUpList,
+    ClickUpOauthTokenResponseBody, ClickUpServiceError, ClickUpTaskResponseBody,
+    GetListTasksRequest, GetTaskRequest, GetWorkspaceTasksRequest,
+};
+use async_trait::async_trait;
+
+/// Everything the app needs from ClickUp. [`ClickUpService`] talks to the real
+/// API, [`FakeClickUpApi`] answers from memory and [`CachingClickUpApi`] keeps
+/// the responses of another implementation for a while.
+///
+/// [`ClickUpService`]: crate::services::clickup::ClickUpService
+/// [`FakeClickUpApi`]: crate::services::clickup::FakeClickUpApi
+/// [`CachingClickUpApi`]: crate::services::clickup::CachingClickUpApi
+#[async_trait]
+pub trait ClickUpApi: Send + Sync {
+    /// Fetches the task and its sub tasks, recursively, with each task's time
+    /// in status attached.
+    async fn get_task(
+        &self,
+        token: &str,
+        request_config: GetTaskRequest,
+    ) -> Result<ClickUpTaskResponseBody, ClickUpServiceError>;
+
+    /// Fetches every task in a list, following pagination, with each task's
+    /// time in status attached.
+    async fn get_list_tasks(
+        &self,
+        token: &str,
+        request_config: GetListTasksRequest,
+    ) -> Result<Vec<ClickUpTaskResponseBody>, ClickUpServiceError>;
+
+    /// Fetches every task in the workspace's matching spaces and folders,
+    /// following pagination, with each task's time in status attached.
+    async fn get_workspace_tasks(
+        &self,
+        token: &str,
+        request_config: GetWorkspaceTasksRequest,
+    ) -> Result<Vec<ClickUpTaskResponseBody>, ClickUpServiceError>;
+
+    fn generate_oauth_login_redirect_url(&self) -> Result<url::Url, ClickUpServiceError>;
+
+    async fn post_oauth_token(
+        &self,
+        code: String,
+    ) -> Result<ClickUpOauthTokenResponseBody, ClickUpServiceError>;
+
+    async fn get_authorized_workspaces(
+        &self,
+        token: String,
+    ) -> Result<ClickUpGetWorkspacesResponseBody, ClickUpServiceError>;
+
+    /// Returns the custom fields that can be set on tasks in the list.
+    async fn get_list_custom_fields(
+        &self,
+        token: &str,
+        list_id: &str,
+    ) -> Result<Vec<ClickUpCustomField>, ClickUpServiceError>;
+
+    /// Returns the lists in a folder, e.g. the sprints of a Sprints folder.
+    async fn get_folder_lists(
+        &self,
+        token: &str,
+        folder_id: &str,
+    ) -> Result<Vec<ClickUpList>, ClickUpServiceError>;
+
+    /// Checks that the ClickUp API is reachable. Any response that isn't a
+    /// server error counts, since the request is deliberately unauthenticated.
+    async fn ping(&self) -> Result<(), ClickUpServiceError>;
+}
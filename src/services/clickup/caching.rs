@@ -0,0 +1,358 @@
+use crate::services::clickup::{
+    ClickUpApi, ClickUpCustomField, ClickUpGetWorkspacesResponseBody, ClickUpList,
+    ClickUpOauthTokenResponseBody, ClickUpServiceError, ClickUpTaskResponseBody,
+    GetListTasksRequest, GetTaskRequest, GetWorkspaceTasksRequest,
+};
+use async_trait::async_trait;
+use chrono::{DateTime, TimeDelta, Utc};
+use std::{
+    collections::HashMap,
+    sync::{Arc, RwLock},
+};
+
+/// Successful responses, keyed by the token and the request so that users
+/// never see each other's data.
+struct ResponseCache<T> {
+    entries: RwLock<HashMap<String, (DateTime<Utc>, T)>>,
+}
+
+impl<T: Clone> ResponseCache<T> {
+    fn new() -> Self {
+        Self {
+            entries: RwLock::new(HashMap::new()),
+        }
+    }
+
+    fn get(&self, key: &str, retention: TimeDelta) -> Option<T> {
+        let entries = self.entries.read().unwrap();
+        entries
+            .get(key)
+            .filter(|(cached_at, _)| Utc::now() - *cached_at < retention)
+            .map(|(_, value)| value.clone())
+    }
+
+    fn insert(&self, key: String, value: T, retention: TimeDelta) {
+        let mut entries = self.entries.write().unwrap();
+        let now = Utc::now();
+        entries.retain(|_, (cached_at, _)| now - *cached_at < retention);
+        entries.insert(key, (now, value));
+    }
+}
+
+/// Serves repeated calls from memory for `retention`, instead of fetching the
+/// same tasks again for every report. OAuth and pings always go through.
+#[derive(Clone)]
+pub struct CachingClickUpApi<A> {
+    inner: A,
+    retention: TimeDelta,
+    tasks: Arc<ResponseCache<ClickUpTaskResponseBody>>,
+    task_lists: Arc<ResponseCache<Vec<ClickUpTaskResponseBody>>>,
+    workspaces: Arc<ResponseCache<ClickUpGetWorkspacesResponseBody>>,
+    custom_fields: Arc<ResponseCache<Vec<ClickUpCustomField>>>,
+    folder_lists: Arc<ResponseCache<Vec<ClickUpList>>>,
+}
+
+impl<A: ClickUpApi> CachingClickUpApi<A> {
+    pub fn new(inner: A, retention: TimeDelta) -> Self {
+        Self {
+            inner,
+            retention,
+            tasks: Arc::new(ResponseCache::new()),
+            task_lists: Arc::new(ResponseCache::new()),
+            workspaces: Arc::new(ResponseCache::new()),
+            custom_fields: Arc::new(ResponseCache::new()),
+            folder_lists: Arc::new(ResponseCache::new()),
+        }
+    }
+}
+
+#[async_trait]
+impl<A: ClickUpApi> ClickUpApi for CachingClickUpApi<A> {
+    async fn get_task(
+        &self,
+        token: &str,
+        request_config: GetTaskRequest,
+    ) -> Result<ClickUpTaskResponseBody, ClickUpServiceError> {
+        let key = format!(
+            "{token}/{}/{}/{}/{}",
+            request_config.task_id,
+            request_config.workspace_id.as_deref().unwrap_or_default(),
+            request_config.limits.max_depth,
+            request_config.limits.max_tasks
+        );
+        if let Some(task) = self.tasks.get(&key, self.retention) {
+            if let Some(progress) = &request_config.progress {
+                progress.tasks_discovered(1);
+                progress.task_fetched();
+            }
+            return Ok(task);
+        }
+
+        let task = self.inner.get_task(token, request_config).await?;
+        // A sub task that failed to load may load on the next try, so the
+        // tree is only kept when it is complete.
+        if !has_failed_sub_task(&task) {
+            self.tasks.insert(key, task.clone(), self.retention);
+        }
+
+        Ok(task)
+    }
+
+    async fn get_list_tasks(
+        &self,
+        token: &str,
+        request_config: GetListTasksRequest,
+    ) -> Result<Vec<ClickUpTaskResponseBody>, ClickUpServiceError> {
+        let key = format!(
+            "{token}/list/{}/{}",
+            request_config.list_id, request_config.include_closed
+        );
+        if let Some(tasks) = self.task_lists.get(&key, self.retention) {
+            return Ok(tasks);
+        }
+
+        let tasks = self.inner.get_list_tasks(token, request_config).await?;
+        self.task_lists.insert(key, tasks.clone(), self.retention);
+
+        Ok(tasks)
+    }
+
+    async fn get_workspace_tasks(
+        &self,
+        token: &str,
+        request_config: GetWorkspaceTasksRequest,
+    ) -> Result<Vec<ClickUpTaskResponseBody>, ClickUpServiceError> {
+        let key = format!(
+            "{token}/workspace/{}/{}/{}/{}",
+            request_config.workspace_id,
+            request_config.space_ids.join(","),
+            request_config.folder_ids.join(","),
+            request_config.include_closed
+        );
+        if let Some(tasks) = self.task_lists.get(&key, self.retention) {
+            return Ok(tasks);
+        }
+
+        let tasks = self
+            .inner
+            .get_workspace_tasks(token, request_config)
+            .await?;
+        self.task_lists.insert(key, tasks.clone(), self.retention);
+
+        Ok(tasks)
+    }
+
+    fn generate_oauth_login_redirect_url(&self) -> Result<url::Url, ClickUpServiceError> {
+        self.inner.generate_oauth_login_redirect_url()
+    }
+
+    async fn post_oauth_token(
+        &self,
+        code: String,
+    ) -> Result<ClickUpOauthTokenResponseBody, ClickUpServiceError> {
+        self.inner.post_oauth_token(code).await
+    }
+
+    async fn get_authorized_workspaces(
+        &self,
+        token: String,
+    ) -> Result<ClickUpGetWorkspacesResponseBody, ClickUpServiceError> {
+        if let Some(workspaces) = self.workspaces.get(&token, self.retention) {
+            return Ok(workspaces);
+        }
+
+        let workspaces = self.inner.get_authorized_workspaces(token.clone()).await?;
+        self.workspaces
+            .insert(token, workspaces.clone(), self.retention);
+
+        Ok(workspaces)
+    }
+
+    async fn get_list_custom_fields(
+        &self,
+        token: &str,
+        list_id: &str,
+    ) -> Result<Vec<ClickUpCustomField>, ClickUpServiceError> {
+        let key = format!("{token}/{list_id}");
+        if let Some(custom_fields) = self.custom_fields.get(&key, self.retention) {
+            return Ok(custom_fields);
+        }
+
+        let custom_fields = self.inner.get_list_custom_fields(token, list_id).await?;
+        self.custom_fields
+            .insert(key, custom_fields.clone(), self.retention);
+
+        Ok(custom_fields)
+    }
+
+    async fn get_folder_lists(
+        &self,
+        token: &str,
+        folder_id: &str,
+    ) -> Result<Vec<ClickUpList>, ClickUpServiceError> {
+        let key = format!("{token}/{folder_id}");
+        if let Some(lists) = self.folder_lists.get(&key, self.retention) {
+            return Ok(lists);
+        }
+
+        let lists = self.inner.get_folder_lists(token, folder_id).await?;
+        self.folder_lists.insert(key, lists.clone(), self.retention);
+
+        Ok(lists)
+    }
+
+    async fn ping(&self) -> Result<(), ClickUpServiceError> {
+        self.inner.ping().await
+    }
+}
+
+fn has_failed_sub_task(task: &ClickUpTaskResponseBody) -> bool {
+    task.sub_tasks.iter().flatten().any(|sub_task| {
+        sub_task
+            .load_error
+            .as_ref()
+            .is_some_and(|e| e.is_fetch_failure())
+            || sub_task.task.as_ref().is_some_and(has_failed_sub_task)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::services::clickup::{FakeClickUpApi, SubTaskLoadError, TaskTreeLimits};
+    use serde_json::json;
+
+    fn task(id: &str, sub_task_ids: &[&str]) -> ClickUpTaskResponseBody {
+        let sub_tasks: Vec<_> = sub_task_ids
+            .iter()
+            .map(|sub_task_id| {
+                json!({
+                    "id": sub_task_id,
+                    "custom_id": null,
+                    "name": "Sub task",
+                    "points": null,
+                    "date_created": "1740960000000"
+                })
+            })
+            .collect();
+
+        serde_json::from_value(json!({
+            "id": id,
+            "custom_id": null,
+            "name": "Task",
+            "url": format!("https://app.clickup.com/t/{id}"),
+            "text_content": "",
+            "description": "",
+            "points": null,
+            "time_estimate": null,
+            "time_spent": null,
+            "date_created": "1740960000000",
+            "subtasks": (!sub_tasks.is_empty()).then_some(sub_tasks),
+            "priority": null,
+            "status": null,
+            "time_in_status": null
+        }))
+        .unwrap()
+    }
+
+    fn fake_click_up_api() -> FakeClickUpApi {
+        FakeClickUpApi::new().with_list("list", vec![task("abc", &[])])
+    }
+
+    fn get_task_request() -> GetTaskRequest {
+        GetTaskRequest {
+            task_id: "abc".to_string(),
+            workspace_id: None,
+            progress: None,
+            limits: TaskTreeLimits::default(),
+        }
+    }
+
+    fn get_list_tasks_request() -> GetListTasksRequest {
+        GetListTasksRequest {
+            list_id: "list".to_string(),
+            include_closed: true,
+        }
+    }
+
+    #[tokio::test]
+    async fn repeated_calls_are_served_from_the_cache() {
+        let fake = fake_click_up_api();
+        let click_up_api = CachingClickUpApi::new(fake.clone(), TimeDelta::hours(1));
+
+        for _ in 0..2 {
+            click_up_api
+                .get_task("token", get_task_request())
+                .await
+                .unwrap();
+            click_up_api
+                .get_list_tasks("token", get_list_tasks_request())
+                .await
+                .unwrap();
+        }
+        assert_eq!(fake.task_requests(), 2);
+
+        // Another token never sees the first token's responses.
+        click_up_api
+            .get_task("other token", get_task_request())
+            .await
+            .unwrap();
+        assert_eq!(fake.task_requests(), 3);
+    }
+
+    #[tokio::test]
+    async fn expired_responses_are_fetched_again() {
+        let fake = fake_click_up_api();
+        let click_up_api = CachingClickUpApi::new(fake.clone(), TimeDelta::zero());
+
+        for _ in 0..2 {
+            click_up_api
+                .get_task("token", get_task_request())
+                .await
+                .unwrap();
+            click_up_api
+                .get_list_tasks("token", get_list_tasks_request())
+                .await
+                .unwrap();
+        }
+        assert_eq!(fake.task_requests(), 4);
+    }
+
+    #[tokio::test]
+    async fn trees_with_a_failed_sub_task_are_not_cached() {
+        // The sub task of the sub task isn't in the fake, so it fails to load
+        // as it would after a 404.
+        let fake = FakeClickUpApi::new()
+            .with_task(task("abc", &["def"]))
+            .with_task(task("def", &["deleted"]));
+        let click_up_api = CachingClickUpApi::new(fake.clone(), TimeDelta::hours(1));
+
+        for _ in 0..2 {
+            let task = click_up_api
+                .get_task("token", get_task_request())
+                .await
+                .unwrap();
+            assert!(has_failed_sub_task(&task));
+        }
+        assert_eq!(fake.task_requests(), 2);
+    }
+
+    #[tokio::test]
+    async fn sub_tasks_left_out_by_the_limits_are_not_failures() {
+        let fake = FakeClickUpApi::new()
+            .with_task(task("abc", &["def"]))
+            .with_task(task("def", &[]));
+        let click_up_api = CachingClickUpApi::new(fake.clone(), TimeDelta::hours(1));
+        let mut task = click_up_api
+            .get_task("token", get_task_request())
+            .await
+            .unwrap();
+        let sub_task = &mut task.sub_tasks.as_mut().unwrap()[0];
+        sub_task.task = None;
+        sub_task.load_error = Some(SubTaskLoadError::NotFetched(
+            "not loaded, deeper than the limit of 0 levels".to_string(),
+        ));
+
+        assert!(!has_failed_sub_task(&task));
+    }
+}
@@ -0,0 +1,229 @@
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::{
+    path::{Path, PathBuf},
+    sync::{Arc, Mutex},
+};
+
+/// Written in place of tokens and other secrets.
+pub const REDACTED: &str = "REDACTED";
+
+/// Response fields that hold a secret, e.g. the OAuth access token.
+const SECRET_FIELDS: [&str; 2] = ["access_token", "client_secret"];
+
+#[derive(Debug)]
+pub enum CassetteError {
+    ReadError(std::io::Error),
+    WriteError(std::io::Error),
+    ParseError(serde_json::Error),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum CassetteMode {
+    Record,
+    Replay,
+}
+
+/// One request to the ClickUp API and the response it got. Request headers
+/// and bodies are never written, so the token can't leak through them.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CassetteInteraction {
+    pub method: String,
+    pub url: String,
+    pub status: u16,
+    pub body: String,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct CassetteFile {
+    interactions: Vec<CassetteInteraction>,
+}
+
+/// ClickUp API traffic saved to a JSON file, so a user's numbers can be
+/// reproduced without their token or network access.
+///
+/// A recording cassette keeps the responses in memory until it is saved. A
+/// replaying cassette answers each request with the last response recorded
+/// for the same method and URL.
+#[derive(Debug, Clone)]
+pub struct Cassette {
+    mode: CassetteMode,
+    path: PathBuf,
+    interactions: Arc<Mutex<Vec<CassetteInteraction>>>,
+}
+
+impl Cassette {
+    /// Starts an empty cassette that will be written to `path`.
+    pub fn record(path: impl Into<PathBuf>) -> Self {
+        Self {
+            mode: CassetteMode::Record,
+            path: path.into(),
+            interactions: Arc::new(Mutex::new(vec![])),
+        }
+    }
+
+    /// Loads a cassette recorded earlier to replay it.
+    pub fn replay(path: impl AsRef<Path>) -> Result<Self, CassetteError> {
+        let path = path.as_ref();
+        let bytes = std::fs::read(path).map_err(CassetteError::ReadError)?;
+        let file: CassetteFile =
+            serde_json::from_slice(&bytes).map_err(CassetteError::ParseError)?;
+
+        Ok(Self {
+            mode: CassetteMode::Replay,
+            path: path.to_path_buf(),
+            interactions: Arc::new(Mutex::new(file.interactions)),
+        })
+    }
+
+    pub fn is_replaying(&self) -> bool {
+        self.mode == CassetteMode::Replay
+    }
+
+    /// The recorded status code and body for the request, if there is one.
+    pub fn response(&self, method: &str, url: &str) -> Option<(u16, String)> {
+        let interactions = self.interactions.lock().unwrap();
+        interactions
+            .iter()
+            .rev()
+            .find(|i| i.method == method && i.url == url)
+            .map(|i| (i.status, i.body.clone()))
+    }
+
+    /// Adds a response to the cassette, with every occurrence of `secrets` and
+    /// every secret field replaced by [`REDACTED`].
+    pub fn insert(&self, method: &str, url: &str, status: u16, body: &str, secrets: &[&str]) {
+        let interaction = CassetteInteraction {
+            method: method.to_string(),
+            url: redact(url, secrets),
+            status,
+            body: redact(&redact_secret_fields(body), secrets),
+        };
+
+        let mut interactions = self.interactions.lock().unwrap();
+        interactions.retain(|i| i.method != interaction.method || i.url != interaction.url);
+        interactions.push(interaction);
+    }
+
+    /// Writes every response recorded so far to the cassette's file, off the
+    /// runtime's worker threads.
+    pub async fn save(&self) -> Result<(), CassetteError> {
+        let file = CassetteFile {
+            interactions: self.interactions.lock().unwrap().clone(),
+        };
+        let json = serde_json::to_string_pretty(&file).map_err(CassetteError::ParseError)?;
+        let path = self.path.clone();
+
+        tokio::task::spawn_blocking(move || std::fs::write(path, json))
+            .await
+            .map_err(|e| CassetteError::WriteError(e.into()))?
+            .map_err(CassetteError::WriteError)
+    }
+}
+
+fn redact(text: &str, secrets: &[&str]) -> String {
+    secrets
+        .iter()
+        .filter(|secret| !secret.is_empty())
+        .fold(text.to_string(), |text, secret| {
+            text.replace(secret, REDACTED)
+        })
+}
+
+/// Bodies that aren't JSON are kept as they are.
+fn redact_secret_fields(body: &str) -> String {
+    fn redact_value(value: &mut Value) {
+        match value {
+            Value::Object(object) => {
+                for (key, value) in object.iter_mut() {
+                    if SECRET_FIELDS.contains(&key.as_str()) && value.is_string() {
+                        *value = Value::String(REDACTED.to_string());
+                    } else {
+                        redact_value(value);
+                    }
+                }
+            }
+            Value::Array(values) => values.iter_mut().for_each(redact_value),
+            _ => {}
+        }
+    }
+
+    match serde_json::from_str::<Value>(body) {
+        Ok(mut value) => {
+            redact_value(&mut value);
+            value.to_string()
+        }
+        Err(_) => body.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        domain::Task,
+        services::clickup::{ClickUpApi, ClickUpService, GetTaskRequest, TaskTreeLimits},
+    };
+
+    const TASK_CASSETTE: &str = concat!(
+        env!("CARGO_MANIFEST_DIR"),
+        "/tests/fixtures/task_cassette.json"
+    );
+
+    fn get_task_request(task_id: &str) -> GetTaskRequest {
+        GetTaskRequest {
+            task_id: task_id.to_string(),
+            workspace_id: None,
+            progress: None,
+            limits: TaskTreeLimits::default(),
+        }
+    }
+
+    #[tokio::test]
+    async fn replays_a_recorded_task_tree() {
+        let cassette = Cassette::replay(TASK_CASSETTE).unwrap();
+        let click_up_service = ClickUpService::new("", "", "").with_cassette(cassette);
+
+        let task = click_up_service
+            .get_task("", get_task_request("abc"))
+            .await
+            .unwrap();
+        let task = Task::from(task);
+
+        assert_eq!(task.name, "Checkout redesign");
+        assert_eq!(task.sub_tasks.len(), 1);
+        assert_eq!(task.sub_tasks[0].name, "Payment form");
+        assert_eq!(task.total_points, 5.0);
+        assert_eq!(task.total_time_tracked_hours, 3.0);
+        assert_eq!(task.total_time_in_dev_status, 3);
+        assert!(task.is_completed);
+        assert!(!task.is_incomplete);
+    }
+
+    #[tokio::test]
+    async fn recorded_responses_are_redacted_and_saved() {
+        let path =
+            std::env::temp_dir().join(format!("cassette-{:032x}.json", rand::random::<u128>()));
+        let cassette = Cassette::record(&path);
+
+        cassette.insert(
+            "POST",
+            "https://api.clickup.com/api/v2/oauth/token?client_secret=s3cr3t",
+            200,
+            r#"{"access_token":"pk_123"}"#,
+            &["s3cr3t"],
+        );
+        assert!(!path.exists());
+        cassette.save().await.unwrap();
+
+        let replayed = Cassette::replay(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+        assert_eq!(
+            replayed.response(
+                "POST",
+                "https://api.clickup.com/api/v2/oauth/token?client_secret=REDACTED"
+            ),
+            Some((200, r#"{"access_token":"REDACTED"}"#.to_string()))
+        );
+    }
+}
@@ -0,0 +1,240 @@
+use crate::services::clickup::{
+    ClickUpApi, ClickUpCustomField, ClickUpGetWorkspacesResponseBody, ClickUpList,
+    ClickUpOauthTokenResponseBody, ClickUpServiceError, ClickUpTaskResponseBody, ClickUpWorkspace,
+    GetListTasksRequest, GetTaskRequest, GetWorkspaceTasksRequest,
+};
+use async_trait::async_trait;
+use std::{
+    collections::HashMap,
+    sync::{
+        Arc,
+        atomic::{AtomicUsize, Ordering},
+    },
+};
+
+/// Answers every call from tasks, lists and workspaces kept in memory, so the
+/// routes can run without ClickUp.
+///
+/// Tasks are returned as they were added, so they should carry their
+/// `time_in_status`. The fake has no spaces or folders, so the workspace's
+/// tasks are the tasks of every list.
+#[derive(Clone, Default)]
+pub struct FakeClickUpApi {
+    tasks: HashMap<String, ClickUpTaskResponseBody>,
+    /// Task ids by list id, in the order they were added.
+    lists: HashMap<String, Vec<String>>,
+    list_custom_fields: HashMap<String, Vec<ClickUpCustomField>>,
+    folder_lists: HashMap<String, Vec<ClickUpList>>,
+    workspaces: Vec<ClickUpWorkspace>,
+    access_token: String,
+    /// Shared by clones, so it can be read after the fake is wrapped.
+    task_requests: Arc<AtomicUsize>,
+}
+
+impl FakeClickUpApi {
+    pub fn new() -> Self {
+        Self {
+            access_token: "fake_access_token".to_string(),
+            ..Self::default()
+        }
+    }
+
+    /// Adds a task that can be fetched by its id, or by its custom id when a
+    /// workspace is given. Its sub tasks are filled in from the other tasks,
+    /// and fail to load as if deleted when they aren't among them.
+    pub fn with_task(mut self, task: ClickUpTaskResponseBody) -> Self {
+        self.tasks.insert(task.id.clone(), task);
+        self
+    }
+
+    /// Adds the tasks and puts them in the list.
+    pub fn with_list(mut self, list_id: &str, tasks: Vec<ClickUpTaskResponseBody>) -> Self {
+        let task_ids = self.lists.entry(list_id.to_string()).or_default();
+        for task in tasks {
+            task_ids.push(task.id.clone());
+            self.tasks.insert(task.id.clone(), task);
+        }
+        self
+    }
+
+    pub fn with_list_custom_fields(
+        mut self,
+        list_id: &str,
+        custom_fields: Vec<ClickUpCustomField>,
+    ) -> Self {
+        self.list_custom_fields
+            .insert(list_id.to_string(), custom_fields);
+        self
+    }
+
+    pub fn with_folder_lists(mut self, folder_id: &str, lists: Vec<ClickUpList>) -> Self {
+        self.folder_lists.insert(folder_id.to_string(), lists);
+        self
+    }
+
+    pub fn with_workspace(mut self, workspace: ClickUpWorkspace) -> Self {
+        self.workspaces.push(workspace);
+        self
+    }
+
+    /// The token every OAuth code is exchanged for.
+    pub fn with_access_token(mut self, access_token: &str) -> Self {
+        self.access_token = access_token.to_string();
+        self
+    }
+
+    /// How many times tasks were fetched, by this fake or any of its clones.
+    pub fn task_requests(&self) -> usize {
+        self.task_requests.load(Ordering::Relaxed)
+    }
+
+    fn list_tasks(&self, list_id: &str, include_closed: bool) -> Vec<ClickUpTaskResponseBody> {
+        self.lists
+            .get(list_id)
+            .into_iter()
+            .flatten()
+            .filter_map(|task_id| self.tasks.get(task_id))
+            .filter(|t| include_closed || !is_closed(t))
+            .cloned()
+            .collect()
+    }
+
+    fn task_tree(
+        &self,
+        task: &ClickUpTaskResponseBody,
+        ancestors: &mut Vec<String>,
+    ) -> ClickUpTaskResponseBody {
+        let mut task = task.clone();
+        ancestors.push(task.id.clone());
+        if let Some(sub_tasks) = &mut task.sub_tasks {
+            for sub_task in sub_tasks {
+                if ancestors.contains(&sub_task.id) {
+                    continue;
+                }
+                match self.tasks.get(&sub_task.id) {
+                    Some(saved) => sub_task.task = Some(self.task_tree(saved, ancestors)),
+                    None => {
+                        sub_task.load_error = Some(
+                            ClickUpServiceError::UnexpectedStatus(404, String::new()).load_error(),
+                        )
+                    }
+                }
+            }
+        }
+        ancestors.pop();
+
+        task
+    }
+}
+
+fn is_closed(task: &ClickUpTaskResponseBody) -> bool {
+    task.status
+        .as_ref()
+        .is_some_and(|s| s.status_type == "closed")
+}
+
+#[async_trait]
+impl ClickUpApi for FakeClickUpApi {
+    async fn get_task(
+        &self,
+        _token: &str,
+        request_config: GetTaskRequest,
+    ) -> Result<ClickUpTaskResponseBody, ClickUpServiceError> {
+        self.task_requests.fetch_add(1, Ordering::Relaxed);
+        let task = match &request_config.workspace_id {
+            Some(_) => self
+                .tasks
+                .values()
+                .find(|t| t.custom_id.as_deref() == Some(request_config.task_id.as_str())),
+            None => self.tasks.get(&request_config.task_id),
+        };
+        let task = match task {
+            Some(task) => self.task_tree(task, &mut vec![]),
+            None => return Err(ClickUpServiceError::UnexpectedError),
+        };
+
+        if let Some(progress) = &request_config.progress {
+            progress.tasks_discovered(1);
+            progress.task_fetched();
+        }
+
+        Ok(task)
+    }
+
+    async fn get_list_tasks(
+        &self,
+        _token: &str,
+        request_config: GetListTasksRequest,
+    ) -> Result<Vec<ClickUpTaskResponseBody>, ClickUpServiceError> {
+        self.task_requests.fetch_add(1, Ordering::Relaxed);
+        Ok(self.list_tasks(&request_config.list_id, request_config.include_closed))
+    }
+
+    async fn get_workspace_tasks(
+        &self,
+        _token: &str,
+        request_config: GetWorkspaceTasksRequest,
+    ) -> Result<Vec<ClickUpTaskResponseBody>, ClickUpServiceError> {
+        self.task_requests.fetch_add(1, Ordering::Relaxed);
+        let mut list_ids: Vec<&String> = self.lists.keys().collect();
+        list_ids.sort();
+
+        Ok(list_ids
+            .into_iter()
+            .flat_map(|list_id| self.list_tasks(list_id, request_config.include_closed))
+            .collect())
+    }
+
+    fn generate_oauth_login_redirect_url(&self) -> Result<url::Url, ClickUpServiceError> {
+        match url::Url::parse("https://app.clickup.com/api") {
+            Ok(url) => Ok(url),
+            Err(_) => Err(ClickUpServiceError::UnexpectedError),
+        }
+    }
+
+    async fn post_oauth_token(
+        &self,
+        _code: String,
+    ) -> Result<ClickUpOauthTokenResponseBody, ClickUpServiceError> {
+        Ok(ClickUpOauthTokenResponseBody {
+            access_token: self.access_token.clone(),
+        })
+    }
+
+    async fn get_authorized_workspaces(
+        &self,
+        _token: String,
+    ) -> Result<ClickUpGetWorkspacesResponseBody, ClickUpServiceError> {
+        Ok(ClickUpGetWorkspacesResponseBody {
+            teams: self.workspaces.clone(),
+        })
+    }
+
+    async fn get_list_custom_fields(
+        &self,
+        _token: &str,
+        list_id: &str,
+    ) -> Result<Vec<ClickUpCustomField>, ClickUpServiceError> {
+        Ok(self
+            .list_custom_fields
+            .get(list_id)
+            .cloned()
+            .unwrap_or_default())
+    }
+
+    async fn get_folder_lists(
+        &self,
+        _token: &str,
+        folder_id: &str,
+    ) -> Result<Vec<ClickUpList>, ClickUpServiceError> {
+        Ok(self
+            .folder_lists
+            .get(folder_id)
+            .cloned()
+            .unwrap_or_default())
+    }
+
+    async fn ping(&self) -> Result<(), ClickUpServiceError> {
+        Ok(())
+    }
+}
@@ -1,5 +1,13 @@
+mod api;
+mod caching;
+mod cassette;
+mod fake;
 mod models;
 mod service;
 
+pub use api::*;
+pub use caching::*;
+pub use cassette::*;
+pub use fake::*;
 pub use models::*;
 pub use service::*;
@@ -1,5 +1,6 @@
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Deserializer};
+use std::{collections::HashMap, fmt};
 
 #[derive(Debug, Deserialize, Clone)]
 pub struct ClickUpTimeInStatusResponseBody {
@@ -16,7 +17,7 @@ pub struct CurrentStatus {
 #[derive(Debug, Deserialize, Clone)]
 pub struct TotalTime {
     pub by_minute: i64,
-    #[serde(deserialize_with = "ts_milliseconds_string")]
+    #[serde(deserialize_with = "ts_milliseconds")]
     pub since: DateTime<Utc>,
 }
 
@@ -30,14 +31,75 @@ pub struct StatusHistory {
     pub order_index: Option<i32>,
 }
 
-fn ts_milliseconds_string<'de, D>(deserializer: D) -> Result<DateTime<Utc>, D::Error>
+/// Why a millisecond timestamp couldn't be read.
+#[derive(Debug, PartialEq)]
+pub enum TimestampError {
+    Missing,
+    /// Neither a whole number nor a string holding one.
+    Malformed(String),
+    OutOfRange(i64),
+}
+
+impl fmt::Display for TimestampError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TimestampError::Missing => f.write_str("missing timestamp"),
+            TimestampError::Malformed(value) => write!(f, "invalid timestamp: {value}"),
+            TimestampError::OutOfRange(millis) => write!(f, "timestamp out of range: {millis}"),
+        }
+    }
+}
+
+/// ClickUp sends most timestamps as strings, but not all of them.
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum MillisecondTimestamp {
+    Number(i64),
+    String(String),
+    Other(serde_json::Value),
+}
+
+impl MillisecondTimestamp {
+    /// `None` for an empty string.
+    fn parse(self) -> Result<Option<DateTime<Utc>>, TimestampError> {
+        let millis = match self {
+            MillisecondTimestamp::Number(millis) => millis,
+            MillisecondTimestamp::String(value) if value.trim().is_empty() => return Ok(None),
+            MillisecondTimestamp::String(value) => value
+                .trim()
+                .parse::<i64>()
+                .map_err(|_| TimestampError::Malformed(format!("{value:?}")))?,
+            MillisecondTimestamp::Other(value) => {
+                return Err(TimestampError::Malformed(value.to_string()));
+            }
+        };
+
+        DateTime::from_timestamp_millis(millis)
+            .map(Some)
+            .ok_or(TimestampError::OutOfRange(millis))
+    }
+}
+
+fn ts_milliseconds<'de, D>(deserializer: D) -> Result<DateTime<Utc>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    MillisecondTimestamp::deserialize(deserializer)?
+        .parse()
+        .and_then(|date| date.ok_or(TimestampError::Missing))
+        .map_err(serde::de::Error::custom)
+}
+
+/// Like [`ts_milliseconds`], for timestamps ClickUp sends as `null` when
+/// unset.
+fn ts_milliseconds_option<'de, D>(deserializer: D) -> Result<Option<DateTime<Utc>>, D::Error>
 where
     D: Deserializer<'de>,
 {
-    let val = String::deserialize(deserializer)?;
-    let val = val.parse::<i64>().unwrap(); // TODO: Fix this unwrap.
-    let date = DateTime::from_timestamp_millis(val).unwrap().to_utc();
-    Ok(date)
+    match Option::<MillisecondTimestamp>::deserialize(deserializer)? {
+        Some(timestamp) => timestamp.parse().map_err(serde::de::Error::custom),
+        None => Ok(None),
+    }
 }
 
 #[derive(Debug, Deserialize, Clone)]
@@ -45,23 +107,367 @@ pub struct ClickUpTaskResponseBody {
     pub id: String,
     pub custom_id: Option<String>,
     pub name: String,
+    pub url: String,
     pub text_content: String,
     pub description: String,
     pub points: Option<f32>,
-    #[serde(deserialize_with = "ts_milliseconds_string")]
+    /// In milliseconds.
+    pub time_estimate: Option<i64>,
+    /// Total of the task's tracked time entries, in milliseconds.
+    pub time_spent: Option<i64>,
+    #[serde(deserialize_with = "ts_milliseconds")]
     pub date_created: DateTime<Utc>,
+    #[serde(default, deserialize_with = "ts_milliseconds_option")]
+    pub date_done: Option<DateTime<Utc>>,
+    #[serde(default, deserialize_with = "ts_milliseconds_option")]
+    pub date_closed: Option<DateTime<Utc>>,
     #[serde(rename = "subtasks")]
     pub sub_tasks: Option<Vec<SubTask>>,
+    #[serde(default)]
+    pub assignees: Vec<ClickUpUser>,
+    #[serde(default)]
+    pub watchers: Vec<ClickUpUser>,
+    pub creator: Option<ClickUpUser>,
+    #[serde(default)]
+    pub custom_fields: Vec<ClickUpCustomField>,
+    #[serde(default)]
+    pub tags: Vec<ClickUpTag>,
+    pub priority: Option<ClickUpPriority>,
+    pub status: Option<ClickUpStatus>,
     pub time_in_status: Option<ClickUpTimeInStatusResponseBody>, // Not actually part of request.
+    /// The task's home list.
+    pub list: Option<ClickUpTaskLocation>,
+    /// Lists the task was also added to, e.g. sprints.
+    #[serde(default)]
+    pub locations: Vec<ClickUpTaskLocation>,
+    /// Changes made to the task. The REST API never returns these, but exports
+    /// built from ClickUp's webhook events can include them.
+    #[serde(default)]
+    pub history_items: Vec<ClickUpHistoryItem>,
+}
+
+impl ClickUpTaskResponseBody {
+    /// Whether the task's home list or one of the lists it was added to is
+    /// `list_id`.
+    pub fn is_in_list(&self, list_id: &str) -> bool {
+        self.list
+            .iter()
+            .chain(&self.locations)
+            .any(|l| l.id == list_id)
+    }
+}
+
+#[derive(Debug, Deserialize, Clone)]
+pub struct ClickUpTaskLocation {
+    pub id: String,
+}
+
+/// A change to one of a task's fields, as sent in ClickUp's webhook events.
+#[derive(Debug, Deserialize, Clone)]
+pub struct ClickUpHistoryItem {
+    pub field: String,
+    #[serde(deserialize_with = "ts_milliseconds")]
+    pub date: DateTime<Utc>,
+    /// Shaped by `field`, e.g. a status for status changes.
+    #[serde(default)]
+    pub before: Option<serde_json::Value>,
+    #[serde(default)]
+    pub after: Option<serde_json::Value>,
+}
+
+/// A status as it appears in a status change's history item.
+#[derive(Debug, Deserialize, Clone)]
+pub struct ClickUpHistoryStatus {
+    pub status: String,
+    #[serde(rename = "type")]
+    pub status_type: String,
+    #[serde(rename = "orderindex")]
+    pub order_index: Option<i32>,
+}
+
+impl ClickUpHistoryItem {
+    /// The statuses before and after the change, if it changed the status.
+    pub fn status_change(&self) -> Option<(ClickUpHistoryStatus, ClickUpHistoryStatus)> {
+        if self.field != "status" {
+            return None;
+        }
+
+        let before = serde_json::from_value(self.before.clone()?).ok()?;
+        let after = serde_json::from_value(self.after.clone()?).ok()?;
+
+        Some((before, after))
+    }
+}
+
+#[derive(Debug, Deserialize, Clone)]
+pub struct ClickUpUser {
+    pub id: i64,
+    pub username: Option<String>,
+    pub email: Option<String>,
+    pub initials: Option<String>,
+    pub color: Option<String>,
+    #[serde(rename = "profilePicture")]
+    pub profile_picture: Option<String>,
+}
+
+impl ClickUpUser {
+    /// The best available name to show for the user.
+    pub fn display_name(&self) -> String {
+        self.username
+            .clone()
+            .or_else(|| self.email.clone())
+            .unwrap_or_else(|| self.id.to_string())
+    }
+}
+
+#[derive(Debug, Deserialize, Clone)]
+pub struct ClickUpTag {
+    pub name: String,
+}
+
+#[derive(Debug, Deserialize, Clone)]
+pub struct ClickUpPriority {
+    /// `urgent`, `high`, `normal` or `low`.
+    pub priority: String,
+}
+
+#[derive(Debug, Deserialize, Clone)]
+pub struct ClickUpStatus {
+    pub status: String,
+    #[serde(rename = "type")]
+    pub status_type: String,
+}
+
+#[derive(Debug, Deserialize, Clone)]
+pub struct ClickUpCustomField {
+    pub id: String,
+    pub name: String,
+    #[serde(rename = "type")]
+    pub field_type: String,
+    pub type_config: Option<ClickUpCustomFieldTypeConfig>,
+    /// Missing when the field isn't set on the task, or when the field was
+    /// fetched from a list rather than a task.
+    pub value: Option<serde_json::Value>,
+}
+
+#[derive(Debug, Deserialize, Clone)]
+pub struct ClickUpCustomFieldTypeConfig {
+    #[serde(default)]
+    pub options: Vec<ClickUpCustomFieldOption>,
+}
+
+#[derive(Debug, Deserialize, Clone)]
+pub struct ClickUpCustomFieldOption {
+    pub id: String,
+    /// Label fields call this `label` rather than `name`.
+    #[serde(alias = "label")]
+    pub name: Option<String>,
+    #[serde(rename = "orderindex")]
+    pub order_index: Option<i64>,
+}
+
+impl ClickUpCustomField {
+    pub fn options(&self) -> &[ClickUpCustomFieldOption] {
+        match &self.type_config {
+            Some(type_config) => &type_config.options,
+            None => &[],
+        }
+    }
+}
+
+#[derive(Debug, Deserialize, Clone)]
+pub struct ClickUpListCustomFieldsResponseBody {
+    pub fields: Vec<ClickUpCustomField>,
 }
 
 #[derive(Debug, Deserialize, Clone)]
 pub struct SubTask {
     pub id: String,
     pub task: Option<ClickUpTaskResponseBody>, // Not actually part of response
+    /// Why the sub task wasn't fetched.
+    #[serde(default)]
+    pub load_error: Option<SubTaskLoadError>, // Not actually part of response
     pub custom_id: Option<String>,
     pub name: String,
     pub points: Option<f32>,
-    #[serde(deserialize_with = "ts_milliseconds_string")]
+    #[serde(deserialize_with = "ts_milliseconds")]
     pub date_created: DateTime<Utc>,
 }
+
+/// Why a sub task is missing from its tree.
+#[derive(Debug, Deserialize, Clone, PartialEq)]
+pub enum SubTaskLoadError {
+    /// ClickUp didn't return it, e.g. `could not load: 403`. Fetching it again
+    /// may work.
+    FetchFailed(String),
+    /// Left out by the tree's limits, e.g. `not loaded, past the limit of 1000
+    /// tasks`.
+    NotFetched(String),
+    /// Listed under more than one task, and already fetched under another.
+    AlreadyInTree,
+}
+
+impl SubTaskLoadError {
+    pub fn is_fetch_failure(&self) -> bool {
+        matches!(self, SubTaskLoadError::FetchFailed(_))
+    }
+
+    /// Whether the tree's totals are missing the sub task's share. A task
+    /// already in the tree is counted where it was fetched.
+    pub fn leaves_tree_incomplete(&self) -> bool {
+        !matches!(self, SubTaskLoadError::AlreadyInTree)
+    }
+}
+
+impl fmt::Display for SubTaskLoadError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SubTaskLoadError::FetchFailed(reason) | SubTaskLoadError::NotFetched(reason) => {
+                f.write_str(reason)
+            }
+            SubTaskLoadError::AlreadyInTree => f.write_str("not loaded, already in the tree"),
+        }
+    }
+}
+
+#[derive(Debug, Deserialize, Clone)]
+pub struct ClickUpListTasksResponseBody {
+    pub tasks: Vec<ClickUpTaskResponseBody>,
+    #[serde(default)]
+    pub last_page: bool,
+}
+
+pub type ClickUpBulkTimeInStatusResponseBody = HashMap<String, ClickUpTimeInStatusResponseBody>;
+
+/// A list in a folder. Sprint lists carry the sprint's start and end dates.
+#[derive(Debug, Deserialize, Clone)]
+pub struct ClickUpList {
+    pub id: String,
+    pub name: String,
+    #[serde(default, deserialize_with = "ts_milliseconds_option")]
+    pub start_date: Option<DateTime<Utc>>,
+    #[serde(default, deserialize_with = "ts_milliseconds_option")]
+    pub due_date: Option<DateTime<Utc>>,
+}
+
+#[derive(Debug, Deserialize, Clone)]
+pub struct ClickUpFolderListsResponseBody {
+    pub lists: Vec<ClickUpList>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::testing::task_json;
+    use serde_json::json;
+
+    #[derive(Debug, Deserialize)]
+    struct Required {
+        #[serde(deserialize_with = "ts_milliseconds")]
+        date: DateTime<Utc>,
+    }
+
+    #[derive(Debug, Deserialize)]
+    struct Optional {
+        #[serde(default, deserialize_with = "ts_milliseconds_option")]
+        date: Option<DateTime<Utc>>,
+    }
+
+    fn parse(value: serde_json::Value) -> Result<Option<DateTime<Utc>>, TimestampError> {
+        serde_json::from_value::<MillisecondTimestamp>(value)
+            .unwrap()
+            .parse()
+    }
+
+    #[test]
+    fn reads_numbers_and_strings_of_milliseconds() {
+        let date = DateTime::from_timestamp_millis(1_740_960_000_000);
+
+        assert_eq!(parse(json!(1_740_960_000_000_i64)), Ok(date));
+        assert_eq!(parse(json!("1740960000000")), Ok(date));
+        assert_eq!(parse(json!(" 1740960000000 ")), Ok(date));
+        assert_eq!(parse(json!(-1)), Ok(DateTime::from_timestamp_millis(-1)));
+    }
+
+    #[test]
+    fn empty_strings_and_nulls_are_unset() {
+        assert_eq!(parse(json!("")), Ok(None));
+        assert_eq!(parse(json!("  ")), Ok(None));
+
+        for value in [json!({ "date": null }), json!({ "date": "" }), json!({})] {
+            let optional: Optional = serde_json::from_value(value).unwrap();
+            assert_eq!(optional.date, None);
+        }
+    }
+
+    #[test]
+    fn malformed_and_out_of_range_values_are_errors() {
+        assert_eq!(
+            parse(json!("soon")),
+            Err(TimestampError::Malformed(r#""soon""#.to_string()))
+        );
+        assert_eq!(
+            parse(json!(1.5)),
+            Err(TimestampError::Malformed("1.5".to_string()))
+        );
+        assert_eq!(
+            parse(json!(true)),
+            Err(TimestampError::Malformed("true".to_string()))
+        );
+        assert_eq!(
+            parse(json!(i64::MAX)),
+            Err(TimestampError::OutOfRange(i64::MAX))
+        );
+        assert_eq!(
+            parse(json!(i64::MAX.to_string())),
+            Err(TimestampError::OutOfRange(i64::MAX))
+        );
+    }
+
+    #[test]
+    fn required_timestamps_must_be_set() {
+        let required: Required = serde_json::from_value(json!({ "date": "0" })).unwrap();
+        assert_eq!(required.date, DateTime::UNIX_EPOCH);
+
+        for (value, message) in [
+            (json!({ "date": "" }), "missing timestamp"),
+            (json!({ "date": "soon" }), r#"invalid timestamp: "soon""#),
+            (
+                json!({ "date": i64::MAX }),
+                "timestamp out of range: 9223372036854775807",
+            ),
+        ] {
+            let error = serde_json::from_value::<Required>(value).unwrap_err();
+            assert_eq!(error.to_string(), message);
+        }
+        assert!(serde_json::from_value::<Required>(json!({ "date": null })).is_err());
+        assert!(serde_json::from_value::<Required>(json!({})).is_err());
+    }
+
+    #[test]
+    fn completion_dates_are_null_numbers_or_strings() {
+        let date = DateTime::from_timestamp_millis(1_740_960_000_000);
+
+        for field in ["date_done", "date_closed"] {
+            for (value, expected) in [
+                (json!(null), None),
+                (json!(1_740_960_000_000_i64), date),
+                (json!("1740960000000"), date),
+            ] {
+                let mut task = task_json("a", 1.0, &[("done", "done", 0)]);
+                task[field] = value;
+                let task: ClickUpTaskResponseBody = serde_json::from_value(task).unwrap();
+
+                let actual = match field {
+                    "date_done" => task.date_done,
+                    _ => task.date_closed,
+                };
+                assert_eq!(actual, expected, "{field}");
+            }
+        }
+
+        let task: ClickUpTaskResponseBody =
+            serde_json::from_value(task_json("a", 1.0, &[("to do", "open", 0)])).unwrap();
+        assert_eq!((task.date_done, task.date_closed), (None, None));
+    }
+}
@@ -1,23 +1,124 @@
-use crate::services::clickup::{ClickUpTaskResponseBody, ClickUpTimeInStatusResponseBody};
+use crate::services::clickup::{
+    Cassette, ClickUpApi, ClickUpBulkTimeInStatusResponseBody, ClickUpCustomField,
+    ClickUpFolderListsResponseBody, ClickUpList, ClickUpListCustomFieldsResponseBody,
+    ClickUpListTasksResponseBody, ClickUpTaskResponseBody, ClickUpTimeInStatusResponseBody,
+    SubTaskLoadError,
+};
 use async_recursion::async_recursion;
+use async_trait::async_trait;
 use futures::StreamExt;
+use reqwest::StatusCode;
 use serde::{Deserialize, Serialize};
 use serde_json::json;
-use std::error::Error;
-use tokio::{join, task::JoinSet, time::Instant};
+use std::{collections::HashSet, error::Error, sync::Mutex};
+use tokio::{
+    join,
+    sync::{Semaphore, watch},
+};
+use tokio_util::sync::CancellationToken;
 
 pub static IN_PROGRESS_ORDER_INDEX: i32 = 5;
 
 const TIME_IN_STATUS_NOT_ENABLED_ERROR_CODE: &str = "TIS_027";
+const BULK_TIME_IN_STATUS_MAX_TASKS: usize = 100;
 const NOT_AUTHORIZED_ERROR_CODE: &str = "OAUTH_018";
+const PERSONAL_TOKEN_PREFIX: &str = "pk_";
+const DEFAULT_MAX_TASK_TREE_DEPTH: usize = 10;
+const DEFAULT_MAX_TASK_TREE_TASKS: usize = 1000;
+/// Tasks of one tree fetched at once, across every level. Each fetch is two
+/// requests, and ClickUp rate limits tokens to 100 requests a minute.
+const MAX_CONCURRENT_TASK_FETCHES: usize = 4;
 
 #[derive(Clone)]
 pub struct ClickUpService {
     base_url: String,
-    http_client: reqwest::Client,
+    http_client: ClickUpHttpClient,
     client_id: String,
     client_secret: String,
     redirect_uri: String,
+    cancellation_token: CancellationToken,
+}
+
+/// Sends requests to the ClickUp API, through a [`Cassette`] when one is set.
+#[derive(Clone, Default)]
+pub struct ClickUpHttpClient {
+    client: reqwest::Client,
+    cassette: Option<Cassette>,
+}
+
+impl ClickUpHttpClient {
+    fn get(&self, url: String) -> reqwest::RequestBuilder {
+        self.client.get(url)
+    }
+
+    fn post(&self, url: String) -> reqwest::RequestBuilder {
+        self.client.post(url)
+    }
+
+    fn is_replaying(&self) -> bool {
+        self.cassette.as_ref().is_some_and(|c| c.is_replaying())
+    }
+
+    /// Returns the response's status code and body.
+    async fn send(
+        &self,
+        request: reqwest::RequestBuilder,
+    ) -> Result<(StatusCode, String), ClickUpServiceError> {
+        let request = match request.build() {
+            Ok(request) => request,
+            Err(e) => {
+                return Err(ClickUpServiceError::FailedToSendNetworkRequestError(
+                    Box::new(e),
+                ));
+            }
+        };
+        let method = request.method().to_string();
+        let url = request.url().to_string();
+
+        let cassette = match &self.cassette {
+            Some(cassette) if cassette.is_replaying() => {
+                return match cassette.response(&method, &url) {
+                    Some((status, body)) => match StatusCode::from_u16(status) {
+                        Ok(status_code) => Ok((status_code, body)),
+                        Err(_) => Err(ClickUpServiceError::UnexpectedError),
+                    },
+                    None => Err(ClickUpServiceError::NotInCassette(format!(
+                        "{method} {url}"
+                    ))),
+                };
+            }
+            cassette => cassette,
+        };
+
+        let token = request
+            .headers()
+            .get(reqwest::header::AUTHORIZATION)
+            .and_then(|value| value.to_str().ok())
+            .map(|value| value.trim_start_matches("Bearer ").to_string());
+
+        let response = match self.client.execute(request).await {
+            Ok(response) => response,
+            Err(e) => {
+                return Err(ClickUpServiceError::FailedToSendNetworkRequestError(
+                    Box::new(e),
+                ));
+            }
+        };
+
+        let status_code = response.status();
+
+        let text = match response.text().await {
+            Ok(text) => text,
+            Err(e) => return Err(ClickUpServiceError::ParseError(Box::new(e), None)),
+        };
+
+        if let Some(cassette) = cassette {
+            let secrets: Vec<&str> = token.iter().map(String::as_str).collect();
+            cassette.insert(&method, &url, status_code.as_u16(), &text, &secrets);
+        }
+
+        Ok((status_code, text))
+    }
 }
 
 #[derive(Debug)]
@@ -27,40 +128,278 @@ pub enum ClickUpServiceError {
     UnexpectedError,
     TimeInStatusNotEnabled,
     CustomIDError,
+    Cancelled,
+    /// ClickUp answered with an error status, e.g. 403 for a task the token
+    /// can't see.
+    UnexpectedStatus(u16, String),
+    /// Replaying a cassette that has no response for the request.
+    NotInCassette(String),
+}
+
+impl ClickUpServiceError {
+    /// Shown on a sub task that couldn't be loaded, e.g. `could not load: 403`.
+    pub fn load_error(&self) -> SubTaskLoadError {
+        let reason = match self {
+            ClickUpServiceError::UnexpectedStatus(status, _) => status.to_string(),
+            ClickUpServiceError::FailedToSendNetworkRequestError(_) => "network error".to_string(),
+            ClickUpServiceError::TimeInStatusNotEnabled => "time in status not enabled".to_string(),
+            ClickUpServiceError::NotInCassette(_) => "not in cassette".to_string(),
+            _ => "unexpected response".to_string(),
+        };
+
+        SubTaskLoadError::FetchFailed(format!("could not load: {reason}"))
+    }
 }
 
 #[derive(Clone)]
 pub struct GetTaskRequest {
     pub task_id: String,
     pub workspace_id: Option<String>,
+    pub progress: Option<FetchProgressReporter>,
+    pub limits: TaskTreeLimits,
+}
+
+/// How much of a task tree is fetched. Sub tasks past either limit, or seen
+/// earlier in the same tree, are marked as not loaded instead.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct TaskTreeLimits {
+    /// Levels of sub tasks below the task.
+    pub max_depth: usize,
+    /// Tasks fetched in total, the task itself included.
+    pub max_tasks: usize,
+}
+
+impl Default for TaskTreeLimits {
+    fn default() -> Self {
+        Self {
+            max_depth: DEFAULT_MAX_TASK_TREE_DEPTH,
+            max_tasks: DEFAULT_MAX_TASK_TREE_TASKS,
+        }
+    }
+}
+
+/// Shared by every branch of one task tree fetch.
+struct TaskTreeFetch {
+    limits: TaskTreeLimits,
+    /// Ids of the tasks fetched or being fetched.
+    task_ids: Mutex<HashSet<String>>,
+    /// Held while a task is being fetched, but not while its sub tasks are.
+    fetch_permits: Semaphore,
+}
+
+impl TaskTreeFetch {
+    /// Claims the sub task for fetching, or returns why it won't be.
+    fn claim(&self, task_id: &str, depth: usize) -> Result<(), SubTaskLoadError> {
+        if depth > self.limits.max_depth {
+            return Err(SubTaskLoadError::NotFetched(format!(
+                "not loaded, deeper than the limit of {} levels",
+                self.limits.max_depth
+            )));
+        }
+
+        let mut task_ids = self.task_ids.lock().unwrap();
+        if task_ids.contains(task_id) {
+            return Err(SubTaskLoadError::AlreadyInTree);
+        }
+        if task_ids.len() >= self.limits.max_tasks {
+            return Err(SubTaskLoadError::NotFetched(format!(
+                "not loaded, past the limit of {} tasks",
+                self.limits.max_tasks
+            )));
+        }
+        task_ids.insert(task_id.to_string());
+
+        Ok(())
+    }
+}
+
+#[derive(Clone)]
+pub struct GetListTasksRequest {
+    pub list_id: String,
+    pub include_closed: bool,
+}
+
+/// Tasks across a workspace, narrowed down to spaces and folders.
+#[derive(Clone)]
+pub struct GetWorkspaceTasksRequest {
+    pub workspace_id: String,
+    pub space_ids: Vec<String>,
+    pub folder_ids: Vec<String>,
+    pub include_closed: bool,
+}
+
+/// The paginated endpoint tasks are fetched from.
+#[derive(Clone, Copy)]
+enum TaskPages<'a> {
+    List(&'a GetListTasksRequest),
+    Workspace(&'a GetWorkspaceTasksRequest),
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct FetchProgress {
+    pub fetched: usize,
+    pub discovered: usize,
+}
+
+/// Counts tasks as they are discovered and fetched while walking a task tree.
+/// `discovered` grows as subtasks are found, so it is only final once
+/// `fetched` has caught up with it.
+#[derive(Clone)]
+pub struct FetchProgressReporter(watch::Sender<FetchProgress>);
+
+impl FetchProgressReporter {
+    pub fn new() -> (Self, watch::Receiver<FetchProgress>) {
+        let (sender, receiver) = watch::channel(FetchProgress::default());
+        (Self(sender), receiver)
+    }
+
+    pub(crate) fn tasks_discovered(&self, count: usize) {
+        self.0.send_modify(|progress| progress.discovered += count);
+    }
+
+    pub(crate) fn task_fetched(&self) {
+        self.0.send_modify(|progress| progress.fetched += 1);
+    }
 }
 
 impl ClickUpService {
     pub fn new(client_id: &str, client_secret: &str, redirect_uri: &str) -> Self {
         Self {
             base_url: "https://api.clickup.com".to_string(),
-            http_client: reqwest::Client::new(),
+            http_client: ClickUpHttpClient::default(),
             client_id: client_id.to_string(),
             client_secret: client_secret.to_string(),
             redirect_uri: redirect_uri.to_string(),
+            cancellation_token: CancellationToken::new(),
         }
     }
 
-    pub async fn get_task(
+    /// Records every request and response to `cassette`, or answers them from
+    /// it without network access when it is replaying.
+    pub fn with_cassette(mut self, cassette: Cassette) -> Self {
+        self.http_client.cassette = Some(cassette);
+        self
+    }
+
+    /// Aborts in-flight task fetches once `cancellation_token` is cancelled,
+    /// e.g. when the server is shutting down.
+    pub fn with_cancellation_token(mut self, cancellation_token: CancellationToken) -> Self {
+        self.cancellation_token = cancellation_token;
+        self
+    }
+
+    async fn get_tasks_with_time_in_status(
+        &self,
+        token: &str,
+        pages: TaskPages<'_>,
+    ) -> Result<Vec<ClickUpTaskResponseBody>, ClickUpServiceError> {
+        let fetch = async {
+            let mut tasks = vec![];
+            let mut page = 0;
+            loop {
+                let body = match pages {
+                    TaskPages::List(request_config) => {
+                        get_list_tasks_page(
+                            &self.http_client,
+                            &self.base_url,
+                            token,
+                            request_config,
+                            page,
+                        )
+                        .await?
+                    }
+                    TaskPages::Workspace(request_config) => {
+                        get_workspace_tasks_page(
+                            &self.http_client,
+                            &self.base_url,
+                            token,
+                            request_config,
+                            page,
+                        )
+                        .await?
+                    }
+                };
+                let is_last_page = body.last_page || body.tasks.is_empty();
+                tasks.extend(body.tasks);
+                if is_last_page {
+                    break;
+                }
+                page += 1;
+            }
+
+            let task_ids: Vec<String> = tasks.iter().map(|t| t.id.clone()).collect();
+            let mut time_in_status = ClickUpBulkTimeInStatusResponseBody::new();
+            for chunk in task_ids.chunks(BULK_TIME_IN_STATUS_MAX_TASKS) {
+                time_in_status.extend(
+                    get_bulk_time_in_status(&self.http_client, &self.base_url, token, chunk)
+                        .await?,
+                );
+            }
+
+            for task in &mut tasks {
+                task.time_in_status = time_in_status.remove(&task.id);
+            }
+
+            Ok(tasks)
+        };
+
+        tokio::select! {
+            _ = self.cancellation_token.cancelled() => Err(ClickUpServiceError::Cancelled),
+            tasks = fetch => tasks,
+        }
+    }
+}
+
+#[async_trait]
+impl ClickUpApi for ClickUpService {
+    async fn get_task(
         &self,
         token: &str,
         mut request_config: GetTaskRequest,
     ) -> Result<ClickUpTaskResponseBody, ClickUpServiceError> {
-        get_task_tree(
-            &self.http_client,
-            &self.base_url,
-            token,
-            &mut request_config,
-        )
-        .await
+        if let Some(progress) = &request_config.progress {
+            progress.tasks_discovered(1);
+        }
+
+        let fetch = TaskTreeFetch {
+            limits: request_config.limits,
+            task_ids: Mutex::new(HashSet::new()),
+            fetch_permits: Semaphore::new(MAX_CONCURRENT_TASK_FETCHES),
+        };
+
+        // Dropping the tree future drops every pending subtask request with it.
+        tokio::select! {
+            _ = self.cancellation_token.cancelled() => Err(ClickUpServiceError::Cancelled),
+            task = get_task_tree(
+                &self.http_client,
+                &self.base_url,
+                token,
+                &mut request_config,
+                &fetch,
+                0,
+            ) => task,
+        }
+    }
+
+    async fn get_list_tasks(
+        &self,
+        token: &str,
+        request_config: GetListTasksRequest,
+    ) -> Result<Vec<ClickUpTaskResponseBody>, ClickUpServiceError> {
+        self.get_tasks_with_time_in_status(token, TaskPages::List(&request_config))
+            .await
     }
 
-    pub fn generate_oauth_login_redirect_url(&self) -> Result<url::Url, ClickUpServiceError> {
+    async fn get_workspace_tasks(
+        &self,
+        token: &str,
+        request_config: GetWorkspaceTasksRequest,
+    ) -> Result<Vec<ClickUpTaskResponseBody>, ClickUpServiceError> {
+        self.get_tasks_with_time_in_status(token, TaskPages::Workspace(&request_config))
+            .await
+    }
+
+    fn generate_oauth_login_redirect_url(&self) -> Result<url::Url, ClickUpServiceError> {
         match url::Url::parse_with_params(
             "https://app.clickup.com/api",
             &[
@@ -73,12 +412,12 @@ impl ClickUpService {
         }
     }
 
-    pub async fn post_oauth_token(
+    async fn post_oauth_token(
         &self,
         code: String,
     ) -> Result<ClickUpOauthTokenResponseBody, ClickUpServiceError> {
         let url = format!("{}/api/v2/oauth/token", self.base_url);
-        let response = match self
+        let request = self
             .http_client
             .post(url)
             .header(reqwest::header::ACCEPT, "application/json")
@@ -87,24 +426,8 @@ impl ClickUpService {
                 "client_id": self.client_id,
                 "client_secret": self.client_secret,
                 "code": code,
-            }))
-            .send()
-            .await
-        {
-            Ok(response) => response,
-            Err(e) => {
-                return Err(ClickUpServiceError::FailedToSendNetworkRequestError(
-                    Box::new(e),
-                ));
-            }
-        };
-
-        let text = match response.text().await {
-            Ok(text) => text,
-            Err(e) => {
-                return Err(ClickUpServiceError::ParseError(Box::new(e), None));
-            }
-        };
+            }));
+        let (_, text) = self.http_client.send(request).await?;
 
         let body = match serde_json::from_str::<ClickUpOauthTokenResponseBody>(&text) {
             Ok(body) => body,
@@ -114,7 +437,7 @@ impl ClickUpService {
         Ok(body)
     }
 
-    pub async fn get_authorized_workspaces(
+    async fn get_authorized_workspaces(
         &self,
         token: String,
     ) -> Result<ClickUpGetWorkspacesResponseBody, ClickUpServiceError> {
@@ -124,22 +447,8 @@ impl ClickUpService {
             .get(url)
             .header(reqwest::header::ACCEPT, "application/json")
             .header(reqwest::header::CONTENT_TYPE, "application/json")
-            .header(reqwest::header::AUTHORIZATION, format!("Bearer {token}"));
-        let response = match request.send().await {
-            Ok(response) => response,
-            Err(e) => {
-                return Err(ClickUpServiceError::FailedToSendNetworkRequestError(
-                    Box::new(e),
-                ));
-            }
-        };
-
-        let text = match response.text().await {
-            Ok(text) => text,
-            Err(e) => {
-                return Err(ClickUpServiceError::ParseError(Box::new(e), None));
-            }
-        };
+            .header(reqwest::header::AUTHORIZATION, authorization_header(&token));
+        let (_, text) = self.http_client.send(request).await?;
 
         let body = match serde_json::from_str::<ClickUpGetWorkspacesResponseBody>(&text) {
             Ok(body) => body,
@@ -150,19 +459,86 @@ impl ClickUpService {
 
         Ok(body)
     }
+
+    async fn get_list_custom_fields(
+        &self,
+        token: &str,
+        list_id: &str,
+    ) -> Result<Vec<ClickUpCustomField>, ClickUpServiceError> {
+        let url = format!("{}/api/v2/list/{list_id}/field", self.base_url);
+        let request = self
+            .http_client
+            .get(url)
+            .header(reqwest::header::ACCEPT, "application/json")
+            .header(reqwest::header::CONTENT_TYPE, "application/json")
+            .header(reqwest::header::AUTHORIZATION, authorization_header(token));
+        let (status_code, text) = self.http_client.send(request).await?;
+
+        match serde_json::from_str::<ClickUpListCustomFieldsResponseBody>(&text) {
+            Ok(body) => Ok(body.fields),
+            Err(e) => Err(ClickUpServiceError::ParseError(
+                Box::new(e),
+                Some(format!("get_list_custom_fields = {status_code} {text}")),
+            )),
+        }
+    }
+
+    async fn get_folder_lists(
+        &self,
+        token: &str,
+        folder_id: &str,
+    ) -> Result<Vec<ClickUpList>, ClickUpServiceError> {
+        let url = format!("{}/api/v2/folder/{folder_id}/list", self.base_url);
+        let request = self
+            .http_client
+            .get(url)
+            .header(reqwest::header::ACCEPT, "application/json")
+            .header(reqwest::header::CONTENT_TYPE, "application/json")
+            .header(reqwest::header::AUTHORIZATION, authorization_header(token))
+            .query(&[("archived", "false")]);
+        let (status_code, text) = self.http_client.send(request).await?;
+
+        match serde_json::from_str::<ClickUpFolderListsResponseBody>(&text) {
+            Ok(body) => Ok(body.lists),
+            Err(e) => Err(ClickUpServiceError::ParseError(
+                Box::new(e),
+                Some(format!("get_folder_lists = {status_code} {text}")),
+            )),
+        }
+    }
+
+    async fn ping(&self) -> Result<(), ClickUpServiceError> {
+        // There is no network to check when replaying a cassette.
+        if self.http_client.is_replaying() {
+            return Ok(());
+        }
+
+        let url = format!("{}/api/v2/team", self.base_url);
+        let request = self
+            .http_client
+            .get(url)
+            .header(reqwest::header::ACCEPT, "application/json");
+        let (status_code, _) = self.http_client.send(request).await?;
+
+        if status_code.is_server_error() {
+            return Err(ClickUpServiceError::UnexpectedError);
+        }
+
+        Ok(())
+    }
 }
 
-#[derive(Serialize, Deserialize)]
+#[derive(Clone, Serialize, Deserialize)]
 pub struct ClickUpOauthTokenResponseBody {
     pub access_token: String,
 }
 
-#[derive(Serialize, Deserialize)]
+#[derive(Clone, Serialize, Deserialize)]
 pub struct ClickUpGetWorkspacesResponseBody {
     pub teams: Vec<ClickUpWorkspace>,
 }
 
-#[derive(Serialize, Deserialize)]
+#[derive(Clone, Serialize, Deserialize)]
 pub struct ClickUpWorkspace {
     pub id: String,
     pub name: String,
@@ -171,12 +547,12 @@ pub struct ClickUpWorkspace {
     pub members: Vec<ClickUpWorkspaceMember>,
 }
 
-#[derive(Serialize, Deserialize)]
+#[derive(Clone, Serialize, Deserialize)]
 pub struct ClickUpWorkspaceMember {
     pub user: ClickUpWorkspaceUser,
 }
 
-#[derive(Serialize, Deserialize)]
+#[derive(Clone, Serialize, Deserialize)]
 pub struct ClickUpWorkspaceUser {
     pub id: i32,
     pub username: String,
@@ -185,42 +561,87 @@ pub struct ClickUpWorkspaceUser {
     pub profile_picture: Option<String>,
 }
 
+/// Personal API tokens are sent as they are, OAuth access tokens as bearer
+/// tokens.
+fn authorization_header(token: &str) -> String {
+    if token.starts_with(PERSONAL_TOKEN_PREFIX) {
+        token.to_string()
+    } else {
+        format!("Bearer {token}")
+    }
+}
+
 #[async_recursion]
 async fn get_task_tree(
-    http_client: &reqwest::Client,
+    http_client: &ClickUpHttpClient,
     base_url: &str,
     token: &str,
     request_config: &mut GetTaskRequest,
+    fetch: &TaskTreeFetch,
+    depth: usize,
 ) -> Result<ClickUpTaskResponseBody, ClickUpServiceError> {
-    let mut task = get_task(http_client, base_url, token, &request_config.clone()).await?;
+    let mut task = {
+        let _permit = fetch.fetch_permits.acquire().await;
+        get_task(http_client, base_url, token, &request_config.clone()).await?
+    };
+    // The root may have been asked for by its custom id.
+    fetch.task_ids.lock().unwrap().insert(task.id.clone());
+
+    if let Some(progress) = &request_config.progress {
+        progress.task_fetched();
+    }
 
     if let ClickUpTaskResponseBody {
         sub_tasks: Some(sub_tasks),
         ..
     } = &mut task
     {
-        let mut requests = futures::stream::FuturesUnordered::new();
-        for (i, sub_task_record) in sub_tasks.iter().enumerate() {
+        let mut requests = vec![];
+        for (i, sub_task_record) in sub_tasks.iter_mut().enumerate() {
+            if let Err(reason) = fetch.claim(&sub_task_record.id, depth + 1) {
+                sub_task_record.load_error = Some(reason);
+                continue;
+            }
+
             let sub_task_id = sub_task_record.id.clone();
             request_config.task_id = sub_task_id;
             let mut request_config_clone = request_config.clone();
             requests.push(async move {
-                let task =
-                    get_task_tree(http_client, base_url, token, &mut request_config_clone).await;
+                let task = get_task_tree(
+                    http_client,
+                    base_url,
+                    token,
+                    &mut request_config_clone,
+                    fetch,
+                    depth + 1,
+                )
+                .await;
 
                 (i, task)
             });
         }
 
-        while let Some((i, sub_task_request)) = requests.next().await {
-            let task = match sub_task_request {
-                Ok(task) => task,
-                Err(e) => return Err(e),
-            };
+        if let Some(progress) = &request_config.progress {
+            progress.tasks_discovered(requests.len());
+        }
 
-            if let Some(sub_task) = sub_tasks.get_mut(i) {
-                sub_task.task = Some(task);
+        let mut requests =
+            futures::stream::iter(requests).buffer_unordered(MAX_CONCURRENT_TASK_FETCHES);
+        while let Some((i, sub_task_request)) = requests.next().await {
+            let Some(sub_task) = sub_tasks.get_mut(i) else {
+                continue;
             };
+            match sub_task_request {
+                Ok(task) => sub_task.task = Some(task),
+                // One deleted or forbidden sub task shouldn't fail the whole
+                // tree, it is marked instead.
+                Err(e) => {
+                    sub_task.load_error = Some(e.load_error());
+                    if let Some(progress) = &request_config.progress {
+                        progress.task_fetched();
+                    }
+                }
+            }
         }
     };
 
@@ -228,7 +649,7 @@ async fn get_task_tree(
 }
 
 pub async fn get_task(
-    http_client: &reqwest::Client,
+    http_client: &ClickUpHttpClient,
     base_url: &str,
     token: &str,
     request_config: &GetTaskRequest,
@@ -246,24 +667,10 @@ pub async fn get_task(
             .get(url)
             .header(reqwest::header::ACCEPT, "application/json")
             .header(reqwest::header::CONTENT_TYPE, "application/json")
-            .header(reqwest::header::AUTHORIZATION, format!("Bearer {token}"))
+            .header(reqwest::header::AUTHORIZATION, authorization_header(token))
             .query(&query_params);
 
-        let response = match request.send().await {
-            Ok(response) => response,
-            Err(e) => {
-                return Err(ClickUpServiceError::FailedToSendNetworkRequestError(
-                    Box::new(e),
-                ));
-            }
-        };
-
-        let status_code = response.status();
-
-        let text = match response.text().await {
-            Ok(text) => text,
-            Err(e) => return Err(ClickUpServiceError::ParseError(Box::new(e), None)),
-        };
+        let (status_code, text) = http_client.send(request).await?;
 
         let task = match serde_json::from_str::<ClickUpTaskResponseBody>(&text) {
             Ok(task) => task,
@@ -271,6 +678,11 @@ pub async fn get_task(
                 if text.contains(NOT_AUTHORIZED_ERROR_CODE) && request_config.workspace_id.is_none()
                 {
                     return Err(ClickUpServiceError::CustomIDError);
+                } else if !status_code.is_success() {
+                    return Err(ClickUpServiceError::UnexpectedStatus(
+                        status_code.as_u16(),
+                        format!("get_task {text}"),
+                    ));
                 } else {
                     return Err(ClickUpServiceError::ParseError(
                         Box::new(e),
@@ -297,7 +709,7 @@ pub async fn get_task(
 }
 
 async fn get_task_time_in_status(
-    http_client: &reqwest::Client,
+    http_client: &ClickUpHttpClient,
     base_url: &str,
     token: &str,
     request_config: &GetTaskRequest,
@@ -310,7 +722,7 @@ async fn get_task_time_in_status(
         .get(url)
         .header(reqwest::header::ACCEPT, "application/json")
         .header(reqwest::header::CONTENT_TYPE, "application/json")
-        .header(reqwest::header::AUTHORIZATION, format!("Bearer {token}"));
+        .header(reqwest::header::AUTHORIZATION, authorization_header(token));
 
     if let Some(workspace_id) = &request_config.workspace_id {
         request = request.query(&[
@@ -318,19 +730,7 @@ async fn get_task_time_in_status(
             ("team_id", workspace_id.as_str()),
         ]);
     }
-    let response = match request.send().await {
-        Ok(response) => response,
-        Err(e) => {
-            return Err(ClickUpServiceError::FailedToSendNetworkRequestError(
-                Box::new(e),
-            ));
-        }
-    };
-
-    let text = match response.text().await {
-        Ok(text) => text,
-        Err(e) => return Err(ClickUpServiceError::ParseError(Box::new(e), None)),
-    };
+    let (status_code, text) = http_client.send(request).await?;
 
     match serde_json::from_str::<ClickUpTimeInStatusResponseBody>(&text) {
         Ok(v) => Ok(v),
@@ -341,6 +741,11 @@ async fn get_task_time_in_status(
                 && request_config.workspace_id.is_none()
             {
                 Err(ClickUpServiceError::CustomIDError)
+            } else if !status_code.is_success() {
+                Err(ClickUpServiceError::UnexpectedStatus(
+                    status_code.as_u16(),
+                    format!("get_task_time_in_status {text}"),
+                ))
             } else {
                 Err(ClickUpServiceError::ParseError(
                     Box::new(e),
@@ -350,3 +755,143 @@ async fn get_task_time_in_status(
         }
     }
 }
+
+async fn get_list_tasks_page(
+    http_client: &ClickUpHttpClient,
+    base_url: &str,
+    token: &str,
+    request_config: &GetListTasksRequest,
+    page: u32,
+) -> Result<ClickUpListTasksResponseBody, ClickUpServiceError> {
+    let url = format!("{base_url}/api/v2/list/{}/task", request_config.list_id);
+    let request = http_client
+        .get(url)
+        .header(reqwest::header::ACCEPT, "application/json")
+        .header(reqwest::header::CONTENT_TYPE, "application/json")
+        .header(reqwest::header::AUTHORIZATION, authorization_header(token))
+        .query(&[
+            ("page", page.to_string()),
+            ("subtasks", "true".to_string()),
+            ("include_closed", request_config.include_closed.to_string()),
+        ]);
+
+    let (status_code, text) = http_client.send(request).await?;
+
+    match serde_json::from_str::<ClickUpListTasksResponseBody>(&text) {
+        Ok(body) => Ok(body),
+        Err(e) => Err(ClickUpServiceError::ParseError(
+            Box::new(e),
+            Some(format!("get_list_tasks_page = {status_code} {text}")),
+        )),
+    }
+}
+
+async fn get_workspace_tasks_page(
+    http_client: &ClickUpHttpClient,
+    base_url: &str,
+    token: &str,
+    request_config: &GetWorkspaceTasksRequest,
+    page: u32,
+) -> Result<ClickUpListTasksResponseBody, ClickUpServiceError> {
+    let url = format!(
+        "{base_url}/api/v2/team/{}/task",
+        request_config.workspace_id
+    );
+    let mut query_params = vec![
+        ("page", page.to_string()),
+        ("subtasks", "true".to_string()),
+        ("include_closed", request_config.include_closed.to_string()),
+    ];
+    for space_id in &request_config.space_ids {
+        query_params.push(("space_ids[]", space_id.clone()));
+    }
+    // ClickUp calls folders projects in this endpoint.
+    for folder_id in &request_config.folder_ids {
+        query_params.push(("project_ids[]", folder_id.clone()));
+    }
+
+    let request = http_client
+        .get(url)
+        .header(reqwest::header::ACCEPT, "application/json")
+        .header(reqwest::header::CONTENT_TYPE, "application/json")
+        .header(reqwest::header::AUTHORIZATION, authorization_header(token))
+        .query(&query_params);
+
+    let (status_code, text) = http_client.send(request).await?;
+
+    match serde_json::from_str::<ClickUpListTasksResponseBody>(&text) {
+        Ok(body) => Ok(body),
+        Err(e) => Err(ClickUpServiceError::ParseError(
+            Box::new(e),
+            Some(format!("get_workspace_tasks_page = {status_code} {text}")),
+        )),
+    }
+}
+
+async fn get_bulk_time_in_status(
+    http_client: &ClickUpHttpClient,
+    base_url: &str,
+    token: &str,
+    task_ids: &[String],
+) -> Result<ClickUpBulkTimeInStatusResponseBody, ClickUpServiceError> {
+    let url = format!("{base_url}/api/v2/task/bulk_time_in_status/task_ids");
+    let query_params: Vec<(&str, &str)> = task_ids
+        .iter()
+        .map(|task_id| ("task_ids", task_id.as_str()))
+        .collect();
+    let request = http_client
+        .get(url)
+        .header(reqwest::header::ACCEPT, "application/json")
+        .header(reqwest::header::CONTENT_TYPE, "application/json")
+        .header(reqwest::header::AUTHORIZATION, authorization_header(token))
+        .query(&query_params);
+
+    let (_, text) = http_client.send(request).await?;
+
+    match serde_json::from_str::<ClickUpBulkTimeInStatusResponseBody>(&text) {
+        Ok(v) => Ok(v),
+        Err(e) => {
+            if text.contains(TIME_IN_STATUS_NOT_ENABLED_ERROR_CODE) {
+                Err(ClickUpServiceError::TimeInStatusNotEnabled)
+            } else {
+                Err(ClickUpServiceError::ParseError(
+                    Box::new(e),
+                    Some(format!("get_bulk_time_in_status {text}")),
+                ))
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn task_tree_fetch(max_depth: usize, max_tasks: usize) -> TaskTreeFetch {
+        TaskTreeFetch {
+            limits: TaskTreeLimits {
+                max_depth,
+                max_tasks,
+            },
+            task_ids: Mutex::new(HashSet::from(["root".to_string()])),
+            fetch_permits: Semaphore::new(MAX_CONCURRENT_TASK_FETCHES),
+        }
+    }
+
+    #[test]
+    fn claims_each_sub_task_once_within_the_limits() {
+        let fetch = task_tree_fetch(2, 3);
+
+        assert_eq!(fetch.claim("a", 1), Ok(()));
+        assert_eq!(fetch.claim("a", 2), Err(SubTaskLoadError::AlreadyInTree));
+        assert!(matches!(
+            fetch.claim("b", 3),
+            Err(SubTaskLoadError::NotFetched(_))
+        ));
+        assert_eq!(fetch.claim("b", 2), Ok(()));
+        assert!(matches!(
+            fetch.claim("c", 1),
+            Err(SubTaskLoadError::NotFetched(_))
+        ));
+    }
+}
@@ -0,0 +1,5 @@
+mod models;
+mod service;
+
+pub use models::*;
+pub use service::*;
@@ -0,0 +1,18 @@
+use crate::services::clickup::FetchProgress;
+use chrono::{DateTime, Utc};
+
+#[derive(Debug, Clone)]
+pub enum JobState<T> {
+    Running,
+    Completed(T),
+    Failed(String),
+}
+
+#[derive(Debug, Clone)]
+pub struct JobSnapshot<T> {
+    pub id: String,
+    pub label: String,
+    pub created_at: DateTime<Utc>,
+    pub progress: FetchProgress,
+    pub state: JobState<T>,
+}
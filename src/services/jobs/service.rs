@@ -0,0 +1,217 @@
+use crate::services::{
+    clickup::{FetchProgress, FetchProgressReporter},
+    jobs::{JobSnapshot, JobState},
+};
+use chrono::{DateTime, TimeDelta, Utc};
+use std::{
+    collections::HashMap,
+    future::Future,
+    sync::{Arc, RwLock},
+};
+use tokio::sync::watch;
+use tokio_util::task::TaskTracker;
+
+const JOB_RETENTION: TimeDelta = TimeDelta::hours(24);
+
+/// How many jobs are kept at most, across every session. The oldest is
+/// dropped to make room for a new one.
+const MAX_JOBS: usize = 1000;
+
+struct Job<T> {
+    label: String,
+    created_at: DateTime<Utc>,
+    progress: watch::Receiver<FetchProgress>,
+    state: JobState<T>,
+}
+
+/// Runs analyses in the background so they outlive the request (and browser
+/// tab) that started them. Jobs are kept in memory for [`JOB_RETENTION`], up
+/// to [`MAX_JOBS`] of them.
+#[derive(Clone)]
+pub struct JobService<T> {
+    jobs: Arc<RwLock<HashMap<String, Job<T>>>>,
+    running: TaskTracker,
+}
+
+impl<T> Default for JobService<T> {
+    fn default() -> Self {
+        Self {
+            jobs: Arc::new(RwLock::new(HashMap::new())),
+            running: TaskTracker::new(),
+        }
+    }
+}
+
+impl<T> JobService<T>
+where
+    T: Clone + Send + Sync + 'static,
+{
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Starts `run` on the runtime and returns the new job's id.
+    pub fn spawn<F, Fut>(&self, label: String, run: F) -> String
+    where
+        F: FnOnce(FetchProgressReporter) -> Fut,
+        Fut: Future<Output = Result<T, String>> + Send + 'static,
+    {
+        let id = format!("{:032x}", rand::random::<u128>());
+        let (progress_reporter, progress) = FetchProgressReporter::new();
+
+        {
+            let mut jobs = self.jobs.write().unwrap();
+            let now = Utc::now();
+            jobs.retain(|_, job| !job.is_expired(now));
+            while jobs.len() >= MAX_JOBS {
+                let Some(oldest_id) = jobs
+                    .iter()
+                    .min_by_key(|(_, job)| job.created_at)
+                    .map(|(id, _)| id.clone())
+                else {
+                    break;
+                };
+                jobs.remove(&oldest_id);
+            }
+            jobs.insert(
+                id.clone(),
+                Job {
+                    label,
+                    created_at: now,
+                    progress,
+                    state: JobState::Running,
+                },
+            );
+        }
+
+        let future = run(progress_reporter.clone());
+        let jobs = self.jobs.clone();
+        let job_id = id.clone();
+        self.running.spawn(async move {
+            let state = match future.await {
+                Ok(output) => JobState::Completed(output),
+                Err(message) => JobState::Failed(message),
+            };
+
+            if let Some(job) = jobs.write().unwrap().get_mut(&job_id) {
+                job.state = state;
+            }
+
+            // Subscribers treat the progress channel closing as the job being
+            // finished, so it must only close once the state is stored.
+            drop(progress_reporter);
+        });
+
+        id
+    }
+
+    /// Waits for every running job to finish, for a graceful shutdown. Jobs
+    /// started while waiting are waited for too.
+    pub async fn drain(&self) {
+        self.running.close();
+        self.running.wait().await;
+    }
+
+    pub fn get(&self, id: &str) -> Option<JobSnapshot<T>> {
+        let jobs = self.jobs.read().unwrap();
+        let job = jobs.get(id).filter(|job| !job.is_expired(Utc::now()))?;
+
+        Some(JobSnapshot {
+            id: id.to_string(),
+            label: job.label.clone(),
+            created_at: job.created_at,
+            progress: *job.progress.borrow(),
+            state: job.state.clone(),
+        })
+    }
+
+    /// Returns a receiver that yields every progress update for the job and
+    /// closes once the job has completed or failed.
+    pub fn subscribe(&self, id: &str) -> Option<watch::Receiver<FetchProgress>> {
+        let jobs = self.jobs.read().unwrap();
+        jobs.get(id)
+            .filter(|job| !job.is_expired(Utc::now()))
+            .map(|job| job.progress.clone())
+    }
+}
+
+impl<T> Job<T> {
+    fn is_expired(&self, now: DateTime<Utc>) -> bool {
+        now - self.created_at >= JOB_RETENTION
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn stores_the_outcome_once_the_job_finishes() {
+        let job_service = JobService::<u32>::new();
+        let completed_id = job_service.spawn("completed".to_string(), |progress| async move {
+            progress.tasks_discovered(2);
+            progress.task_fetched();
+            Ok(7)
+        });
+        let failed_id = job_service.spawn("failed".to_string(), |_| async {
+            Err("<b>ClickUp</b> is down".to_string())
+        });
+
+        let mut progress = job_service.subscribe(&completed_id).unwrap();
+        job_service.drain().await;
+
+        let completed = job_service.get(&completed_id).unwrap();
+        assert!(matches!(completed.state, JobState::Completed(7)));
+        assert_eq!(completed.progress.discovered, 2);
+        assert_eq!(completed.progress.fetched, 1);
+        progress.borrow_and_update();
+        assert!(progress.changed().await.is_err());
+
+        let failed = job_service.get(&failed_id).unwrap();
+        assert!(matches!(failed.state, JobState::Failed(message) if message.contains("ClickUp")));
+    }
+
+    #[tokio::test]
+    async fn expired_jobs_are_gone() {
+        let job_service = JobService::<u32>::new();
+        let id = job_service.spawn("expired".to_string(), |_| async { Ok(1) });
+        job_service.drain().await;
+
+        job_service
+            .jobs
+            .write()
+            .unwrap()
+            .get_mut(&id)
+            .unwrap()
+            .created_at -= JOB_RETENTION;
+
+        assert!(job_service.get(&id).is_none());
+        assert!(job_service.subscribe(&id).is_none());
+    }
+
+    #[tokio::test]
+    async fn drops_the_oldest_job_past_the_limit() {
+        let job_service = JobService::<u32>::new();
+        let ids: Vec<_> = (0..MAX_JOBS)
+            .map(|i| {
+                let id = job_service.spawn(format!("job {i}"), |_| async { Ok(1) });
+                job_service
+                    .jobs
+                    .write()
+                    .unwrap()
+                    .get_mut(&id)
+                    .unwrap()
+                    .created_at -= TimeDelta::seconds((MAX_JOBS - i) as i64);
+                id
+            })
+            .collect();
+
+        let newest_id = job_service.spawn("newest".to_string(), |_| async { Ok(1) });
+        job_service.drain().await;
+
+        assert_eq!(job_service.jobs.read().unwrap().len(), MAX_JOBS);
+        assert!(job_service.get(&ids[0]).is_none());
+        assert!(job_service.get(&ids[1]).is_some());
+        assert!(job_service.get(&newest_id).is_some());
+    }
+}
@@ -1 +1,4 @@
 pub mod clickup;
+pub mod jobs;
+pub mod offline_datasets;
+pub mod workspace_settings;
@@ -0,0 +1,163 @@
+use crate::domain::offline::OfflineDataset;
+use chrono::{DateTime, TimeDelta, Utc};
+use std::{
+    collections::HashMap,
+    sync::{Arc, RwLock},
+};
+
+const DATASET_RETENTION: TimeDelta = TimeDelta::hours(24);
+/// Most datasets a session keeps at once.
+const MAX_SESSION_DATASETS: usize = 5;
+/// Most upload bytes a session keeps at once. Parsed datasets take about as
+/// much memory as the uploads they came from.
+const MAX_SESSION_DATASET_BYTES: usize = 128 * 1024 * 1024;
+/// Most upload bytes kept at once, across every session.
+const MAX_TOTAL_DATASET_BYTES: usize = 1024 * 1024 * 1024;
+
+/// Every session's datasets together would go past
+/// [`MAX_TOTAL_DATASET_BYTES`].
+#[derive(Debug)]
+pub struct OfflineDatasetsFull;
+
+struct StoredDataset {
+    /// The id of the session that uploaded the dataset.
+    owner: String,
+    created_at: DateTime<Utc>,
+    /// Orders datasets stored within the same instant.
+    sequence: u64,
+    /// The size of the upload the dataset was parsed from.
+    size: usize,
+    dataset: Arc<OfflineDataset>,
+}
+
+/// Keeps uploaded datasets so every report can be run against them without
+/// uploading them again. Datasets are kept in memory for
+/// [`DATASET_RETENTION`]. A session's oldest datasets are dropped early to
+/// stay within [`MAX_SESSION_DATASETS`] and [`MAX_SESSION_DATASET_BYTES`],
+/// but other sessions' datasets never are.
+#[derive(Clone, Default)]
+pub struct OfflineDatasetService {
+    datasets: Arc<RwLock<HashMap<String, StoredDataset>>>,
+}
+
+impl OfflineDatasetService {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Stores `dataset`, parsed from an upload of `size` bytes by the session
+    /// `owner`, and returns its id.
+    pub fn insert(
+        &self,
+        owner: &str,
+        dataset: Arc<OfflineDataset>,
+        size: usize,
+    ) -> Result<String, OfflineDatasetsFull> {
+        let id = format!("{:032x}", rand::random::<u128>());
+
+        let mut datasets = self.datasets.write().unwrap();
+        let now = Utc::now();
+        datasets.retain(|_, stored| now - stored.created_at < DATASET_RETENTION);
+
+        let mut owned: Vec<(&String, &StoredDataset)> = datasets
+            .iter()
+            .filter(|(_, stored)| stored.owner == owner)
+            .collect();
+        owned.sort_by_key(|(_, stored)| stored.sequence);
+        let mut owned_size: usize =
+            owned.iter().map(|(_, stored)| stored.size).sum::<usize>() + size;
+        let mut evicted_ids = vec![];
+        for (id, stored) in owned.iter() {
+            if owned.len() - evicted_ids.len() < MAX_SESSION_DATASETS
+                && owned_size <= MAX_SESSION_DATASET_BYTES
+            {
+                break;
+            }
+            evicted_ids.push((*id).clone());
+            owned_size -= stored.size;
+        }
+
+        let total_size: usize = datasets
+            .iter()
+            .filter(|(id, _)| !evicted_ids.contains(id))
+            .map(|(_, stored)| stored.size)
+            .sum::<usize>()
+            + size;
+        if total_size > MAX_TOTAL_DATASET_BYTES {
+            return Err(OfflineDatasetsFull);
+        }
+        for evicted_id in &evicted_ids {
+            datasets.remove(evicted_id);
+        }
+
+        let sequence = datasets.values().map(|stored| stored.sequence + 1).max();
+        datasets.insert(
+            id.clone(),
+            StoredDataset {
+                owner: owner.to_string(),
+                created_at: now,
+                sequence: sequence.unwrap_or_default(),
+                size,
+                dataset,
+            },
+        );
+
+        Ok(id)
+    }
+
+    pub fn get(&self, id: &str) -> Option<Arc<OfflineDataset>> {
+        let datasets = self.datasets.read().unwrap();
+        datasets
+            .get(id)
+            .filter(|stored| Utc::now() - stored.created_at < DATASET_RETENTION)
+            .map(|stored| stored.dataset.clone())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn evicts_the_sessions_oldest_datasets_past_its_caps() {
+        let service = OfflineDatasetService::new();
+        let dataset = Arc::new(OfflineDataset::default());
+        let other_id = service.insert("other", dataset.clone(), 1).unwrap();
+
+        let ids: Vec<String> = (0..MAX_SESSION_DATASETS + 1)
+            .map(|_| service.insert("session", dataset.clone(), 1).unwrap())
+            .collect();
+        assert!(service.get(&ids[0]).is_none());
+        assert!(ids[1..].iter().all(|id| service.get(id).is_some()));
+
+        let large_id = service
+            .insert("session", dataset.clone(), MAX_SESSION_DATASET_BYTES)
+            .unwrap();
+        assert!(ids.iter().all(|id| service.get(id).is_none()));
+        assert!(service.get(&large_id).is_some());
+        assert!(service.get(&other_id).is_some());
+    }
+
+    #[test]
+    fn refuses_uploads_past_the_total_cap() {
+        let service = OfflineDatasetService::new();
+        let dataset = Arc::new(OfflineDataset::default());
+        let sessions = MAX_TOTAL_DATASET_BYTES / MAX_SESSION_DATASET_BYTES;
+        let ids: Vec<String> = (0..sessions)
+            .map(|i| {
+                service
+                    .insert(&i.to_string(), dataset.clone(), MAX_SESSION_DATASET_BYTES)
+                    .unwrap()
+            })
+            .collect();
+
+        assert!(service.insert("late", dataset.clone(), 1).is_err());
+        assert!(ids.iter().all(|id| service.get(id).is_some()));
+        // Replacing a session's own dataset still fits.
+        let replacement = service
+            .insert("0", dataset, MAX_SESSION_DATASET_BYTES)
+            .unwrap();
+        assert!(service.get(&ids[0]).is_none());
+        assert!(service.get(&replacement).is_some());
+    }
+}
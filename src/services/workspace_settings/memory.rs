@@ -0,0 +1,47 @@
+use crate::services::workspace_settings::{
+    WorkspaceSettings, WorkspaceSettingsServiceError, WorkspaceSettingsStore,
+    WorkspaceSettingsUpdate,
+};
+use async_trait::async_trait;
+use std::{
+    collections::HashMap,
+    sync::{Arc, RwLock},
+};
+
+/// Keeps settings in memory, so they are lost on restart. Lets the routes run
+/// without Redis.
+#[derive(Clone, Default)]
+pub struct InMemoryWorkspaceSettingsStore {
+    settings: Arc<RwLock<HashMap<String, WorkspaceSettings>>>,
+}
+
+impl InMemoryWorkspaceSettingsStore {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+#[async_trait]
+impl WorkspaceSettingsStore for InMemoryWorkspaceSettingsStore {
+    async fn get(
+        &self,
+        workspace_id: &str,
+    ) -> Result<WorkspaceSettings, WorkspaceSettingsServiceError> {
+        let settings = self.settings.read().unwrap();
+        Ok(settings.get(workspace_id).cloned().unwrap_or_default())
+    }
+
+    async fn update(
+        &self,
+        workspace_id: &str,
+        update: WorkspaceSettingsUpdate,
+    ) -> Result<(), WorkspaceSettingsServiceError> {
+        self.settings
+            .write()
+            .unwrap()
+            .entry(workspace_id.to_string())
+            .or_default()
+            .apply(update);
+        Ok(())
+    }
+}
@@ -0,0 +1,9 @@
+mod memory;
+mod models;
+mod service;
+mod store;
+
+pub use memory::*;
+pub use models::*;
+pub use service::*;
+pub use store::*;
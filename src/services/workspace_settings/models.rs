@@ -0,0 +1,119 @@
+use crate::services::workspace_settings::WorkspaceSettingsUpdate;
+use chrono::{NaiveDate, NaiveTime, Weekday};
+use chrono_tz::Tz;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+/// Analysis settings shared by everyone analysing tasks in a workspace.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct WorkspaceSettings {
+    /// Keyed by lowercase status name.
+    #[serde(default)]
+    pub status_categories: HashMap<String, StatusCategory>,
+    /// Sprint points are used as the estimate when unset.
+    #[serde(default)]
+    pub estimate_field: Option<EstimateField>,
+    /// Durations count wall-clock time when unset.
+    #[serde(default)]
+    pub working_hours: Option<WorkingHours>,
+    #[serde(default)]
+    pub calendar: Option<WorkCalendar>,
+}
+
+/// A custom field used as the task estimate in place of sprint points.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EstimateField {
+    pub field_id: String,
+    pub field_name: String,
+    /// Points per dropdown option, keyed by lowercase option name. Options
+    /// without a rule fall back to their name read as a number.
+    #[serde(default)]
+    pub option_points: HashMap<String, f32>,
+}
+
+/// The daily window, Monday to Friday, in which a team's time is counted.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct WorkingHours {
+    pub start: NaiveTime,
+    pub end: NaiveTime,
+    /// An IANA time zone such as `Europe/Paris`, so the window follows
+    /// daylight saving time.
+    pub time_zone: Tz,
+}
+
+/// The days a team works, for teams that don't work Monday to Friday or want
+/// their holidays left out.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct WorkCalendar {
+    pub working_days: Vec<Weekday>,
+    pub holidays: Vec<NaiveDate>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum StatusCategory {
+    /// Someone is working on the task, e.g. "in progress" or "in review".
+    Active,
+    /// The task is queued or blocked, e.g. "ready for qa" or "blocked".
+    Waiting,
+}
+
+impl WorkspaceSettings {
+    pub fn status_category(&self, status: &str) -> Option<StatusCategory> {
+        self.status_categories.get(&status.to_lowercase()).copied()
+    }
+
+    pub fn apply(&mut self, update: WorkspaceSettingsUpdate) {
+        match update {
+            WorkspaceSettingsUpdate::StatusCategory(status, Some(category)) => {
+                self.status_categories
+                    .insert(status.to_lowercase(), category);
+            }
+            WorkspaceSettingsUpdate::StatusCategory(status, None) => {
+                self.status_categories.remove(&status.to_lowercase());
+            }
+            WorkspaceSettingsUpdate::EstimateField(estimate_field) => {
+                self.estimate_field = estimate_field
+            }
+            WorkspaceSettingsUpdate::WorkingHours(working_hours) => {
+                self.working_hours = working_hours
+            }
+            WorkspaceSettingsUpdate::Calendar(calendar) => self.calendar = calendar,
+        }
+    }
+}
+
+impl EstimateField {
+    pub fn option_points(&self, option_name: &str) -> Option<f32> {
+        match self.option_points.get(&option_name.to_lowercase()) {
+            Some(points) => Some(*points),
+            None => option_name.trim().parse().ok(),
+        }
+    }
+}
+
+impl WorkingHours {
+    /// Returns `None` unless the window starts before it ends on the same day.
+    pub fn new(start: NaiveTime, end: NaiveTime, time_zone: Tz) -> Option<Self> {
+        if start >= end {
+            return None;
+        }
+
+        Some(Self {
+            start,
+            end,
+            time_zone,
+        })
+    }
+
+    /// Parses `09:00-17:00` style hours and an IANA time zone name.
+    pub fn parse(hours: &str, time_zone: &str) -> Option<Self> {
+        let (start, end) = hours.split_once('-')?;
+
+        Self::new(
+            NaiveTime::parse_from_str(start.trim(), "%H:%M").ok()?,
+            NaiveTime::parse_from_str(end.trim(), "%H:%M").ok()?,
+            time_zone.trim().parse().ok()?,
+        )
+    }
+}
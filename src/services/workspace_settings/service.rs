@@ -0,0 +1,117 @@
+use crate::services::workspace_settings::{
+    WorkspaceSettings, WorkspaceSettingsStore, WorkspaceSettingsUpdate,
+};
+use async_trait::async_trait;
+use std::{collections::HashMap, error::Error};
+use tower_sessions_redis_store::fred::prelude::*;
+
+const KEY_PREFIX: &str = "workspace_settings";
+const STATUS_CATEGORY_FIELD_PREFIX: &str = "status_category:";
+const ESTIMATE_FIELD_FIELD: &str = "estimate_field";
+const WORKING_HOURS_FIELD: &str = "working_hours";
+const CALENDAR_FIELD: &str = "calendar";
+
+#[derive(Debug)]
+pub enum WorkspaceSettingsServiceError {
+    StoreError(Box<dyn Error + Send + 'static>),
+    ParseError(Box<dyn Error + Send + 'static>),
+}
+
+/// Stores [`WorkspaceSettings`] in Redis as a hash per workspace, with each
+/// setting, and each status's category, as a JSON field. Every update writes
+/// a single field, so concurrent updates to different settings all stick.
+#[derive(Clone)]
+pub struct WorkspaceSettingsService {
+    redis_pool: Pool,
+}
+
+impl WorkspaceSettingsService {
+    pub fn new(redis_pool: Pool) -> Self {
+        Self { redis_pool }
+    }
+}
+
+#[async_trait]
+impl WorkspaceSettingsStore for WorkspaceSettingsService {
+    async fn get(
+        &self,
+        workspace_id: &str,
+    ) -> Result<WorkspaceSettings, WorkspaceSettingsServiceError> {
+        let fields: HashMap<String, String> = match self.redis_pool.hgetall(key(workspace_id)).await
+        {
+            Ok(fields) => fields,
+            Err(e) => return Err(WorkspaceSettingsServiceError::StoreError(Box::new(e))),
+        };
+
+        let mut settings = WorkspaceSettings::default();
+        for (field, value) in fields {
+            let parse_error = |e| WorkspaceSettingsServiceError::ParseError(Box::new(e));
+            match field.as_str() {
+                ESTIMATE_FIELD_FIELD => {
+                    settings.estimate_field = serde_json::from_str(&value).map_err(parse_error)?
+                }
+                WORKING_HOURS_FIELD => {
+                    settings.working_hours = serde_json::from_str(&value).map_err(parse_error)?
+                }
+                CALENDAR_FIELD => {
+                    settings.calendar = serde_json::from_str(&value).map_err(parse_error)?
+                }
+                _ => {
+                    if let Some(status) = field.strip_prefix(STATUS_CATEGORY_FIELD_PREFIX) {
+                        settings.status_categories.insert(
+                            status.to_string(),
+                            serde_json::from_str(&value).map_err(parse_error)?,
+                        );
+                    }
+                }
+            }
+        }
+
+        Ok(settings)
+    }
+
+    async fn update(
+        &self,
+        workspace_id: &str,
+        update: WorkspaceSettingsUpdate,
+    ) -> Result<(), WorkspaceSettingsServiceError> {
+        let (field, value) = match &update {
+            WorkspaceSettingsUpdate::StatusCategory(status, category) => (
+                format!("{STATUS_CATEGORY_FIELD_PREFIX}{}", status.to_lowercase()),
+                category.as_ref().map(serde_json::to_string),
+            ),
+            WorkspaceSettingsUpdate::EstimateField(estimate_field) => (
+                ESTIMATE_FIELD_FIELD.to_string(),
+                estimate_field.as_ref().map(serde_json::to_string),
+            ),
+            WorkspaceSettingsUpdate::WorkingHours(working_hours) => (
+                WORKING_HOURS_FIELD.to_string(),
+                working_hours.as_ref().map(serde_json::to_string),
+            ),
+            WorkspaceSettingsUpdate::Calendar(calendar) => (
+                CALENDAR_FIELD.to_string(),
+                calendar.as_ref().map(serde_json::to_string),
+            ),
+        };
+
+        let result = match value {
+            Some(Ok(value)) => {
+                self.redis_pool
+                    .hset::<(), _, _>(key(workspace_id), (field, value))
+                    .await
+            }
+            Some(Err(e)) => return Err(WorkspaceSettingsServiceError::ParseError(Box::new(e))),
+            None => {
+                self.redis_pool
+                    .hdel::<(), _, _>(key(workspace_id), field)
+                    .await
+            }
+        };
+
+        result.map_err(|e| WorkspaceSettingsServiceError::StoreError(Box::new(e)))
+    }
+}
+
+fn key(workspace_id: &str) -> String {
+    format!("{KEY_PREFIX}:{workspace_id}")
+}
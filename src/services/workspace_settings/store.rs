@@ -0,0 +1,38 @@
+use crate::services::workspace_settings::{
+    EstimateField, StatusCategory, WorkCalendar, WorkingHours, WorkspaceSettings,
+    WorkspaceSettingsServiceError,
+};
+use async_trait::async_trait;
+
+/// Where each workspace's [`WorkspaceSettings`] are kept.
+/// [`WorkspaceSettingsService`] keeps them in Redis and
+/// [`InMemoryWorkspaceSettingsStore`] in memory.
+///
+/// [`WorkspaceSettingsService`]: crate::services::workspace_settings::WorkspaceSettingsService
+/// [`InMemoryWorkspaceSettingsStore`]: crate::services::workspace_settings::InMemoryWorkspaceSettingsStore
+#[async_trait]
+pub trait WorkspaceSettingsStore: Send + Sync {
+    /// Returns the workspace's settings, or the defaults if none were saved.
+    async fn get(
+        &self,
+        workspace_id: &str,
+    ) -> Result<WorkspaceSettings, WorkspaceSettingsServiceError>;
+
+    /// Applies `update` on its own, so that people changing other settings of
+    /// the workspace at the same time don't overwrite it.
+    async fn update(
+        &self,
+        workspace_id: &str,
+        update: WorkspaceSettingsUpdate,
+    ) -> Result<(), WorkspaceSettingsServiceError>;
+}
+
+/// A change to one of a workspace's settings. `None` goes back to the default.
+#[derive(Debug, Clone)]
+pub enum WorkspaceSettingsUpdate {
+    /// Keyed by status name, in any case.
+    StatusCategory(String, Option<StatusCategory>),
+    EstimateField(Option<EstimateField>),
+    WorkingHours(Option<WorkingHours>),
+    Calendar(Option<WorkCalendar>),
+}